@@ -11,4 +11,6 @@ pub enum Error {
     TokenNotFound = 5,
     AlreadyExists = 6,
     InvalidState = 7,
+    NoPendingAdmin = 8,
+    ProposalExpired = 9,
 }
@@ -1,4 +1,4 @@
-use soroban_sdk::{symbol_short, Address, Env, String, Symbol};
+use soroban_sdk::{symbol_short, Address, BytesN, Env, String, Symbol};
 
 pub fn emit_deposit_event(env: &Env, token_id: u32, depositor: &Address, project_id: &String) {
     #[allow(deprecated)]
@@ -8,11 +8,16 @@ pub fn emit_deposit_event(env: &Env, token_id: u32, depositor: &Address, project
     );
 }
 
-pub fn emit_withdraw_event(env: &Env, token_id: u32, target_token_id: u32, governance: &Address) {
+pub fn emit_withdraw_event(
+    env: &Env,
+    token_id: u32,
+    replacement_token_id: u32,
+    target_token_id: u32,
+) {
     #[allow(deprecated)]
     env.events().publish(
         (symbol_short!("withdraw"),),
-        (token_id, target_token_id, governance),
+        (token_id, replacement_token_id, target_token_id),
     );
 }
 
@@ -22,6 +27,37 @@ pub fn emit_auto_deposit_event(env: &Env, token_id: u32, project_id: &String) {
         .publish((symbol_short!("auto_dep"),), (token_id, project_id));
 }
 
+pub fn emit_admin_proposed_event(
+    env: &Env,
+    current_admin: &Address,
+    proposed_admin: &Address,
+    expiration_ledger: u32,
+) {
+    #[allow(deprecated)]
+    env.events().publish(
+        (symbol_short!("adm_prop"),),
+        (current_admin, proposed_admin, expiration_ledger),
+    );
+}
+
+pub fn emit_admin_transferred_event(env: &Env, previous_admin: &Address, new_admin: &Address) {
+    #[allow(deprecated)]
+    env.events()
+        .publish((symbol_short!("adm_xfer"),), (previous_admin, new_admin));
+}
+
+pub fn emit_upgrade_event(env: &Env, new_wasm_hash: &BytesN<32>) {
+    #[allow(deprecated)]
+    env.events()
+        .publish((symbol_short!("upgrade"),), new_wasm_hash.clone());
+}
+
+pub fn emit_migrate_event(env: &Env, from_version: u32, to_version: u32) {
+    #[allow(deprecated)]
+    env.events()
+        .publish((symbol_short!("migrate"),), (from_version, to_version));
+}
+
 #[allow(dead_code)]
 pub fn emit_config_update_event(env: &Env, param_name: &Symbol, new_value: i64) {
     #[allow(deprecated)]
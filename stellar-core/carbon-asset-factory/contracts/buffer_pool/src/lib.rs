@@ -8,7 +8,7 @@ mod test;
 
 use errors::Error;
 use events::*;
-use soroban_sdk::{contract, contractimpl, Address, Env, String};
+use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, String};
 use storage::*;
 
 #[contract]
@@ -38,10 +38,113 @@ impl BufferPoolContract {
         set_carbon_asset_contract(&env, &carbon_asset_contract);
         set_replenishment_percentage(&env, initial_percentage);
         set_total_value_locked(&env, 0);
+        set_data_version(&env, DATA_VERSION);
 
         Ok(())
     }
 
+    /// Propose a new admin. The handover only takes effect once `new_admin`
+    /// calls `accept_admin` on or before `expiration_ledger`.
+    pub fn propose_admin(
+        env: Env,
+        admin_caller: Address,
+        new_admin: Address,
+        expiration_ledger: u32,
+    ) -> Result<(), Error> {
+        admin_caller.require_auth();
+
+        let admin = get_admin(&env);
+
+        if admin_caller != admin {
+            return Err(Error::Unauthorized);
+        }
+
+        if expiration_ledger < env.ledger().sequence() {
+            return Err(Error::ProposalExpired);
+        }
+
+        set_pending_admin(
+            &env,
+            &PendingAdmin {
+                new_admin: new_admin.clone(),
+                expiration_ledger,
+            },
+        );
+
+        emit_admin_proposed_event(&env, &admin, &new_admin, expiration_ledger);
+
+        Ok(())
+    }
+
+    /// Complete a pending admin handover. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        new_admin.require_auth();
+
+        let pending = get_pending_admin(&env).ok_or(Error::NoPendingAdmin)?;
+
+        if pending.new_admin != new_admin {
+            return Err(Error::Unauthorized);
+        }
+
+        if env.ledger().sequence() > pending.expiration_ledger {
+            return Err(Error::ProposalExpired);
+        }
+
+        let previous_admin = get_admin(&env);
+        set_admin(&env, &new_admin);
+        remove_pending_admin(&env);
+
+        emit_admin_transferred_event(&env, &previous_admin, &new_admin);
+
+        Ok(())
+    }
+
+    /// Replace the contract WASM in place. Storage and the contract address are
+    /// kept, so CarbonAsset does not need to be re-pointed. Admin only.
+    pub fn upgrade(
+        env: Env,
+        admin_caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        admin_caller.require_auth();
+
+        let admin = get_admin(&env);
+
+        if admin_caller != admin {
+            return Err(Error::Unauthorized);
+        }
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        emit_upgrade_event(&env, &new_wasm_hash);
+
+        Ok(())
+    }
+
+    /// Bring stored data up to the current layout version after an upgrade.
+    /// Returns the resulting version; a no-op when already current.
+    pub fn migrate(env: Env, admin_caller: Address) -> Result<u32, Error> {
+        admin_caller.require_auth();
+
+        let admin = get_admin(&env);
+
+        if admin_caller != admin {
+            return Err(Error::Unauthorized);
+        }
+
+        let from_version = get_data_version(&env);
+        if from_version >= DATA_VERSION {
+            return Ok(from_version);
+        }
+
+        set_data_version(&env, DATA_VERSION);
+
+        emit_migrate_event(&env, from_version, DATA_VERSION);
+
+        Ok(DATA_VERSION)
+    }
+
     /// Manually deposit a carbon credit token into the pool.
     /// Only admin or carbon_asset_contract can call this.
     pub fn deposit(
@@ -80,33 +183,53 @@ impl BufferPoolContract {
         Ok(())
     }
 
-    /// Governance withdraws a credit from pool to replace an invalidated token.
+    /// Record a pool credit handed out to replace an invalidated token.
+    /// Only carbon_asset_contract can call this, as part of its reversal flow
+    /// after it has moved `replacement_token_id` to the affected holder. The
+    /// custody record is closed when the whole of `token_id` was used; a
+    /// partial use leaves the remainder in custody.
     pub fn withdraw_to_replace(
         env: Env,
-        governance_caller: Address,
+        carbon_contract_caller: Address,
         token_id: u32,
+        replacement_token_id: u32,
         target_invalidated_token: u32,
     ) -> Result<(), Error> {
-        let governance = get_governance(&env);
+        let carbon_contract = get_carbon_asset_contract(&env);
 
-        if governance_caller != governance {
+        if carbon_contract_caller != carbon_contract {
             return Err(Error::Unauthorized);
         }
 
-        governance_caller.require_auth();
+        carbon_contract_caller.require_auth();
 
         if !has_custody_record(&env, token_id) {
             return Err(Error::TokenNotFound);
         }
 
-        env.storage()
-            .persistent()
-            .remove(&(storage::CUSTODY, token_id));
+        if replacement_token_id == token_id {
+            remove_custody_record(&env, token_id);
 
-        let tvl = get_total_value_locked(&env);
-        set_total_value_locked(&env, tvl - 1);
+            let tvl = get_total_value_locked(&env);
+            set_total_value_locked(&env, tvl - 1);
+        }
 
-        emit_withdraw_event(&env, token_id, target_invalidated_token, &governance_caller);
+        set_replacement_record(
+            &env,
+            &ReplacementRecord {
+                buffer_token_id: token_id,
+                replacement_token_id,
+                invalidated_token_id: target_invalidated_token,
+                replaced_at: env.ledger().timestamp(),
+            },
+        );
+
+        emit_withdraw_event(
+            &env,
+            token_id,
+            replacement_token_id,
+            target_invalidated_token,
+        );
 
         Ok(())
     }
@@ -189,6 +312,22 @@ impl BufferPoolContract {
         Ok(())
     }
 
+    pub fn get_data_version(env: Env) -> u32 {
+        get_data_version(&env)
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        get_admin(&env)
+    }
+
+    pub fn get_pending_admin(env: Env) -> Option<PendingAdmin> {
+        get_pending_admin(&env)
+    }
+
+    pub fn get_replenishment_rate(env: Env) -> i64 {
+        get_replenishment_percentage(&env)
+    }
+
     pub fn get_total_value_locked(env: Env) -> i128 {
         get_total_value_locked(&env)
     }
@@ -200,4 +339,11 @@ impl BufferPoolContract {
     pub fn is_token_in_pool(env: Env, token_id: u32) -> bool {
         has_custody_record(&env, token_id)
     }
+
+    pub fn get_replacement_record(
+        env: Env,
+        replacement_token_id: u32,
+    ) -> Option<ReplacementRecord> {
+        get_replacement_record(&env, replacement_token_id)
+    }
 }
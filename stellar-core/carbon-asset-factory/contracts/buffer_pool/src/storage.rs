@@ -9,12 +9,39 @@ pub struct CustodyRecord {
     pub project_id: String,
 }
 
+/// Link between a pool credit handed out in a reversal and the invalidated
+/// token it replaces. `buffer_token_id` is the pool token it came from; it
+/// differs from `replacement_token_id` when only part of it was needed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReplacementRecord {
+    pub buffer_token_id: u32,
+    pub replacement_token_id: u32,
+    pub invalidated_token_id: u32,
+    pub replaced_at: u64,
+}
+
+/// Admin handover waiting for the proposed address to accept it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingAdmin {
+    pub new_admin: Address,
+    pub expiration_ledger: u32,
+}
+
 pub const ADMIN: Symbol = symbol_short!("admin");
+pub const PENDING_ADMIN: Symbol = symbol_short!("pend_adm");
 pub const GOVERNANCE: Symbol = symbol_short!("gov");
 pub const CARBON_CONTRACT: Symbol = symbol_short!("carbon");
 pub const REPLENISH_PCT: Symbol = symbol_short!("rep_pct");
 pub const TVL: Symbol = symbol_short!("tvl");
 pub const CUSTODY: Symbol = symbol_short!("custody");
+pub const REPLACEMENT: Symbol = symbol_short!("replace");
+pub const DATA_VER: Symbol = symbol_short!("data_ver");
+
+/// Storage layout version written by this build. Bump it together with a
+/// migration step in `migrate` whenever the layout changes.
+pub const DATA_VERSION: u32 = 1;
 
 pub fn get_admin(env: &Env) -> Address {
     env.storage().instance().get(&ADMIN).unwrap()
@@ -24,6 +51,18 @@ pub fn set_admin(env: &Env, admin: &Address) {
     env.storage().instance().set(&ADMIN, admin);
 }
 
+pub fn get_pending_admin(env: &Env) -> Option<PendingAdmin> {
+    env.storage().instance().get(&PENDING_ADMIN)
+}
+
+pub fn set_pending_admin(env: &Env, pending: &PendingAdmin) {
+    env.storage().instance().set(&PENDING_ADMIN, pending);
+}
+
+pub fn remove_pending_admin(env: &Env) {
+    env.storage().instance().remove(&PENDING_ADMIN);
+}
+
 pub fn get_governance(env: &Env) -> Address {
     env.storage().instance().get(&GOVERNANCE).unwrap()
 }
@@ -56,6 +95,14 @@ pub fn set_total_value_locked(env: &Env, tvl: i128) {
     env.storage().instance().set(&TVL, &tvl);
 }
 
+pub fn get_data_version(env: &Env) -> u32 {
+    env.storage().instance().get(&DATA_VER).unwrap_or(0)
+}
+
+pub fn set_data_version(env: &Env, version: u32) {
+    env.storage().instance().set(&DATA_VER, &version);
+}
+
 pub fn get_custody_record(env: &Env, token_id: u32) -> Option<CustodyRecord> {
     env.storage().persistent().get(&(CUSTODY, token_id))
 }
@@ -67,3 +114,19 @@ pub fn set_custody_record(env: &Env, token_id: u32, record: &CustodyRecord) {
 pub fn has_custody_record(env: &Env, token_id: u32) -> bool {
     env.storage().persistent().has(&(CUSTODY, token_id))
 }
+
+pub fn remove_custody_record(env: &Env, token_id: u32) {
+    env.storage().persistent().remove(&(CUSTODY, token_id));
+}
+
+pub fn get_replacement_record(env: &Env, replacement_token_id: u32) -> Option<ReplacementRecord> {
+    env.storage()
+        .persistent()
+        .get(&(REPLACEMENT, replacement_token_id))
+}
+
+pub fn set_replacement_record(env: &Env, record: &ReplacementRecord) {
+    env.storage()
+        .persistent()
+        .set(&(REPLACEMENT, record.replacement_token_id), record);
+}
@@ -1,7 +1,9 @@
 #![cfg(test)]
 
+use crate::errors::Error;
 use crate::{BufferPoolContract, BufferPoolContractClient};
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{Address, BytesN, Env, String};
 
 fn setup_test_env<'a>() -> (Env, Address, Address, Address, BufferPoolContractClient<'a>) {
     let env = Env::default();
@@ -66,18 +68,30 @@ fn test_deposit_duplicate_fails() {
 }
 
 #[test]
-fn test_withdraw_by_governance() {
+fn test_withdraw_to_replace() {
     let (env, admin, governance, carbon_contract, client) = setup_test_env();
 
     client.initialize(&admin, &governance, &carbon_contract, &500);
 
     let project_id = String::from_str(&env, "PROJECT-001");
     client.deposit(&admin, &1, &project_id);
+    client.deposit(&admin, &2, &project_id);
 
-    client.withdraw_to_replace(&governance, &1, &999);
+    let result = client.try_withdraw_to_replace(&governance, &1, &1, &999);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
 
-    let tvl = client.get_total_value_locked();
-    assert_eq!(tvl, 0);
+    // Part of token 2 was split off as token 3; token 2 stays in custody.
+    client.withdraw_to_replace(&carbon_contract, &2, &3, &998);
+    assert!(client.is_token_in_pool(&2));
+    assert_eq!(client.get_total_value_locked(), 2);
+
+    client.withdraw_to_replace(&carbon_contract, &1, &1, &999);
+    assert!(!client.is_token_in_pool(&1));
+    assert_eq!(client.get_total_value_locked(), 1);
+
+    let record = client.get_replacement_record(&3).unwrap();
+    assert_eq!(record.buffer_token_id, 2);
+    assert_eq!(record.invalidated_token_id, 998);
 }
 
 #[test]
@@ -110,7 +124,7 @@ fn test_withdraw_nonexistent_token() {
 
     client.initialize(&admin, &governance, &carbon_contract, &500);
 
-    let result = client.try_withdraw_to_replace(&governance, &999, &1);
+    let result = client.try_withdraw_to_replace(&carbon_contract, &999, &999, &1);
     assert!(result.is_err());
 }
 
@@ -139,3 +153,45 @@ fn test_set_replenishment_rate() {
 
     client.set_replenishment_rate(&governance, &1000);
 }
+
+#[test]
+fn test_upgrade_and_migrate_are_admin_only() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+    assert_eq!(client.get_data_version(), 1);
+
+    let result = client.try_upgrade(&governance, &BytesN::from_array(&env, &[0u8; 32]));
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    let result = client.try_migrate(&governance);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    assert_eq!(client.migrate(&admin), 1);
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+
+    let new_admin = Address::generate(&env);
+    let expiration = env.ledger().sequence() + 10;
+    client.propose_admin(&admin, &new_admin, &expiration);
+    assert_eq!(client.get_admin(), admin);
+
+    let result = client.try_accept_admin(&governance);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    env.ledger()
+        .with_mut(|li| li.sequence_number = expiration + 1);
+    let result = client.try_accept_admin(&new_admin);
+    assert_eq!(result, Err(Ok(Error::ProposalExpired)));
+
+    let expiration = env.ledger().sequence() + 10;
+    client.propose_admin(&admin, &new_admin, &expiration);
+    client.accept_admin(&new_admin);
+    assert_eq!(client.get_admin(), new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+}
@@ -42,8 +42,8 @@ fn test_full_lifecycle() {
     assert!(client.is_token_in_pool(&40));
     assert!(!client.is_token_in_pool(&21));
 
-    // Governance withdraws for replacement
-    client.withdraw_to_replace(&governance, &20, &1000);
+    // CarbonAsset hands token 20 out during a reversal
+    client.withdraw_to_replace(&carbon_contract, &20, &20, &1000);
 
     let tvl = client.get_total_value_locked();
     assert_eq!(tvl, 4);
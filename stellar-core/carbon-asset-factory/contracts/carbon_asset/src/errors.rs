@@ -15,4 +15,33 @@ pub enum ContractError {
     RegulatoryNotSet = 10,
     HostJurisdictionNotSet = 11,
     TokenAlreadyBurned = 12,
+    InvalidQuantity = 13,
+    MergeMismatch = 14,
+    InvalidSerialRange = 15,
+    SerialRangeOverlap = 16,
+    BatchTooLarge = 17,
+    BatchNotFound = 18,
+    InvalidMetadata = 19,
+    MethodologyNotValid = 20,
+    ProjectNotRegistered = 21,
+    BufferPoolNotSet = 22,
+    ApprovalRequired = 23,
+    ApprovalNotRequired = 24,
+    ApprovalNotFound = 25,
+    ApprovalNotGranted = 26,
+    NoPendingAdmin = 27,
+    AdminProposalExpired = 28,
+    ContractPaused = 29,
+    IssuanceFrozen = 30,
+    InsufficientBuffer = 31,
+    TokenListed = 32,
+    NotListed = 33,
+    InvalidPrice = 34,
+    NotApproved = 35,
+    OracleNotRegistered = 36,
+    TooManyOracles = 37,
+    InvalidWeight = 38,
+    StaleScore = 39,
+    MigrationPending = 40,
+    ScoreOverflow = 41,
 }
@@ -1,6 +1,9 @@
-use soroban_sdk::{contractevent, Address, String};
+use soroban_sdk::{contractevent, Address, BytesN, String};
 
-use crate::types::AssetStatus;
+use crate::types::{
+    AssetStatus, CorrespondingAdjustment, CreditType, IssuanceState, PauseScope, PickingPolicy,
+    Role, ScoreAggregation,
+};
 
 #[contractevent]
 pub struct MintEvent {
@@ -9,6 +12,16 @@ pub struct MintEvent {
     pub project_id: String,
     pub vintage_year: u64,
     pub methodology_id: u32,
+    pub quantity: i128,
+    pub registry: String,
+    pub registry_serial: String,
+    pub issuance_date: u64,
+    pub country: String,
+    pub project_type: String,
+    pub credit_type: CreditType,
+    pub sdg_flags: u32,
+    pub corresponding_adjustment: CorrespondingAdjustment,
+    pub verification_uri: String,
 }
 
 #[contractevent]
@@ -18,6 +31,49 @@ pub struct TransferEvent {
     pub to: Address,
 }
 
+#[contractevent]
+pub struct BatchMintEvent {
+    pub batch_id: u32,
+    pub owner: Address,
+    pub project_id: String,
+    pub vintage_year: u64,
+    pub methodology_id: u32,
+    pub serial_prefix: String,
+    pub serial_start: u64,
+    pub serial_end: u64,
+    pub token_id: u32,
+    pub buffer_token_id: Option<u32>,
+    pub total_quantity: i128,
+    pub issued_by: Address,
+}
+
+// `token_id` is the credit the share was split from, if any; whole buffer
+// serials of a batch come from `batch_id` alone.
+#[contractevent]
+pub struct BufferContributionEvent {
+    pub token_id: Option<u32>,
+    pub batch_id: Option<u32>,
+    pub buffer_token_id: u32,
+    pub buffer_pool: Address,
+    pub quantity: i128,
+}
+
+#[contractevent]
+pub struct SplitEvent {
+    pub token_id: u32,
+    pub new_token_id: u32,
+    pub owner: Address,
+    pub quantity: i128,
+}
+
+#[contractevent]
+pub struct MergeEvent {
+    pub token_id: u32,
+    pub merged_token_id: u32,
+    pub owner: Address,
+    pub quantity: i128,
+}
+
 #[contractevent]
 pub struct StatusChangeEvent {
     pub token_id: u32,
@@ -26,6 +82,12 @@ pub struct StatusChangeEvent {
     pub changed_by: Address,
 }
 
+#[contractevent]
+pub struct PickingPolicyEvent {
+    pub owner: Address,
+    pub policy: PickingPolicy,
+}
+
 #[contractevent]
 pub struct QualityScoreUpdatedEvent {
     pub token_id: u32,
@@ -55,3 +117,150 @@ pub struct Sep41BurnEvent {
     pub from: Address,
     pub amount: i128,
 }
+
+#[contractevent]
+pub struct ApprovalRequestedEvent {
+    pub approval_key: BytesN<32>,
+    pub token_id: u32,
+    pub from: Address,
+    pub to: Address,
+    pub authority: Option<Address>,
+}
+
+#[contractevent]
+pub struct ApprovalConsumedEvent {
+    pub approval_key: BytesN<32>,
+    pub token_id: u32,
+}
+
+#[contractevent]
+pub struct UpgradeEvent {
+    pub new_wasm_hash: BytesN<32>,
+}
+
+#[contractevent]
+pub struct MigrateEvent {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+#[contractevent]
+pub struct RoleGrantedEvent {
+    pub role: Role,
+    pub account: Address,
+    pub granted_by: Address,
+}
+
+#[contractevent]
+pub struct RoleRevokedEvent {
+    pub role: Role,
+    pub account: Address,
+    pub revoked_by: Address,
+}
+
+#[contractevent]
+pub struct AdminProposedEvent {
+    pub current_admin: Address,
+    pub proposed_admin: Address,
+    pub expiration_ledger: u32,
+}
+
+#[contractevent]
+pub struct AdminTransferredEvent {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent]
+pub struct PausedEvent {
+    pub scope: PauseScope,
+    pub paused_by: Address,
+}
+
+#[contractevent]
+pub struct UnpausedEvent {
+    pub scope: PauseScope,
+    pub unpaused_by: Address,
+}
+
+#[contractevent]
+pub struct ProjectStateEvent {
+    pub project_id: String,
+    pub state: IssuanceState,
+    pub changed_by: Address,
+}
+
+#[contractevent]
+pub struct VintageStateEvent {
+    pub project_id: String,
+    pub vintage_year: u64,
+    pub state: IssuanceState,
+    pub changed_by: Address,
+}
+
+#[contractevent]
+pub struct ReversalEvent {
+    pub token_id: u32,
+    pub holder: Address,
+    pub replacement_token_id: u32,
+    pub buffer_token_id: u32,
+    pub quantity: i128,
+}
+
+#[contractevent]
+pub struct ListedEvent {
+    pub token_id: u32,
+    pub seller: Address,
+    pub price: i128,
+    pub currency: Address,
+}
+
+#[contractevent]
+pub struct DelistedEvent {
+    pub token_id: u32,
+    pub seller: Address,
+}
+
+#[contractevent]
+pub struct TokenApprovalEvent {
+    pub token_id: u32,
+    pub owner: Address,
+    pub spender: Address,
+    pub approved: bool,
+}
+
+#[contractevent]
+pub struct ApprovalForAllEvent {
+    pub owner: Address,
+    pub operator: Address,
+    pub approved: bool,
+}
+
+#[contractevent]
+pub struct OracleRegisteredEvent {
+    pub oracle: Address,
+    pub weight: u32,
+    pub registered_by: Address,
+}
+
+#[contractevent]
+pub struct OracleRemovedEvent {
+    pub oracle: Address,
+    pub removed_by: Address,
+}
+
+#[contractevent]
+pub struct ScoreConfigEvent {
+    pub method: ScoreAggregation,
+    pub max_age: u64,
+    pub set_by: Address,
+}
+
+#[contractevent]
+pub struct ScoreSubmittedEvent {
+    pub token_id: u32,
+    pub oracle: Address,
+    pub score: i128,
+    pub observed_at: u64,
+    pub evidence_hash: BytesN<32>,
+}
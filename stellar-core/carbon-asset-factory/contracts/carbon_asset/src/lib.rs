@@ -1,21 +1,36 @@
 #![no_std]
 
-mod errors;
+pub mod errors;
 mod events;
 mod storage;
-mod types;
+pub mod types;
 #[cfg(test)]
 mod test;
 
-use soroban_sdk::{contract, contractimpl, Address, Env, IntoVal, String, Symbol, Vec};
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{
+    contract, contractimpl, Address, BytesN, Env, IntoVal, Map, String, Symbol, Val, Vec,
+};
 
 use crate::errors::ContractError;
 use crate::events::{
-    ApproveEvent, MintEvent, QualityScoreUpdatedEvent, Sep41BurnEvent, Sep41TransferEvent,
-    StatusChangeEvent, TransferEvent,
+    AdminProposedEvent, AdminTransferredEvent, ApprovalConsumedEvent, ApprovalForAllEvent,
+    ApprovalRequestedEvent, ApproveEvent, BatchMintEvent, BufferContributionEvent, DelistedEvent,
+    ListedEvent, MergeEvent, MigrateEvent, MintEvent, OracleRegisteredEvent, OracleRemovedEvent,
+    PausedEvent, PickingPolicyEvent, ProjectStateEvent, QualityScoreUpdatedEvent, ReversalEvent,
+    RoleGrantedEvent, RoleRevokedEvent, ScoreConfigEvent, ScoreSubmittedEvent, Sep41BurnEvent,
+    Sep41TransferEvent, SplitEvent, StatusChangeEvent, TokenApprovalEvent, TransferEvent,
+    UnpausedEvent, UpgradeEvent, VintageStateEvent,
+};
+use crate::storage::{DataKey, ScoreKey};
+use crate::types::{
+    AllowanceData, AssetStatus, CarbonAssetMetadata, CarbonAssetMetadataV0,
+    CorrespondingAdjustment, CreditContext, CreditType, IssuanceBatch, IssuanceState, Listing,
+    OperationType, PauseScope, PendingAdmin, PendingTransfer, PickingPolicy, Role,
+    ScoreAggregation, ScoreConfig, ScoreSubmission, SerialRange, TokenFilter, TokenPage,
+    TokenSerial, ValidationResult, ALL_ROLES, DEFAULT_SCORE_MAX_AGE, MAX_ORACLES,
+    MAX_PAGE_SIZE, MAX_SDG, QUANTITY_DECIMALS, UNITS_PER_TONNE,
 };
-use crate::storage::DataKey;
-use crate::types::{AllowanceData, AssetStatus, CarbonAssetMetadata, OperationType, ValidationResult};
 
 // ========================================================================
 // Contract
@@ -47,7 +62,9 @@ impl CarbonAsset {
         env.storage().instance().set(&DataKey::Admin, &admin);
         env.storage().instance().set(&DataKey::Name, &name);
         env.storage().instance().set(&DataKey::Symbol, &symbol);
-        env.storage().instance().set(&DataKey::Decimals, &0u32);
+        env.storage()
+            .instance()
+            .set(&DataKey::Decimals, &QUANTITY_DECIMALS);
         env.storage()
             .instance()
             .set(&DataKey::RetirementTracker, &retirement_tracker);
@@ -55,52 +72,189 @@ impl CarbonAsset {
             .instance()
             .set(&DataKey::HostJurisdiction, &host_jurisdiction);
         env.storage().instance().set(&DataKey::NextTokenId, &1u32);
+        env.storage()
+            .instance()
+            .set(&DataKey::DataVersion, &storage::DATA_VERSION);
+        Self::grant_all_roles(&env, &admin);
+        storage::extend_instance_ttl(&env);
 
         Ok(())
     }
 
     // ====================================================================
-    // Minting
+    // Upgrades
     // ====================================================================
 
-    pub fn mint(
+    // Swaps the contract code in place, keeping the address and storage so
+    // other contracts don't have to be re-pointed. Call `migrate` afterwards
+    // when the new code changes the storage layout.
+    pub fn upgrade(
         env: Env,
         caller: Address,
-        owner: Address,
-        metadata: CarbonAssetMetadata,
-    ) -> Result<u32, ContractError> {
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), ContractError> {
         caller.require_auth();
         let admin = Self::get_admin(env.clone())?;
         if caller != admin {
             return Err(ContractError::NotAuthorized);
         }
 
-        let token_id: u32 = env
-            .storage()
-            .instance()
-            .get(&DataKey::NextTokenId)
-            .ok_or(ContractError::NotInitialized)?;
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        UpgradeEvent { new_wasm_hash }.publish(&env);
+
+        Ok(())
+    }
+
+    // Brings stored data up to `DATA_VERSION`. Safe to call repeatedly; it is
+    // a no-op once the stored version is current.
+    pub fn migrate(env: Env, caller: Address) -> Result<u32, ContractError> {
+        caller.require_auth();
+        let admin = Self::get_admin(env.clone())?;
+        if caller != admin {
+            return Err(ContractError::NotAuthorized);
+        }
+
+        let from_version = Self::get_data_version(env.clone());
+        if from_version >= storage::DATA_VERSION {
+            return Ok(from_version);
+        }
+
+        // v1: quantities are tracked in kilograms. Baseline tokens were whole
+        // tonnes with no decimals; each one gets its quantity, balance and
+        // full metadata record during the indexing pass below. Allowances
+        // were counted in tokens and cannot be enumerated, so they are left
+        // for holders to re-approve.
+        if from_version < 1 {
+            env.storage()
+                .instance()
+                .set(&DataKey::Decimals, &QUANTITY_DECIMALS);
+            env.storage().instance().set(&DataKey::DataVersion, &1u32);
+        }
+
+        // v2: privileged calls check roles instead of the admin address, so
+        // the existing admin keeps its powers by holding every role.
+        if from_version < 2 {
+            Self::grant_all_roles(&env, &admin);
+            env.storage().instance().set(&DataKey::DataVersion, &2u32);
+        }
+
+        // v3: token indexes hold one entry per token and supply is tracked.
+        // Existing tokens are indexed `MIGRATION_BATCH_SIZE` at a time, so
+        // this step can take several calls; until it is done they return 2.
+        if !Self::migrate_indexes(&env)? {
+            storage::extend_instance_ttl(&env);
+            return Ok(2);
+        }
 
         env.storage()
             .instance()
-            .set(&DataKey::NextTokenId, &(token_id + 1));
+            .set(&DataKey::DataVersion, &storage::DATA_VERSION);
+        storage::extend_instance_ttl(&env);
 
+        MigrateEvent {
+            from_version,
+            to_version: storage::DATA_VERSION,
+        }
+        .publish(&env);
+
+        Ok(storage::DATA_VERSION)
+    }
+
+    // Deployments that predate versioning have no stored value and report 0.
+    pub fn get_data_version(env: Env) -> u32 {
         env.storage()
-            .persistent()
-            .set(&DataKey::Owner(token_id), &owner);
-        Self::add_token_to_owner(env.clone(), owner.clone(), token_id);
-        env.storage()
-            .persistent()
-            .set(&DataKey::Metadata(token_id), &metadata);
-        env.storage()
-            .persistent()
-            .set(&DataKey::Status(token_id), &AssetStatus::Issued);
-        env.storage()
-            .persistent()
-            .set(&DataKey::QualityScore(token_id), &0i128);
-        env.storage()
-            .persistent()
-            .set(&DataKey::Burned(token_id), &false);
+            .instance()
+            .get(&DataKey::DataVersion)
+            .unwrap_or(0)
+    }
+
+    // ====================================================================
+    // TTL Maintenance
+    // ====================================================================
+
+    // Permissionless so keepers can keep long-held credits from being
+    // archived. Entries that were already archived have to be restored with a
+    // footprint restore before this can bump them.
+    pub fn extend_token_ttl(env: Env, token_ids: Vec<u32>) {
+        for i in 0..token_ids.len() {
+            let token_id = token_ids.get(i).unwrap();
+            storage::extend_persistent_ttl(&env, &DataKey::Metadata(token_id));
+            storage::extend_persistent_ttl(&env, &DataKey::Status(token_id));
+            storage::extend_persistent_ttl(&env, &DataKey::QualityScore(token_id));
+            storage::extend_persistent_ttl(&env, &DataKey::Burned(token_id));
+            storage::extend_persistent_ttl(&env, &DataKey::Quantity(token_id));
+            storage::extend_persistent_ttl(&env, &DataKey::TokenIndex(token_id));
+            storage::extend_persistent_ttl(&env, &DataKey::TokenSerial(token_id));
+            storage::extend_persistent_ttl(&env, &DataKey::TokenApproval(token_id));
+            storage::extend_persistent_ttl(&env, &DataKey::Score(ScoreKey::Sources(token_id)));
+            storage::extend_persistent_ttl(
+                &env,
+                &DataKey::Score(ScoreKey::HistoryCount(token_id)),
+            );
+
+            let owner: Option<Address> = env.storage().persistent().get(&DataKey::Owner(token_id));
+            if let Some(owner) = owner {
+                storage::extend_persistent_ttl(&env, &DataKey::Owner(token_id));
+                storage::extend_persistent_ttl(&env, &DataKey::OwnerTokenCount(owner.clone()));
+                let index: Option<u32> = env
+                    .storage()
+                    .persistent()
+                    .get(&DataKey::TokenIndex(token_id));
+                if let Some(index) = index {
+                    storage::extend_persistent_ttl(
+                        &env,
+                        &DataKey::OwnerToken(owner.clone(), index),
+                    );
+                }
+                storage::extend_persistent_ttl(&env, &DataKey::Balance(owner));
+            }
+        }
+
+        storage::extend_instance_ttl(&env);
+    }
+
+    // ====================================================================
+    // Minting
+    // ====================================================================
+
+    // `quantity` is expressed in kilograms of CO2e (see `UNITS_PER_TONNE`).
+    pub fn mint(
+        env: Env,
+        caller: Address,
+        owner: Address,
+        metadata: CarbonAssetMetadata,
+        quantity: i128,
+    ) -> Result<u32, ContractError> {
+        Self::require_role(&env, &caller, Role::Issuer)?;
+        Self::require_not_paused(&env, PauseScope::Mint)?;
+        Self::require_migrated(&env)?;
+
+        if quantity <= 0 {
+            return Err(ContractError::InvalidQuantity);
+        }
+        Self::validate_metadata(env.clone(), &metadata)?;
+
+        let buffer_quantity = quantity
+            .checked_mul(Self::buffer_rate(env.clone()))
+            .ok_or(ContractError::InvalidQuantity)?
+            / 10_000;
+        let owner_quantity = quantity - buffer_quantity;
+        if owner_quantity <= 0 {
+            return Err(ContractError::InvalidQuantity);
+        }
+
+        let token_id = Self::create_token(
+            env.clone(),
+            owner.clone(),
+            &metadata,
+            owner_quantity,
+            AssetStatus::Issued,
+            0,
+            None,
+        )?;
+        Self::adjust_total(&env, DataKey::TotalSupply, owner_quantity);
 
         MintEvent {
             token_id,
@@ -108,6 +262,16 @@ impl CarbonAsset {
             project_id: metadata.project_id.clone(),
             vintage_year: metadata.vintage_year,
             methodology_id: metadata.methodology_id,
+            quantity: owner_quantity,
+            registry: metadata.registry.clone(),
+            registry_serial: metadata.registry_serial.clone(),
+            issuance_date: metadata.issuance_date,
+            country: metadata.country.clone(),
+            project_type: metadata.project_type.clone(),
+            credit_type: metadata.credit_type.clone(),
+            sdg_flags: metadata.sdg_flags,
+            corresponding_adjustment: metadata.corresponding_adjustment.clone(),
+            verification_uri: metadata.verification_uri.clone(),
         }
         .publish(&env);
 
@@ -119,16 +283,253 @@ impl CarbonAsset {
         }
         .publish(&env);
 
+        if buffer_quantity > 0 {
+            Self::deposit_to_buffer(
+                env,
+                Some(token_id),
+                None,
+                &metadata,
+                buffer_quantity,
+                None,
+            )?;
+        }
+
         Ok(token_id)
     }
 
+    // Issues a serial range as a single token carrying the whole range, plus
+    // one buffer pool token for the withheld share, so a batch costs the same
+    // whatever its size. Serials under a prefix must be issued in ascending
+    // order; a range starting at or below the last one issued overlaps it.
+    pub fn mint_batch(
+        env: Env,
+        caller: Address,
+        owner: Address,
+        metadata: CarbonAssetMetadata,
+        serial: SerialRange,
+        quantity_per_serial: i128,
+    ) -> Result<u32, ContractError> {
+        Self::require_role(&env, &caller, Role::Issuer)?;
+        Self::require_not_paused(&env, PauseScope::Mint)?;
+        Self::require_migrated(&env)?;
+
+        if quantity_per_serial <= 0 {
+            return Err(ContractError::InvalidQuantity);
+        }
+        Self::validate_metadata(env.clone(), &metadata)?;
+        let count = serial
+            .end
+            .checked_sub(serial.start)
+            .and_then(|span| span.checked_add(1))
+            .ok_or(ContractError::InvalidSerialRange)?;
+
+        let prefix_key = DataKey::SerialPrefixEnd(serial.prefix.clone());
+        if let Some(issued_end) = storage::read_persistent::<u64>(&env, &prefix_key) {
+            if serial.start <= issued_end {
+                return Err(ContractError::SerialRangeOverlap);
+            }
+        }
+
+        let batch_id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextBatchId)
+            .unwrap_or(1u32);
+        env.storage()
+            .instance()
+            .set(&DataKey::NextBatchId, &(batch_id + 1));
+
+        // The buffer share is rounded the same way as in `mint` and withheld
+        // from the end of the range. When it does not end on a whole serial,
+        // the owner's last serial is shared with the buffer token.
+        let batch_quantity = quantity_per_serial
+            .checked_mul(count as i128)
+            .ok_or(ContractError::InvalidQuantity)?;
+        let buffer_quantity = batch_quantity
+            .checked_mul(Self::buffer_rate(env.clone()))
+            .ok_or(ContractError::InvalidQuantity)?
+            / 10_000;
+        if buffer_quantity >= batch_quantity {
+            return Err(ContractError::InvalidQuantity);
+        }
+        let owner_quantity = batch_quantity - buffer_quantity;
+
+        Self::adjust_total(&env, DataKey::TotalSupply, owner_quantity);
+        let token_id = Self::create_token(
+            env.clone(),
+            owner.clone(),
+            &metadata,
+            owner_quantity,
+            AssetStatus::Issued,
+            0,
+            Some(TokenSerial {
+                batch_id,
+                start: serial.start,
+                end: serial.start + ((owner_quantity - 1) / quantity_per_serial) as u64,
+            }),
+        )?;
+        let buffer_token_id = if buffer_quantity > 0 {
+            Some(Self::deposit_to_buffer(
+                env.clone(),
+                Some(token_id),
+                Some(batch_id),
+                &metadata,
+                buffer_quantity,
+                Some(TokenSerial {
+                    batch_id,
+                    start: serial.start + (owner_quantity / quantity_per_serial) as u64,
+                    end: serial.end,
+                }),
+            )?)
+        } else {
+            None
+        };
+
+        let batch = IssuanceBatch {
+            batch_id,
+            serial: serial.clone(),
+            metadata: metadata.clone(),
+            token_id,
+            buffer_token_id,
+            quantity_per_serial,
+            issued_at: env.ledger().timestamp(),
+        };
+        storage::write_persistent(&env, &DataKey::Batch(batch_id), &batch);
+        storage::write_persistent(&env, &prefix_key, &serial.end);
+
+        BatchMintEvent {
+            batch_id,
+            owner,
+            project_id: metadata.project_id,
+            vintage_year: metadata.vintage_year,
+            methodology_id: metadata.methodology_id,
+            serial_prefix: serial.prefix,
+            serial_start: serial.start,
+            serial_end: serial.end,
+            token_id,
+            buffer_token_id,
+            total_quantity: batch_quantity,
+            issued_by: caller,
+        }
+        .publish(&env);
+
+        Ok(batch_id)
+    }
+
+    // ====================================================================
+    // Split / Merge
+    // ====================================================================
+
+    pub fn split(
+        env: Env,
+        owner: Address,
+        token_id: u32,
+        amount: i128,
+    ) -> Result<u32, ContractError> {
+        owner.require_auth();
+        Self::require_migrated(&env)?;
+
+        let current_owner = Self::owner_of(env.clone(), token_id)?;
+        if current_owner != owner {
+            return Err(ContractError::NotOwner);
+        }
+
+        let status = Self::get_status(env.clone(), token_id)?;
+        if status == AssetStatus::Invalidated {
+            return Err(ContractError::StatusFrozen);
+        }
+        if status == AssetStatus::Listed {
+            return Err(ContractError::TokenListed);
+        }
+
+        Self::split_internal(env, owner, token_id, amount)
+    }
+
+    pub fn merge(
+        env: Env,
+        owner: Address,
+        token_id: u32,
+        other_token_id: u32,
+    ) -> Result<(), ContractError> {
+        owner.require_auth();
+        Self::require_migrated(&env)?;
+
+        if token_id == other_token_id {
+            return Err(ContractError::MergeMismatch);
+        }
+
+        if Self::owner_of(env.clone(), token_id)? != owner
+            || Self::owner_of(env.clone(), other_token_id)? != owner
+        {
+            return Err(ContractError::NotOwner);
+        }
+
+        let status = Self::get_status(env.clone(), token_id)?;
+        if status == AssetStatus::Invalidated {
+            return Err(ContractError::StatusFrozen);
+        }
+        if status == AssetStatus::Listed {
+            return Err(ContractError::TokenListed);
+        }
+        if status != Self::get_status(env.clone(), other_token_id)?
+            || Self::get_metadata(env.clone(), token_id)?
+                != Self::get_metadata(env.clone(), other_token_id)?
+            || Self::get_quality_score(env.clone(), token_id)?
+                != Self::get_quality_score(env.clone(), other_token_id)?
+            || Self::get_token_serial(env.clone(), token_id)
+                != Self::get_token_serial(env.clone(), other_token_id)
+        {
+            return Err(ContractError::MergeMismatch);
+        }
+
+        let other_quantity = Self::quantity_of(env.clone(), other_token_id)?;
+        Self::remove_token_from_owner(env.clone(), owner.clone(), other_token_id)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Owner(other_token_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Metadata(other_token_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Status(other_token_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::QualityScore(other_token_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Burned(other_token_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Quantity(other_token_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::TokenSerial(other_token_id));
+        Self::clear_token_approval(&env, token_id);
+        Self::clear_token_approval(&env, other_token_id);
+
+        let quantity = Self::quantity_of(env.clone(), token_id)? + other_quantity;
+        storage::write_persistent(&env, &DataKey::Quantity(token_id), &quantity);
+        Self::adjust_balance(env.clone(), owner.clone(), other_quantity);
+
+        MergeEvent {
+            token_id,
+            merged_token_id: other_token_id,
+            owner,
+            quantity,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
     // ====================================================================
-    // SEP-41 Token Interface (count-based)
+    // SEP-41 Token Interface (quantity-based)
     // ====================================================================
 
     pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
         let key = DataKey::Allowance(from, spender);
-        if let Some(allowance) = env.storage().persistent().get::<DataKey, AllowanceData>(&key) {
+        if let Some(allowance) = storage::read_persistent::<AllowanceData>(&env, &key) {
             if allowance.live_until_ledger < env.ledger().sequence() {
                 0
             } else {
@@ -158,7 +559,7 @@ impl CarbonAsset {
             amount,
             live_until_ledger,
         };
-        env.storage().persistent().set(&key, &data);
+        storage::write_persistent(&env, &key, &data);
 
         ApproveEvent {
             from,
@@ -177,7 +578,7 @@ impl CarbonAsset {
 
     pub fn transfer(env: Env, from: Address, to: Address, amount: i128) -> Result<(), ContractError> {
         from.require_auth();
-        Self::transfer_amount_internal(env, from, to, amount)
+        Self::transfer_amount_internal(env, from, to, amount, None)
     }
 
     pub fn transfer_from(
@@ -191,9 +592,61 @@ impl CarbonAsset {
 
         let allowance = Self::spend_allowance(env.clone(), from.clone(), spender.clone(), amount)?;
         let key = DataKey::Allowance(from.clone(), spender);
-        env.storage().persistent().set(&key, &allowance);
+        storage::write_persistent(&env, &key, &allowance);
+
+        Self::transfer_amount_internal(env, from, to, amount, None)
+    }
+
+    // ====================================================================
+    // Filtered Transfers
+    // ====================================================================
+
+    pub fn transfer_with_filter(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128,
+        filter: TokenFilter,
+    ) -> Result<(), ContractError> {
+        from.require_auth();
+        Self::transfer_amount_internal(env, from, to, amount, Some(filter))
+    }
+
+    pub fn transfer_from_with_filter(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: i128,
+        filter: TokenFilter,
+    ) -> Result<(), ContractError> {
+        spender.require_auth();
+
+        let allowance = Self::spend_allowance(env.clone(), from.clone(), spender.clone(), amount)?;
+        let key = DataKey::Allowance(from.clone(), spender);
+        storage::write_persistent(&env, &key, &allowance);
+
+        Self::transfer_amount_internal(env, from, to, amount, Some(filter))
+    }
+
+    // Order in which a holder's tokens are picked for amount-based transfers.
+    pub fn set_picking_policy(
+        env: Env,
+        owner: Address,
+        policy: PickingPolicy,
+    ) -> Result<(), ContractError> {
+        owner.require_auth();
+
+        storage::write_persistent(&env, &DataKey::PickingPolicy(owner.clone()), &policy);
+
+        PickingPolicyEvent { owner, policy }.publish(&env);
+
+        Ok(())
+    }
 
-        Self::transfer_amount_internal(env, from, to, amount)
+    pub fn get_picking_policy(env: Env, owner: Address) -> PickingPolicy {
+        storage::read_persistent(&env, &DataKey::PickingPolicy(owner))
+            .unwrap_or(PickingPolicy::Fifo)
     }
 
     pub fn burn(env: Env, from: Address, amount: i128) -> Result<(), ContractError> {
@@ -219,62 +672,323 @@ impl CarbonAsset {
     }
 
     // ====================================================================
-    // Burning (Retirement)
+    // Per-token Approvals
     // ====================================================================
 
-    // C-01 extension: burn a specific token_id (used by RetirementTracker for 1:1 retirement).
-    pub fn burn_token(env: Env, token_id: u32, from: Address) -> Result<(), ContractError> {
-        let retirement_tracker = Self::get_retirement_tracker(env.clone())?;
-        retirement_tracker.require_auth();
-
+    // Lets `spender` move one specific credit with `transfer_token_from`, so
+    // an escrow receives exactly the token it was promised rather than
+    // whatever the amount-based picker selects. A token has at most one
+    // approved spender; the approval ends when the token is transferred,
+    // split, merged or retired.
+    pub fn approve_token(env: Env, token_id: u32, spender: Address) -> Result<(), ContractError> {
         let owner = Self::owner_of(env.clone(), token_id)?;
-        if owner != from {
-            return Err(ContractError::NotOwner);
-        }
+        owner.require_auth();
 
-        let burned: bool = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Burned(token_id))
-            .unwrap_or(false);
-        if burned {
+        if Self::is_burned(env.clone(), token_id)? {
             return Err(ContractError::TokenAlreadyBurned);
         }
 
-        let status = Self::get_status(env.clone(), token_id)?;
-        if status == AssetStatus::Invalidated {
-            return Err(ContractError::StatusFrozen);
-        }
-        if status != AssetStatus::Retired {
-            Self::set_status_internal(
-                env.clone(),
-                token_id,
-                AssetStatus::Retired,
-                retirement_tracker.clone(),
-            )?;
+        storage::write_persistent(&env, &DataKey::TokenApproval(token_id), &spender);
+
+        TokenApprovalEvent {
+            token_id,
+            owner,
+            spender,
+            approved: true,
         }
+        .publish(&env);
 
-        Self::remove_token_from_owner(env.clone(), from, token_id)?;
-        env.storage()
-            .persistent()
-            .set(&DataKey::Burned(token_id), &true);
+        Ok(())
+    }
+
+    pub fn revoke_token_approval(env: Env, token_id: u32) -> Result<(), ContractError> {
+        let owner = Self::owner_of(env.clone(), token_id)?;
+        owner.require_auth();
+
+        let spender =
+            Self::get_approved(env.clone(), token_id).ok_or(ContractError::NotApproved)?;
         env.storage()
             .persistent()
-            .remove(&DataKey::Owner(token_id));
+            .remove(&DataKey::TokenApproval(token_id));
+
+        TokenApprovalEvent {
+            token_id,
+            owner,
+            spender,
+            approved: false,
+        }
+        .publish(&env);
 
         Ok(())
     }
 
-    // ====================================================================
-    // Compliance Hook
-    // ====================================================================
-
-    pub fn before_transfer(
+    // An operator may move any of `owner`'s tokens, including ones received
+    // after the approval was given, until it is revoked.
+    pub fn set_approval_for_all(
         env: Env,
-        from: Address,
-        to: Address,
-        token_id: u32,
+        owner: Address,
+        operator: Address,
+        approved: bool,
+    ) -> Result<(), ContractError> {
+        owner.require_auth();
+
+        let key = DataKey::OperatorApproval(owner.clone(), operator.clone());
+        if approved {
+            storage::write_persistent(&env, &key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        ApprovalForAllEvent {
+            owner,
+            operator,
+            approved,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    pub fn get_approved(env: Env, token_id: u32) -> Option<Address> {
+        storage::read_persistent(&env, &DataKey::TokenApproval(token_id))
+    }
+
+    pub fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool {
+        storage::read_persistent(&env, &DataKey::OperatorApproval(owner, operator))
+            .unwrap_or(false)
+    }
+
+    // Moves `token_id` on behalf of `from`. `spender` must be the owner, the
+    // token's approved spender or one of the owner's operators.
+    pub fn transfer_token_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        token_id: u32,
+    ) -> Result<(), ContractError> {
+        spender.require_auth();
+
+        let owner = Self::owner_of(env.clone(), token_id)?;
+        if owner != from {
+            return Err(ContractError::NotOwner);
+        }
+        if spender != owner
+            && Self::get_approved(env.clone(), token_id) != Some(spender.clone())
+            && !Self::is_approved_for_all(env.clone(), owner, spender)
+        {
+            return Err(ContractError::NotApproved);
+        }
+
+        Self::transfer_token_internal(env, from, to, token_id, false, true)
+    }
+
+    // ====================================================================
+    // Burning (Retirement)
+    // ====================================================================
+
+    // C-01 extension: burn a specific token_id (used by RetirementTracker for 1:1 retirement).
+    pub fn burn_token(env: Env, token_id: u32, from: Address) -> Result<(), ContractError> {
+        let retirement_tracker = Self::get_retirement_tracker(env.clone())?;
+        retirement_tracker.require_auth();
+
+        Self::burn_token_internal(env, token_id, from, retirement_tracker)
+    }
+
+    // Burns `token_id` for RetirementTracker's `retire_from`. `spender` must be
+    // the owner, the token's approved spender or one of the owner's operators,
+    // or else hold an allowance from `from` covering the token's quantity,
+    // which is then spent.
+    pub fn burn_token_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        token_id: u32,
+    ) -> Result<(), ContractError> {
+        let retirement_tracker = Self::get_retirement_tracker(env.clone())?;
+        retirement_tracker.require_auth();
+
+        let owner = Self::owner_of(env.clone(), token_id)?;
+        if owner != from {
+            return Err(ContractError::NotOwner);
+        }
+        if spender != owner
+            && Self::get_approved(env.clone(), token_id) != Some(spender.clone())
+            && !Self::is_approved_for_all(env.clone(), owner, spender.clone())
+        {
+            let quantity = Self::quantity_of(env.clone(), token_id)?;
+            let allowance =
+                Self::spend_allowance(env.clone(), from.clone(), spender.clone(), quantity)
+                    .map_err(|_| ContractError::NotApproved)?;
+            let key = DataKey::Allowance(from.clone(), spender);
+            storage::write_persistent(&env, &key, &allowance);
+        }
+
+        Self::burn_token_internal(env, token_id, from, retirement_tracker)
+    }
+
+    // Picks `owner`'s Issued tokens matching `filter` until they cover exactly
+    // `amount`, splitting the last one if needed, in the owner's picking order.
+    // RetirementTracker then burns each returned id with `burn_token`.
+    pub fn select_for_retirement(
+        env: Env,
+        owner: Address,
+        amount: i128,
+        filter: TokenFilter,
+    ) -> Result<Vec<u32>, ContractError> {
+        let retirement_tracker = Self::get_retirement_tracker(env.clone())?;
+        retirement_tracker.require_auth();
+        Self::require_not_paused(&env, PauseScope::Retire)?;
+        Self::require_migrated(&env)?;
+
+        if amount <= 0 {
+            return Err(ContractError::InvalidQuantity);
+        }
+
+        Self::collect_transferable_tokens(env, owner, amount, Some(filter))
+    }
+
+    // ====================================================================
+    // Compliance Hook
+    // ====================================================================
+
+    pub fn before_transfer(
+        env: Env,
+        from: Address,
+        to: Address,
+        token_id: u32,
     ) -> Result<bool, ContractError> {
+        match Self::validate_compliance(env, from, to, token_id)? {
+            Some((_, result)) => Ok(result.is_compliant && !result.requires_authorization),
+            None => Ok(true),
+        }
+    }
+
+    // Registers a pending approval with the RegulatoryCheck contract for a
+    // transfer whose rule requires an authority's sign-off. The returned key
+    // is later redeemed through `transfer_with_approval`.
+    pub fn request_transfer_approval(
+        env: Env,
+        from: Address,
+        to: Address,
+        token_id: u32,
+    ) -> Result<BytesN<32>, ContractError> {
+        from.require_auth();
+
+        let owner = Self::owner_of(env.clone(), token_id)?;
+        if owner != from {
+            return Err(ContractError::NotOwner);
+        }
+
+        let (operation, result) =
+            Self::validate_compliance(env.clone(), from.clone(), to.clone(), token_id)?
+                .ok_or(ContractError::ApprovalNotRequired)?;
+        if !result.is_compliant {
+            return Err(ContractError::ComplianceFailed);
+        }
+        if !result.requires_authorization {
+            return Err(ContractError::ApprovalNotRequired);
+        }
+
+        let nonce: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ApprovalNonce)
+            .unwrap_or(0u64);
+        env.storage()
+            .instance()
+            .set(&DataKey::ApprovalNonce, &(nonce + 1));
+
+        let preimage = (
+            env.current_contract_address(),
+            token_id,
+            from.clone(),
+            to.clone(),
+            nonce,
+        )
+            .to_xdr(&env);
+        let approval_key: BytesN<32> = env.crypto().sha256(&preimage).into();
+
+        let contract =
+            Self::get_regulatory_check(env.clone()).ok_or(ContractError::RegulatoryNotSet)?;
+        let mut args = Vec::new(&env);
+        args.push_back(approval_key.into_val(&env));
+        args.push_back(token_id.into_val(&env));
+        args.push_back(from.into_val(&env));
+        args.push_back(to.into_val(&env));
+        args.push_back(operation.into_val(&env));
+        env.invoke_contract::<()>(&contract, &Symbol::new(&env, "create_pending_approval"), args);
+
+        storage::write_persistent(
+            &env,
+            &DataKey::PendingTransfer(approval_key.clone()),
+            &PendingTransfer {
+                token_id,
+                from: from.clone(),
+                to: to.clone(),
+                operation,
+            },
+        );
+
+        ApprovalRequestedEvent {
+            approval_key: approval_key.clone(),
+            token_id,
+            from,
+            to,
+            authority: result.authority_address,
+        }
+        .publish(&env);
+
+        Ok(approval_key)
+    }
+
+    // Completes a transfer once the required authority has signed off. The
+    // pending entry is removed first, so a key can only be redeemed once.
+    pub fn transfer_with_approval(
+        env: Env,
+        approval_key: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        let key = DataKey::PendingTransfer(approval_key.clone());
+        let pending: PendingTransfer =
+            storage::read_persistent(&env, &key).ok_or(ContractError::ApprovalNotFound)?;
+        pending.from.require_auth();
+
+        let contract =
+            Self::get_regulatory_check(env.clone()).ok_or(ContractError::RegulatoryNotSet)?;
+        let mut args = Vec::new(&env);
+        args.push_back(approval_key.into_val(&env));
+        let approved: bool =
+            env.invoke_contract(&contract, &Symbol::new(&env, "check_approval"), args);
+        if !approved {
+            return Err(ContractError::ApprovalNotGranted);
+        }
+
+        env.storage().persistent().remove(&key);
+
+        Self::transfer_token_internal(
+            env.clone(),
+            pending.from,
+            pending.to,
+            pending.token_id,
+            false,
+            false,
+        )?;
+
+        ApprovalConsumedEvent {
+            approval_key,
+            token_id: pending.token_id,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    fn validate_compliance(
+        env: Env,
+        from: Address,
+        to: Address,
+        token_id: u32,
+    ) -> Result<Option<(OperationType, ValidationResult)>, ContractError> {
         let _status = Self::get_status(env.clone(), token_id)?;
 
         let regulatory_contract: Option<Address> = env
@@ -283,7 +997,7 @@ impl CarbonAsset {
             .get(&DataKey::RegulatoryCheck);
 
         if regulatory_contract.is_none() {
-            return Ok(true);
+            return Ok(None);
         }
 
         let host_jurisdiction: Option<String> = env
@@ -303,27 +1017,40 @@ impl CarbonAsset {
         } else {
             OperationType::TRANSFER
         };
+        let metadata = Self::get_metadata(env.clone(), token_id)?;
+        let credit = CreditContext {
+            token_id,
+            project_id: metadata.project_id,
+            vintage_year: metadata.vintage_year,
+            methodology_id: metadata.methodology_id,
+            country: metadata.country,
+            corresponding_adjustment: metadata.corresponding_adjustment,
+        };
+
         let symbol = Symbol::new(&env, "validate_transaction");
         let mut args = Vec::new(&env);
         args.push_back(from.into_val(&env));
         args.push_back(to.into_val(&env));
-        args.push_back(operation.into_val(&env));
+        args.push_back(operation.clone().into_val(&env));
         args.push_back(host_jurisdiction.into_val(&env));
+        args.push_back(credit.into_val(&env));
 
         let result: ValidationResult = env.invoke_contract(&contract, &symbol, args);
 
-        Ok(result.is_compliant && !result.requires_authorization)
+        Ok(Some((operation, result)))
     }
 
     // ====================================================================
-    // Status Management
+    // Admin Handover
     // ====================================================================
 
-    pub fn set_status(
+    // Starts a two-step handover. Nothing changes until `new_admin` accepts,
+    // so a mistyped address can simply be left to expire or be re-proposed.
+    pub fn propose_admin(
         env: Env,
         caller: Address,
-        token_id: u32,
-        new_status: AssetStatus,
+        new_admin: Address,
+        expiration_ledger: u32,
     ) -> Result<(), ContractError> {
         caller.require_auth();
         let admin = Self::get_admin(env.clone())?;
@@ -331,80 +1058,71 @@ impl CarbonAsset {
             return Err(ContractError::NotAuthorized);
         }
 
-        let current = Self::get_status(env.clone(), token_id)?;
-        if current == AssetStatus::Retired || current == AssetStatus::Invalidated {
-            return Err(ContractError::StatusFrozen);
+        if expiration_ledger < env.ledger().sequence() {
+            return Err(ContractError::AdminProposalExpired);
         }
 
-        if new_status == AssetStatus::Issued {
-            return Err(ContractError::InvalidStatusTransition);
+        env.storage().instance().set(
+            &DataKey::PendingAdmin,
+            &PendingAdmin {
+                new_admin: new_admin.clone(),
+                expiration_ledger,
+            },
+        );
+
+        AdminProposedEvent {
+            current_admin: admin,
+            proposed_admin: new_admin,
+            expiration_ledger,
         }
+        .publish(&env);
 
-        Self::set_status_internal(env, token_id, new_status, caller)
+        Ok(())
     }
 
-    // ====================================================================
-    // Quality Score Updates
-    // ====================================================================
+    // Roles the previous admin still holds move to the new admin, so the
+    // outgoing key keeps no privileges. Roles delegated to other accounts
+    // are left in place.
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), ContractError> {
+        new_admin.require_auth();
 
-    pub fn set_oracle(env: Env, caller: Address, oracle: Address) -> Result<(), ContractError> {
-        caller.require_auth();
-        let admin = Self::get_admin(env.clone())?;
-        if caller != admin {
+        let pending = Self::get_pending_admin(env.clone()).ok_or(ContractError::NoPendingAdmin)?;
+        if pending.new_admin != new_admin {
             return Err(ContractError::NotAuthorized);
         }
-
-        env.storage().instance().set(&DataKey::Oracle, &oracle);
-        Ok(())
-    }
-
-    pub fn update_quality_score(
-        env: Env,
-        caller: Address,
-        token_id: u32,
-        new_score: i128,
-    ) -> Result<(), ContractError> {
-        caller.require_auth();
-
-        let admin = Self::get_admin(env.clone())?;
-        let oracle: Option<Address> = env.storage().instance().get(&DataKey::Oracle);
-
-        if caller != admin {
-            match oracle {
-                Some(oracle_addr) if caller == oracle_addr => {}
-                _ => return Err(ContractError::NotAuthorized),
-            }
+        if env.ledger().sequence() > pending.expiration_ledger {
+            return Err(ContractError::AdminProposalExpired);
         }
 
-        let old_score: i128 = env
-            .storage()
-            .persistent()
-            .get(&DataKey::QualityScore(token_id))
-            .ok_or(ContractError::TokenNotFound)?;
-
-        env.storage()
-            .persistent()
-            .set(&DataKey::QualityScore(token_id), &new_score);
+        let previous_admin = Self::get_admin(env.clone())?;
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+        Self::move_roles(&env, &previous_admin, &new_admin);
 
-        QualityScoreUpdatedEvent {
-            token_id,
-            old_score,
-            new_score,
-            updated_by: caller,
+        AdminTransferredEvent {
+            previous_admin,
+            new_admin,
         }
         .publish(&env);
 
         Ok(())
     }
 
+    pub fn get_pending_admin(env: Env) -> Option<PendingAdmin> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
     // ====================================================================
-    // Admin Configuration
+    // Roles
     // ====================================================================
 
-    pub fn set_retirement_tracker(
+    // Roles are managed by the admin, who is granted all of them at
+    // initialization and can then hand each one to a separate key.
+    pub fn grant_role(
         env: Env,
         caller: Address,
-        retirement_tracker: Address,
+        role: Role,
+        account: Address,
     ) -> Result<(), ContractError> {
         caller.require_auth();
         let admin = Self::get_admin(env.clone())?;
@@ -412,33 +1130,27 @@ impl CarbonAsset {
             return Err(ContractError::NotAuthorized);
         }
 
-        env.storage()
-            .instance()
-            .set(&DataKey::RetirementTracker, &retirement_tracker);
-        Ok(())
-    }
+        if Self::has_role(env.clone(), role, account.clone()) {
+            return Ok(());
+        }
 
-    pub fn set_regulatory_check(
-        env: Env,
-        caller: Address,
-        regulatory_check: Address,
-    ) -> Result<(), ContractError> {
-        caller.require_auth();
-        let admin = Self::get_admin(env.clone())?;
-        if caller != admin {
-            return Err(ContractError::NotAuthorized);
+        storage::write_persistent(&env, &DataKey::Role(role, account.clone()), &true);
+
+        RoleGrantedEvent {
+            role,
+            account,
+            granted_by: caller,
         }
+        .publish(&env);
 
-        env.storage()
-            .instance()
-            .set(&DataKey::RegulatoryCheck, &regulatory_check);
         Ok(())
     }
 
-    pub fn set_host_jurisdiction(
+    pub fn revoke_role(
         env: Env,
         caller: Address,
-        host_jurisdiction: String,
+        role: Role,
+        account: Address,
     ) -> Result<(), ContractError> {
         caller.require_auth();
         let admin = Self::get_admin(env.clone())?;
@@ -446,343 +1158,1952 @@ impl CarbonAsset {
             return Err(ContractError::NotAuthorized);
         }
 
+        if !Self::has_role(env.clone(), role, account.clone()) {
+            return Ok(());
+        }
+
         env.storage()
-            .instance()
-            .set(&DataKey::HostJurisdiction, &host_jurisdiction);
+            .persistent()
+            .remove(&DataKey::Role(role, account.clone()));
+
+        RoleRevokedEvent {
+            role,
+            account,
+            revoked_by: caller,
+        }
+        .publish(&env);
+
         Ok(())
     }
 
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        storage::read_persistent(&env, &DataKey::Role(role, account)).unwrap_or(false)
+    }
+
     // ====================================================================
-    // Getters
+    // Emergency Pause
     // ====================================================================
 
-    pub fn get_admin(env: Env) -> Result<Address, ContractError> {
-        env.storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .ok_or(ContractError::NotInitialized)
-    }
+    // Circuit breaker for incidents. Pausing `All` halts mints, transfers and
+    // retirements at once; the per-operation scopes can be toggled
+    // independently and stay set when `All` is lifted.
+    pub fn pause(env: Env, caller: Address, scope: PauseScope) -> Result<(), ContractError> {
+        Self::require_role(&env, &caller, Role::Pauser)?;
 
-    pub fn get_name(env: Env) -> Result<String, ContractError> {
-        env.storage()
-            .instance()
-            .get(&DataKey::Name)
-            .ok_or(ContractError::NotInitialized)
-    }
+        if Self::is_paused(env.clone(), scope) {
+            return Ok(());
+        }
 
-    pub fn get_symbol(env: Env) -> Result<String, ContractError> {
-        env.storage()
-            .instance()
-            .get(&DataKey::Symbol)
-            .ok_or(ContractError::NotInitialized)
+        env.storage().instance().set(&DataKey::Paused(scope), &true);
+        storage::extend_instance_ttl(&env);
+
+        PausedEvent {
+            scope,
+            paused_by: caller,
+        }
+        .publish(&env);
+
+        Ok(())
     }
 
-    pub fn decimals(env: Env) -> u32 {
-        env.storage()
-            .instance()
-            .get(&DataKey::Decimals)
-            .unwrap_or(0u32)
+    pub fn unpause(env: Env, caller: Address, scope: PauseScope) -> Result<(), ContractError> {
+        Self::require_role(&env, &caller, Role::Pauser)?;
+
+        if !Self::is_paused(env.clone(), scope) {
+            return Ok(());
+        }
+
+        env.storage().instance().remove(&DataKey::Paused(scope));
+        storage::extend_instance_ttl(&env);
+
+        UnpausedEvent {
+            scope,
+            unpaused_by: caller,
+        }
+        .publish(&env);
+
+        Ok(())
     }
 
-    pub fn name(env: Env) -> String {
+    // Reports only the flag for `scope` itself; an operation is blocked when
+    // either its own scope or `All` is paused.
+    pub fn is_paused(env: Env, scope: PauseScope) -> bool {
         env.storage()
             .instance()
-            .get(&DataKey::Name)
-            .unwrap()
+            .get(&DataKey::Paused(scope))
+            .unwrap_or(false)
     }
 
-    pub fn symbol(env: Env) -> String {
-        env.storage()
-            .instance()
-            .get(&DataKey::Symbol)
-            .unwrap()
+    // ====================================================================
+    // Status Management
+    // ====================================================================
+
+    pub fn set_status(
+        env: Env,
+        caller: Address,
+        token_id: u32,
+        new_status: AssetStatus,
+    ) -> Result<(), ContractError> {
+        Self::require_role(&env, &caller, Role::StatusManager)?;
+
+        let current = Self::get_status(env.clone(), token_id)?;
+        if current == AssetStatus::Retired || current == AssetStatus::Invalidated {
+            return Err(ContractError::StatusFrozen);
+        }
+
+        // Listing carries sale terms, so it is only entered through `list`.
+        if new_status == AssetStatus::Issued || new_status == AssetStatus::Listed {
+            return Err(ContractError::InvalidStatusTransition);
+        }
+
+        Self::set_status_internal(env, token_id, new_status, caller)
     }
 
-    pub fn get_retirement_tracker(env: Env) -> Result<Address, ContractError> {
-        env.storage()
-            .instance()
-            .get(&DataKey::RetirementTracker)
-            .ok_or(ContractError::NotInitialized)
+    // ====================================================================
+    // Listings
+    // ====================================================================
+
+    // Puts a token up for sale, or updates the terms of an existing listing.
+    // While listed a token is reserved for its buyer: amount-based transfers
+    // skip it and it cannot be split, merged or retired until delisted.
+    // Moving it to a buyer, or any other status change, ends the listing.
+    pub fn list(
+        env: Env,
+        token_id: u32,
+        price: i128,
+        currency: Address,
+    ) -> Result<(), ContractError> {
+        let owner = Self::owner_of(env.clone(), token_id)?;
+        owner.require_auth();
+
+        if price <= 0 {
+            return Err(ContractError::InvalidPrice);
+        }
+        if Self::is_burned(env.clone(), token_id)? {
+            return Err(ContractError::TokenAlreadyBurned);
+        }
+        let status = Self::get_status(env.clone(), token_id)?;
+        if status != AssetStatus::Issued && status != AssetStatus::Listed {
+            return Err(ContractError::InvalidStatusTransition);
+        }
+
+        storage::write_persistent(
+            &env,
+            &DataKey::Listing(token_id),
+            &Listing {
+                seller: owner.clone(),
+                price,
+                currency: currency.clone(),
+                listed_at: env.ledger().timestamp(),
+            },
+        );
+        Self::set_status_internal(env.clone(), token_id, AssetStatus::Listed, owner.clone())?;
+
+        ListedEvent {
+            token_id,
+            seller: owner,
+            price,
+            currency,
+        }
+        .publish(&env);
+
+        Ok(())
     }
 
-    pub fn get_regulatory_check(env: Env) -> Option<Address> {
-        env.storage().instance().get(&DataKey::RegulatoryCheck)
+    pub fn delist(env: Env, token_id: u32) -> Result<(), ContractError> {
+        let owner = Self::owner_of(env.clone(), token_id)?;
+        owner.require_auth();
+
+        if Self::get_listing(env.clone(), token_id).is_none() {
+            return Err(ContractError::NotListed);
+        }
+
+        Self::set_status_internal(env, token_id, AssetStatus::Issued, owner)
     }
 
-    pub fn get_host_jurisdiction(env: Env) -> Option<String> {
-        env.storage().instance().get(&DataKey::HostJurisdiction)
+    pub fn get_listing(env: Env, token_id: u32) -> Option<Listing> {
+        storage::read_persistent(&env, &DataKey::Listing(token_id))
     }
 
-    pub fn get_oracle(env: Env) -> Option<Address> {
-        env.storage().instance().get(&DataKey::Oracle)
+    // ====================================================================
+    // Project and Vintage Controls
+    // ====================================================================
+
+    // These act on every credit of a project (or one of its vintages) with a
+    // single write: `get_status` reads the project and vintage state next to
+    // each token's own status, so no token has to be visited. Invalidation
+    // is final; a freeze can be lifted again.
+    pub fn freeze_project(
+        env: Env,
+        caller: Address,
+        project_id: String,
+    ) -> Result<(), ContractError> {
+        Self::set_project_state(env, caller, project_id, IssuanceState::Frozen)
     }
 
-    pub fn owner_of(env: Env, token_id: u32) -> Result<Address, ContractError> {
-        let burned: bool = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Burned(token_id))
-            .unwrap_or(false);
-        if burned {
-            return Err(ContractError::TokenNotFound);
+    pub fn unfreeze_project(
+        env: Env,
+        caller: Address,
+        project_id: String,
+    ) -> Result<(), ContractError> {
+        Self::set_project_state(env, caller, project_id, IssuanceState::Active)
+    }
+
+    pub fn invalidate_project(
+        env: Env,
+        caller: Address,
+        project_id: String,
+    ) -> Result<(), ContractError> {
+        Self::set_project_state(env, caller, project_id, IssuanceState::Invalidated)
+    }
+
+    pub fn freeze_vintage(
+        env: Env,
+        caller: Address,
+        project_id: String,
+        vintage_year: u64,
+    ) -> Result<(), ContractError> {
+        Self::set_vintage_state(env, caller, project_id, vintage_year, IssuanceState::Frozen)
+    }
+
+    pub fn unfreeze_vintage(
+        env: Env,
+        caller: Address,
+        project_id: String,
+        vintage_year: u64,
+    ) -> Result<(), ContractError> {
+        Self::set_vintage_state(env, caller, project_id, vintage_year, IssuanceState::Active)
+    }
+
+    pub fn invalidate_vintage(
+        env: Env,
+        caller: Address,
+        project_id: String,
+        vintage_year: u64,
+    ) -> Result<(), ContractError> {
+        Self::set_vintage_state(
+            env,
+            caller,
+            project_id,
+            vintage_year,
+            IssuanceState::Invalidated,
+        )
+    }
+
+    // ====================================================================
+    // Reversals
+    // ====================================================================
+
+    // Invalidates each token and makes its holder whole from the buffer pool
+    // in the same call. The reversed quantity leaves the holder's balance and
+    // the total supply. `buffer_token_ids` names the pool credits to draw on,
+    // in order (e.g. from `tokens_of_owner` on the pool); candidates the pool
+    // no longer holds, of another credit type or an earlier vintage are
+    // skipped. A credit is split when only part of it is needed and moved to
+    // the holder; the link is kept on both tokens. The whole call fails with
+    // `InsufficientBuffer` rather than leave a holder short. Tokens already
+    // invalidated through a project or vintage action can still be reversed,
+    // so their holders get compensated.
+    pub fn reverse(
+        env: Env,
+        caller: Address,
+        token_ids: Vec<u32>,
+        buffer_token_ids: Vec<u32>,
+    ) -> Result<(), ContractError> {
+        Self::require_role(&env, &caller, Role::StatusManager)?;
+        Self::require_migrated(&env)?;
+        let pool = Self::get_buffer_pool(env.clone()).ok_or(ContractError::BufferPoolNotSet)?;
+        if buffer_token_ids.len() > MAX_PAGE_SIZE {
+            return Err(ContractError::BatchTooLarge);
         }
-        env.storage()
-            .persistent()
-            .get(&DataKey::Owner(token_id))
-            .ok_or(ContractError::TokenNotFound)
+
+        for token_id in token_ids.iter() {
+            Self::reverse_token(&env, &caller, &pool, token_id, &buffer_token_ids)?;
+        }
+
+        Ok(())
     }
 
-    pub fn get_metadata(env: Env, token_id: u32) -> Result<CarbonAssetMetadata, ContractError> {
+    pub fn get_replacements(env: Env, token_id: u32) -> Vec<u32> {
+        storage::read_persistent(&env, &DataKey::Replacements(token_id)).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_replaced_token(env: Env, replacement_token_id: u32) -> Option<u32> {
+        storage::read_persistent(&env, &DataKey::ReplacedToken(replacement_token_id))
+    }
+
+    // ====================================================================
+    // Quality Score Updates
+    // ====================================================================
+
+    pub fn set_oracle(env: Env, caller: Address, oracle: Address) -> Result<(), ContractError> {
+        Self::require_role(&env, &caller, Role::ConfigAdmin)?;
+
+        env.storage().instance().set(&DataKey::Oracle, &oracle);
+        Ok(())
+    }
+
+    // Manual override by the configured oracle contract or an oracle role
+    // holder. It is kept in the score history and stands until the next
+    // aggregation replaces it.
+    pub fn update_quality_score(
+        env: Env,
+        caller: Address,
+        token_id: u32,
+        new_score: i128,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+
+        // The configured oracle contract is always accepted alongside holders
+        // of the oracle role.
+        let oracle: Option<Address> = env.storage().instance().get(&DataKey::Oracle);
+        let is_oracle_contract = oracle == Some(caller.clone());
+        if !is_oracle_contract && !Self::has_role(env.clone(), Role::Oracle, caller.clone()) {
+            return Err(ContractError::NotAuthorized);
+        }
+
+        let old_score = Self::get_quality_score(env.clone(), token_id)?;
+
+        storage::write_persistent(&env, &DataKey::QualityScore(token_id), &new_score);
+        Self::record_score(
+            &env,
+            token_id,
+            &ScoreSubmission {
+                source: caller.clone(),
+                score: new_score,
+                observed_at: env.ledger().timestamp(),
+                evidence_hash: None,
+                recorded_at: env.ledger().timestamp(),
+            },
+        );
+
+        QualityScoreUpdatedEvent {
+            token_id,
+            old_score,
+            new_score,
+            updated_by: caller,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    // Adds `oracle` to the set whose submissions are aggregated, or changes
+    // its weight. The weight only matters for `ScoreAggregation::WeightedMean`.
+    pub fn register_oracle(
+        env: Env,
+        caller: Address,
+        oracle: Address,
+        weight: u32,
+    ) -> Result<(), ContractError> {
+        Self::require_role(&env, &caller, Role::ConfigAdmin)?;
+
+        if weight == 0 {
+            return Err(ContractError::InvalidWeight);
+        }
+        if Self::get_oracle_weight(env.clone(), oracle.clone()) == 0 {
+            let count: u32 = env
+                .storage()
+                .instance()
+                .get(&DataKey::Score(ScoreKey::OracleCount))
+                .unwrap_or(0);
+            if count >= MAX_ORACLES {
+                return Err(ContractError::TooManyOracles);
+            }
+            env.storage().instance().set(&DataKey::Score(ScoreKey::OracleCount), &(count + 1));
+        }
+        let key = DataKey::Score(ScoreKey::OracleWeight(oracle.clone()));
+        storage::write_persistent(&env, &key, &weight);
+
+        OracleRegisteredEvent {
+            oracle,
+            weight,
+            registered_by: caller,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    // Past submissions stay in the history but no longer count towards any
+    // aggregate computed from now on.
+    pub fn remove_oracle(env: Env, caller: Address, oracle: Address) -> Result<(), ContractError> {
+        Self::require_role(&env, &caller, Role::ConfigAdmin)?;
+
+        if Self::get_oracle_weight(env.clone(), oracle.clone()) == 0 {
+            return Err(ContractError::OracleNotRegistered);
+        }
+        let count: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::Score(ScoreKey::OracleCount))
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::Score(ScoreKey::OracleCount), &count.saturating_sub(1));
         env.storage()
             .persistent()
-            .get(&DataKey::Metadata(token_id))
-            .ok_or(ContractError::TokenNotFound)
+            .remove(&DataKey::Score(ScoreKey::OracleWeight(oracle.clone())));
+
+        OracleRemovedEvent {
+            oracle,
+            removed_by: caller,
+        }
+        .publish(&env);
+
+        Ok(())
     }
 
-    pub fn get_status(env: Env, token_id: u32) -> Result<AssetStatus, ContractError> {
+    pub fn set_score_config(
+        env: Env,
+        caller: Address,
+        method: ScoreAggregation,
+        max_age: u64,
+    ) -> Result<(), ContractError> {
+        Self::require_role(&env, &caller, Role::ConfigAdmin)?;
+
         env.storage()
-            .persistent()
-            .get(&DataKey::Status(token_id))
-            .ok_or(ContractError::TokenNotFound)
+            .instance()
+            .set(&DataKey::Score(ScoreKey::Config), &ScoreConfig { method, max_age });
+
+        ScoreConfigEvent {
+            method,
+            max_age,
+            set_by: caller,
+        }
+        .publish(&env);
+
+        Ok(())
     }
 
-    pub fn get_quality_score(env: Env, token_id: u32) -> Result<i128, ContractError> {
+    // Records an oracle's reading for `token_id` and re-aggregates the
+    // token's quality score. `observed_at` is when the underlying data (e.g.
+    // a satellite pass) was captured; it may not lie in the future, be older
+    // than the staleness window, or precede this oracle's previous reading.
+    pub fn submit_quality_score(
+        env: Env,
+        oracle: Address,
+        token_id: u32,
+        score: i128,
+        observed_at: u64,
+        evidence_hash: BytesN<32>,
+    ) -> Result<i128, ContractError> {
+        oracle.require_auth();
+
+        if Self::get_oracle_weight(env.clone(), oracle.clone()) == 0 {
+            return Err(ContractError::OracleNotRegistered);
+        }
+        Self::get_quality_score(env.clone(), token_id)?;
+
+        let now = env.ledger().timestamp();
+        let config = Self::get_score_config(env.clone());
+        if observed_at > now || now - observed_at > config.max_age {
+            return Err(ContractError::StaleScore);
+        }
+        let latest_key = DataKey::Score(ScoreKey::Latest(token_id, oracle.clone()));
+        if let Some(previous) = storage::read_persistent::<ScoreSubmission>(&env, &latest_key) {
+            if observed_at < previous.observed_at {
+                return Err(ContractError::StaleScore);
+            }
+        } else {
+            let sources_key = DataKey::Score(ScoreKey::Sources(token_id));
+            let mut sources: Vec<Address> =
+                storage::read_persistent(&env, &sources_key).unwrap_or(Vec::new(&env));
+            sources.push_back(oracle.clone());
+            storage::write_persistent(&env, &sources_key, &sources);
+        }
+
+        let submission = ScoreSubmission {
+            source: oracle.clone(),
+            score,
+            observed_at,
+            evidence_hash: Some(evidence_hash.clone()),
+            recorded_at: now,
+        };
+        storage::write_persistent(&env, &latest_key, &submission);
+        Self::record_score(&env, token_id, &submission);
+
+        ScoreSubmittedEvent {
+            token_id,
+            oracle: oracle.clone(),
+            score,
+            observed_at,
+            evidence_hash,
+        }
+        .publish(&env);
+
+        Self::aggregate_quality_score(&env, token_id, oracle)
+    }
+
+    // Permissionless re-aggregation, so submissions that have gone stale stop
+    // counting without waiting for the next oracle reading.
+    pub fn refresh_quality_score(env: Env, token_id: u32) -> Result<i128, ContractError> {
+        Self::aggregate_quality_score(&env, token_id, env.current_contract_address())
+    }
+
+    pub fn get_oracle_weight(env: Env, oracle: Address) -> u32 {
+        storage::read_persistent(&env, &DataKey::Score(ScoreKey::OracleWeight(oracle))).unwrap_or(0)
+    }
+
+    pub fn get_score_config(env: Env) -> ScoreConfig {
         env.storage()
-            .persistent()
-            .get(&DataKey::QualityScore(token_id))
-            .ok_or(ContractError::TokenNotFound)
+            .instance()
+            .get(&DataKey::Score(ScoreKey::Config))
+            .unwrap_or(ScoreConfig {
+                method: ScoreAggregation::Median,
+                max_age: DEFAULT_SCORE_MAX_AGE,
+            })
     }
 
-    pub fn balance_of(env: Env, owner: Address) -> i128 {
-        let tokens: Vec<u32> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::OwnerTokens(owner))
-            .unwrap_or(Vec::new(&env));
-        tokens.len() as i128
+    pub fn get_latest_score(env: Env, token_id: u32, oracle: Address) -> Option<ScoreSubmission> {
+        storage::read_persistent(&env, &DataKey::Score(ScoreKey::Latest(token_id, oracle)))
+    }
+
+    pub fn score_history_count(env: Env, token_id: u32) -> u32 {
+        storage::read_persistent(&env, &DataKey::Score(ScoreKey::HistoryCount(token_id)))
+            .unwrap_or(0)
+    }
+
+    // Up to `limit` (capped at `MAX_PAGE_SIZE`) history entries from `start`,
+    // oldest first.
+    pub fn get_score_history(
+        env: Env,
+        token_id: u32,
+        start: u32,
+        limit: u32,
+    ) -> Vec<ScoreSubmission> {
+        let count = Self::score_history_count(env.clone(), token_id);
+        let end = count.min(start.saturating_add(limit.min(MAX_PAGE_SIZE)));
+        let mut history = Vec::new(&env);
+        for index in start..end {
+            let key = DataKey::Score(ScoreKey::History(token_id, index));
+            history.push_back(storage::read_persistent(&env, &key).unwrap());
+        }
+        history
     }
 
-    pub fn tokens_of_owner(env: Env, owner: Address) -> Vec<u32> {
+    // ====================================================================
+    // Admin Configuration
+    // ====================================================================
+
+    pub fn set_retirement_tracker(
+        env: Env,
+        caller: Address,
+        retirement_tracker: Address,
+    ) -> Result<(), ContractError> {
+        Self::require_role(&env, &caller, Role::ConfigAdmin)?;
+
         env.storage()
-            .persistent()
-            .get(&DataKey::OwnerTokens(owner))
-            .unwrap_or(Vec::new(&env))
+            .instance()
+            .set(&DataKey::RetirementTracker, &retirement_tracker);
+        Ok(())
     }
 
-    pub fn is_burned(env: Env, token_id: u32) -> Result<bool, ContractError> {
-        Self::get_status(env.clone(), token_id)?;
-        Ok(env
-            .storage()
+    pub fn set_regulatory_check(
+        env: Env,
+        caller: Address,
+        regulatory_check: Address,
+    ) -> Result<(), ContractError> {
+        Self::require_role(&env, &caller, Role::ConfigAdmin)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::RegulatoryCheck, &regulatory_check);
+        Ok(())
+    }
+
+    pub fn set_host_jurisdiction(
+        env: Env,
+        caller: Address,
+        host_jurisdiction: String,
+    ) -> Result<(), ContractError> {
+        Self::require_role(&env, &caller, Role::ConfigAdmin)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::HostJurisdiction, &host_jurisdiction);
+        Ok(())
+    }
+
+    pub fn set_buffer_pool(
+        env: Env,
+        caller: Address,
+        buffer_pool: Address,
+    ) -> Result<(), ContractError> {
+        Self::require_role(&env, &caller, Role::ConfigAdmin)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::BufferPool, &buffer_pool);
+        Ok(())
+    }
+
+    pub fn set_methodology_library(
+        env: Env,
+        caller: Address,
+        methodology_library: Address,
+    ) -> Result<(), ContractError> {
+        Self::require_role(&env, &caller, Role::ConfigAdmin)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MethodologyLibrary, &methodology_library);
+        Ok(())
+    }
+
+    pub fn set_project_registry(
+        env: Env,
+        caller: Address,
+        project_registry: Address,
+    ) -> Result<(), ContractError> {
+        Self::require_role(&env, &caller, Role::ConfigAdmin)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::ProjectRegistry, &project_registry);
+        Ok(())
+    }
+
+    // ====================================================================
+    // Getters
+    // ====================================================================
+
+    pub fn get_admin(env: Env) -> Result<Address, ContractError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(ContractError::NotInitialized)
+    }
+
+    pub fn get_name(env: Env) -> Result<String, ContractError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Name)
+            .ok_or(ContractError::NotInitialized)
+    }
+
+    pub fn get_symbol(env: Env) -> Result<String, ContractError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Symbol)
+            .ok_or(ContractError::NotInitialized)
+    }
+
+    pub fn decimals(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::Decimals)
+            .unwrap_or(0u32)
+    }
+
+    pub fn name(env: Env) -> String {
+        env.storage()
+            .instance()
+            .get(&DataKey::Name)
+            .unwrap()
+    }
+
+    pub fn symbol(env: Env) -> String {
+        env.storage()
+            .instance()
+            .get(&DataKey::Symbol)
+            .unwrap()
+    }
+
+    pub fn get_retirement_tracker(env: Env) -> Result<Address, ContractError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::RetirementTracker)
+            .ok_or(ContractError::NotInitialized)
+    }
+
+    pub fn get_regulatory_check(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::RegulatoryCheck)
+    }
+
+    pub fn get_host_jurisdiction(env: Env) -> Option<String> {
+        env.storage().instance().get(&DataKey::HostJurisdiction)
+    }
+
+    pub fn get_oracle(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Oracle)
+    }
+
+    pub fn get_buffer_pool(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::BufferPool)
+    }
+
+    pub fn get_methodology_library(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::MethodologyLibrary)
+    }
+
+    pub fn get_project_registry(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::ProjectRegistry)
+    }
+
+    pub fn owner_of(env: Env, token_id: u32) -> Result<Address, ContractError> {
+        let burned: bool =
+            storage::read_persistent(&env, &DataKey::Burned(token_id)).unwrap_or(false);
+        if burned {
+            return Err(ContractError::TokenNotFound);
+        }
+        storage::read_persistent(&env, &DataKey::Owner(token_id))
+            .ok_or(ContractError::TokenNotFound)
+    }
+
+    pub fn get_metadata(env: Env, token_id: u32) -> Result<CarbonAssetMetadata, ContractError> {
+        storage::read_persistent(&env, &DataKey::Metadata(token_id))
+            .ok_or(ContractError::TokenNotFound)
+    }
+
+    // Effective status: the token's own status with any project or vintage
+    // freeze/invalidation applied on top.
+    pub fn get_status(env: Env, token_id: u32) -> Result<AssetStatus, ContractError> {
+        let status = Self::stored_status(&env, token_id)?;
+        if status == AssetStatus::Retired || status == AssetStatus::Invalidated {
+            return Ok(status);
+        }
+
+        let metadata = Self::get_metadata(env.clone(), token_id)?;
+        Ok(
+            match Self::issuance_state(&env, &metadata.project_id, metadata.vintage_year) {
+                IssuanceState::Active => status,
+                IssuanceState::Frozen => AssetStatus::Locked,
+                IssuanceState::Invalidated => AssetStatus::Invalidated,
+            },
+        )
+    }
+
+    pub fn get_project_state(env: Env, project_id: String) -> IssuanceState {
+        storage::read_persistent(&env, &DataKey::ProjectState(project_id))
+            .unwrap_or(IssuanceState::Active)
+    }
+
+    pub fn get_vintage_state(env: Env, project_id: String, vintage_year: u64) -> IssuanceState {
+        storage::read_persistent(&env, &DataKey::VintageState(project_id, vintage_year))
+            .unwrap_or(IssuanceState::Active)
+    }
+
+    pub fn get_quality_score(env: Env, token_id: u32) -> Result<i128, ContractError> {
+        storage::read_persistent(&env, &DataKey::QualityScore(token_id))
+            .ok_or(ContractError::TokenNotFound)
+    }
+
+    pub fn balance_of(env: Env, owner: Address) -> i128 {
+        storage::read_persistent(&env, &DataKey::Balance(owner)).unwrap_or(0)
+    }
+
+    pub fn quantity_of(env: Env, token_id: u32) -> Result<i128, ContractError> {
+        storage::read_persistent(&env, &DataKey::Quantity(token_id))
+            .ok_or(ContractError::TokenNotFound)
+    }
+
+    pub fn get_batch(env: Env, batch_id: u32) -> Result<IssuanceBatch, ContractError> {
+        storage::read_persistent(&env, &DataKey::Batch(batch_id))
+            .ok_or(ContractError::BatchNotFound)
+    }
+
+    pub fn get_token_serial(env: Env, token_id: u32) -> Option<TokenSerial> {
+        storage::read_persistent(&env, &DataKey::TokenSerial(token_id))
+    }
+
+    // Up to `limit` (capped at `MAX_PAGE_SIZE`) of the owner's tokens from
+    // position `start`. Positions are reused as tokens leave, so a holder's
+    // list can shift while it is being paged through.
+    pub fn tokens_of_owner(env: Env, owner: Address, start: u32, limit: u32) -> Vec<u32> {
+        let count = Self::owner_token_count(env.clone(), owner.clone());
+        let end = count.min(start.saturating_add(limit.min(MAX_PAGE_SIZE)));
+        Self::owner_token_range(&env, &owner, start, end)
+    }
+
+    pub fn owner_token_count(env: Env, owner: Address) -> u32 {
+        storage::read_persistent(&env, &DataKey::OwnerTokenCount(owner)).unwrap_or(0)
+    }
+
+    // Quantity issued and not yet retired, buffer pool holdings included.
+    pub fn total_supply(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalSupply)
+            .unwrap_or(0)
+    }
+
+    pub fn total_retired(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalRetired)
+            .unwrap_or(0)
+    }
+
+    // Project and vintage indexes only grow, so a cursor stays valid across
+    // calls. Tokens merged away since they were indexed are skipped, which
+    // can leave a page shorter than `limit`.
+    pub fn tokens_by_project(env: Env, project_id: String, cursor: u32, limit: u32) -> TokenPage {
+        let count = storage::read_persistent(&env, &DataKey::ProjectTokenCount(project_id.clone()))
+            .unwrap_or(0);
+        Self::index_page(&env, count, cursor, limit, |position| {
+            DataKey::ProjectToken(project_id.clone(), position)
+        })
+    }
+
+    pub fn tokens_by_vintage(
+        env: Env,
+        project_id: String,
+        vintage_year: u64,
+        cursor: u32,
+        limit: u32,
+    ) -> TokenPage {
+        let count = storage::read_persistent(
+            &env,
+            &DataKey::VintageTokenCount(project_id.clone(), vintage_year),
+        )
+        .unwrap_or(0);
+        Self::index_page(&env, count, cursor, limit, |position| {
+            DataKey::VintageToken(project_id.clone(), vintage_year, position)
+        })
+    }
+
+    pub fn is_burned(env: Env, token_id: u32) -> Result<bool, ContractError> {
+        Self::get_status(env.clone(), token_id)?;
+        Ok(storage::read_persistent(&env, &DataKey::Burned(token_id)).unwrap_or(false))
+    }
+
+    // Lets wrappers and vaults screen a credit against the same criteria used
+    // by `transfer_with_filter`.
+    pub fn token_matches_filter(
+        env: Env,
+        token_id: u32,
+        filter: TokenFilter,
+    ) -> Result<bool, ContractError> {
+        Self::matches_filter(env, token_id, &filter)
+    }
+
+    fn transfer_token_internal(
+        env: Env,
+        from: Address,
+        to: Address,
+        token_id: u32,
+        require_auth: bool,
+        check_compliance: bool,
+    ) -> Result<(), ContractError> {
+        if require_auth {
+            from.require_auth();
+        }
+        Self::require_not_paused(&env, PauseScope::Transfer)?;
+        Self::require_migrated(&env)?;
+
+        let owner = Self::owner_of(env.clone(), token_id)?;
+        if owner != from {
+            return Err(ContractError::NotOwner);
+        }
+
+        let burned = Self::is_burned(env.clone(), token_id)?;
+        if burned {
+            return Err(ContractError::TokenAlreadyBurned);
+        }
+
+        let status = Self::get_status(env.clone(), token_id)?;
+        if status != AssetStatus::Issued && status != AssetStatus::Listed {
+            return Err(ContractError::TransferNotAllowed);
+        }
+        let retirement_tracker = Self::get_retirement_tracker(env.clone())?;
+        if status == AssetStatus::Listed && to == retirement_tracker {
+            return Err(ContractError::TokenListed);
+        }
+
+        if check_compliance {
+            if let Some((_, result)) =
+                Self::validate_compliance(env.clone(), from.clone(), to.clone(), token_id)?
+            {
+                if !result.is_compliant {
+                    return Err(ContractError::ComplianceFailed);
+                }
+                if result.requires_authorization {
+                    return Err(ContractError::ApprovalRequired);
+                }
+            }
+        }
+
+        Self::remove_token_from_owner(env.clone(), from.clone(), token_id)?;
+        Self::add_token_to_owner(env.clone(), to.clone(), token_id);
+        storage::write_persistent(&env, &DataKey::Owner(token_id), &to);
+        Self::clear_token_approval(&env, token_id);
+
+        TransferEvent {
+            token_id,
+            from: from.clone(),
+            to: to.clone(),
+        }
+        .publish(&env);
+
+        // A sale ends the listing; the buyer lists again if they want to.
+        if status == AssetStatus::Listed {
+            Self::set_status_internal(env.clone(), token_id, AssetStatus::Issued, from.clone())?;
+        }
+        if to == retirement_tracker {
+            Self::set_status_internal(env, token_id, AssetStatus::Retired, from)?;
+        }
+
+        Ok(())
+    }
+
+    fn clear_token_approval(env: &Env, token_id: u32) {
+        env.storage()
             .persistent()
-            .get(&DataKey::Burned(token_id))
-            .unwrap_or(false))
+            .remove(&DataKey::TokenApproval(token_id));
+    }
+
+    fn transfer_amount_internal(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128,
+        filter: Option<TokenFilter>,
+    ) -> Result<(), ContractError> {
+        if amount <= 0 {
+            return Err(ContractError::InvalidStatusTransition);
+        }
+
+        let token_ids =
+            Self::collect_transferable_tokens(env.clone(), from.clone(), amount, filter)?;
+        for i in 0..token_ids.len() {
+            let token_id = token_ids.get(i).unwrap();
+            Self::transfer_token_internal(
+                env.clone(),
+                from.clone(),
+                to.clone(),
+                token_id,
+                false,
+                true,
+            )?;
+        }
+
+        Sep41TransferEvent { from, to, amount }.publish(&env);
+
+        Ok(())
+    }
+
+    fn burn_amount_internal(
+        env: Env,
+        from: Address,
+        amount: i128,
+    ) -> Result<(), ContractError> {
+        if amount <= 0 {
+            return Err(ContractError::InvalidStatusTransition);
+        }
+
+        let token_ids = Self::collect_retired_tokens(env.clone(), from.clone(), amount)?;
+        for i in 0..token_ids.len() {
+            let token_id = token_ids.get(i).unwrap();
+            Self::burn_token(env.clone(), token_id, from.clone())?;
+        }
+
+        Sep41BurnEvent { from, amount }.publish(&env);
+
+        Ok(())
+    }
+
+    // Walks the whole wallet a page of MAX_PAGE_SIZE tokens at a time. Under
+    // `Fifo` the walk stops as soon as the amount is covered; the other
+    // policies need every candidate before they can order them.
+    fn collect_transferable_tokens(
+        env: Env,
+        owner: Address,
+        amount: i128,
+        filter: Option<TokenFilter>,
+    ) -> Result<Vec<u32>, ContractError> {
+        let policy = Self::get_picking_policy(env.clone(), owner.clone());
+        let count = Self::owner_token_count(env.clone(), owner.clone());
+
+        let mut transferable = Vec::new(&env);
+        let mut covered: i128 = 0;
+        let mut start = 0;
+        while start < count && !(policy == PickingPolicy::Fifo && covered >= amount) {
+            let end = count.min(start + MAX_PAGE_SIZE);
+            for token_id in Self::owner_token_range(&env, &owner, start, end).iter() {
+                if policy == PickingPolicy::Fifo && covered >= amount {
+                    break;
+                }
+                let burned = Self::is_burned(env.clone(), token_id)?;
+                if burned {
+                    continue;
+                }
+                // Listed tokens are held back for their buyer.
+                let status = Self::get_status(env.clone(), token_id)?;
+                if status != AssetStatus::Issued {
+                    continue;
+                }
+                if let Some(filter) = &filter {
+                    if !Self::matches_filter(env.clone(), token_id, filter)? {
+                        continue;
+                    }
+                }
+                transferable.push_back(token_id);
+                if policy == PickingPolicy::Fifo {
+                    covered += Self::quantity_of(env.clone(), token_id)?;
+                }
+            }
+            start = end;
+        }
+
+        let ordered = Self::order_by_policy(env.clone(), transferable, policy)?;
+        Self::take_quantity(env, owner, ordered, amount)
+    }
+
+    fn matches_filter(
+        env: Env,
+        token_id: u32,
+        filter: &TokenFilter,
+    ) -> Result<bool, ContractError> {
+        let metadata = Self::get_metadata(env.clone(), token_id)?;
+
+        if let Some(min_vintage) = filter.min_vintage {
+            if metadata.vintage_year < min_vintage {
+                return Ok(false);
+            }
+        }
+        if let Some(max_vintage) = filter.max_vintage {
+            if metadata.vintage_year > max_vintage {
+                return Ok(false);
+            }
+        }
+        if let Some(project_id) = &filter.project_id {
+            if metadata.project_id != *project_id {
+                return Ok(false);
+            }
+        }
+        if let Some(methodology_id) = filter.methodology_id {
+            if metadata.methodology_id != methodology_id {
+                return Ok(false);
+            }
+        }
+        if let Some(country) = &filter.country {
+            if metadata.country != *country {
+                return Ok(false);
+            }
+        }
+        if let Some(min_quality_score) = filter.min_quality_score {
+            if Self::get_quality_score(env, token_id)? < min_quality_score {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    // Stable sort on the policy key; `Fifo` keeps wallet order.
+    fn order_by_policy(
+        env: Env,
+        token_ids: Vec<u32>,
+        policy: PickingPolicy,
+    ) -> Result<Vec<u32>, ContractError> {
+        if policy == PickingPolicy::Fifo {
+            return Ok(token_ids);
+        }
+
+        // Insertion by binary search keeps the keys sorted however many
+        // candidates the wallet holds. Ties keep wallet order through the
+        // position field.
+        let mut keyed: Vec<(i128, u32, u32)> = Vec::new(&env);
+        for (position, token_id) in token_ids.iter().enumerate() {
+            let key = match policy {
+                PickingPolicy::OldestVintageFirst => {
+                    Self::get_metadata(env.clone(), token_id)?.vintage_year as i128
+                }
+                _ => -Self::get_quality_score(env.clone(), token_id)?,
+            };
+            let entry = (key, position as u32, token_id);
+            let at = keyed.binary_search(entry).unwrap_or_else(|at| at);
+            keyed.insert(at, entry);
+        }
+
+        let mut ordered = Vec::new(&env);
+        for (_, _, token_id) in keyed.iter() {
+            ordered.push_back(token_id);
+        }
+        Ok(ordered)
+    }
+
+    fn collect_retired_tokens(
+        env: Env,
+        owner: Address,
+        amount: i128,
+    ) -> Result<Vec<u32>, ContractError> {
+        let count = Self::owner_token_count(env.clone(), owner.clone());
+
+        // Same paged walk as `collect_transferable_tokens` under `Fifo`.
+        let mut retired = Vec::new(&env);
+        let mut covered: i128 = 0;
+        let mut start = 0;
+        while start < count && covered < amount {
+            let end = count.min(start + MAX_PAGE_SIZE);
+            for token_id in Self::owner_token_range(&env, &owner, start, end).iter() {
+                if covered >= amount {
+                    break;
+                }
+                let burned = Self::is_burned(env.clone(), token_id)?;
+                if burned {
+                    continue;
+                }
+                let status = Self::get_status(env.clone(), token_id)?;
+                if status == AssetStatus::Retired {
+                    retired.push_back(token_id);
+                    covered += Self::quantity_of(env.clone(), token_id)?;
+                }
+            }
+            start = end;
+        }
+
+        Self::take_quantity(env, owner, retired, amount)
+    }
+
+    // Walks `candidates` in order until `amount` is covered, splitting the last
+    // token when only part of it is needed. The returned tokens sum to `amount`.
+    fn take_quantity(
+        env: Env,
+        owner: Address,
+        candidates: Vec<u32>,
+        amount: i128,
+    ) -> Result<Vec<u32>, ContractError> {
+        let mut selected = Vec::new(&env);
+        let mut remaining = amount;
+        for i in 0..candidates.len() {
+            if remaining == 0 {
+                break;
+            }
+            let token_id = candidates.get(i).unwrap();
+            let quantity = Self::quantity_of(env.clone(), token_id)?;
+            if quantity <= remaining {
+                selected.push_back(token_id);
+                remaining -= quantity;
+            } else {
+                let new_token_id =
+                    Self::split_internal(env.clone(), owner.clone(), token_id, remaining)?;
+                selected.push_back(new_token_id);
+                remaining = 0;
+            }
+        }
+
+        if remaining != 0 {
+            return Err(ContractError::TransferNotAllowed);
+        }
+
+        Ok(selected)
+    }
+
+    // ====================================================================
+    // Internal Helpers
+    // ====================================================================
+
+    // Shared burn for `burn_token` and `burn_token_from`; callers have
+    // already checked the RetirementTracker's authorization.
+    fn burn_token_internal(
+        env: Env,
+        token_id: u32,
+        from: Address,
+        retirement_tracker: Address,
+    ) -> Result<(), ContractError> {
+        Self::require_not_paused(&env, PauseScope::Retire)?;
+        Self::require_migrated(&env)?;
+
+        let owner = Self::owner_of(env.clone(), token_id)?;
+        if owner != from {
+            return Err(ContractError::NotOwner);
+        }
+
+        let burned: bool =
+            storage::read_persistent(&env, &DataKey::Burned(token_id)).unwrap_or(false);
+        if burned {
+            return Err(ContractError::TokenAlreadyBurned);
+        }
+
+        let status = Self::get_status(env.clone(), token_id)?;
+        if status == AssetStatus::Invalidated {
+            return Err(ContractError::StatusFrozen);
+        }
+        if status == AssetStatus::Listed {
+            return Err(ContractError::TokenListed);
+        }
+        if status != AssetStatus::Retired {
+            let metadata = Self::get_metadata(env.clone(), token_id)?;
+            if Self::issuance_state(&env, &metadata.project_id, metadata.vintage_year)
+                != IssuanceState::Active
+            {
+                return Err(ContractError::IssuanceFrozen);
+            }
+            Self::set_status_internal(
+                env.clone(),
+                token_id,
+                AssetStatus::Retired,
+                retirement_tracker.clone(),
+            )?;
+        }
+
+        Self::remove_token_from_owner(env.clone(), from, token_id)?;
+        Self::clear_token_approval(&env, token_id);
+        storage::write_persistent(&env, &DataKey::Burned(token_id), &true);
+        let quantity = Self::quantity_of(env.clone(), token_id)?;
+        Self::adjust_total(&env, DataKey::TotalSupply, -quantity);
+        Self::adjust_total(&env, DataKey::TotalRetired, quantity);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Owner(token_id));
+
+        Ok(())
+    }
+
+    fn require_role(env: &Env, caller: &Address, role: Role) -> Result<(), ContractError> {
+        caller.require_auth();
+        if !Self::has_role(env.clone(), role, caller.clone()) {
+            return Err(ContractError::NotAuthorized);
+        }
+        Ok(())
+    }
+
+    fn stored_status(env: &Env, token_id: u32) -> Result<AssetStatus, ContractError> {
+        storage::read_persistent(env, &DataKey::Status(token_id))
+            .ok_or(ContractError::TokenNotFound)
+    }
+
+    // The stricter of the project-wide and vintage-level states.
+    fn issuance_state(env: &Env, project_id: &String, vintage_year: u64) -> IssuanceState {
+        let project = Self::get_project_state(env.clone(), project_id.clone());
+        if project == IssuanceState::Invalidated {
+            return project;
+        }
+        let vintage = Self::get_vintage_state(env.clone(), project_id.clone(), vintage_year);
+        if vintage == IssuanceState::Active {
+            project
+        } else {
+            vintage
+        }
+    }
+
+    fn record_score(env: &Env, token_id: u32, submission: &ScoreSubmission) {
+        let index = Self::score_history_count(env.clone(), token_id);
+        let key = DataKey::Score(ScoreKey::History(token_id, index));
+        storage::write_persistent(env, &key, submission);
+        let count_key = DataKey::Score(ScoreKey::HistoryCount(token_id));
+        storage::write_persistent(env, &count_key, &(index + 1));
+    }
+
+    // Combines the latest reading of every still-registered oracle that is
+    // inside the staleness window. With no such reading the current score
+    // stands, so a lapse in oracle coverage never zeroes a credit.
+    fn aggregate_quality_score(
+        env: &Env,
+        token_id: u32,
+        updated_by: Address,
+    ) -> Result<i128, ContractError> {
+        let old_score = Self::get_quality_score(env.clone(), token_id)?;
+        let config = Self::get_score_config(env.clone());
+        let now = env.ledger().timestamp();
+
+        let sources: Vec<Address> =
+            storage::read_persistent(env, &DataKey::Score(ScoreKey::Sources(token_id)))
+                .unwrap_or(Vec::new(env));
+        let mut scores: Vec<i128> = Vec::new(env);
+        let mut weighted_sum: i128 = 0;
+        let mut total_weight: i128 = 0;
+        for source in sources.iter() {
+            let weight = Self::get_oracle_weight(env.clone(), source.clone());
+            if weight == 0 {
+                continue;
+            }
+            let Some(submission) = Self::get_latest_score(env.clone(), token_id, source) else {
+                continue;
+            };
+            if now.saturating_sub(submission.observed_at) > config.max_age {
+                continue;
+            }
+
+            // Kept sorted for the median.
+            let mut position = 0;
+            while position < scores.len() && scores.get(position).unwrap() < submission.score {
+                position += 1;
+            }
+            scores.insert(position, submission.score);
+            weighted_sum = submission
+                .score
+                .checked_mul(weight as i128)
+                .and_then(|weighted| weighted_sum.checked_add(weighted))
+                .ok_or(ContractError::ScoreOverflow)?;
+            total_weight += weight as i128;
+        }
+
+        if scores.is_empty() {
+            return Ok(old_score);
+        }
+        let new_score = match config.method {
+            ScoreAggregation::Median => {
+                let mid = scores.len() / 2;
+                if scores.len() % 2 == 1 {
+                    scores.get(mid).unwrap()
+                } else {
+                    scores
+                        .get(mid - 1)
+                        .unwrap()
+                        .checked_add(scores.get(mid).unwrap())
+                        .ok_or(ContractError::ScoreOverflow)?
+                        / 2
+                }
+            }
+            ScoreAggregation::WeightedMean => weighted_sum / total_weight,
+        };
+
+        if new_score != old_score {
+            storage::write_persistent(env, &DataKey::QualityScore(token_id), &new_score);
+            QualityScoreUpdatedEvent {
+                token_id,
+                old_score,
+                new_score,
+                updated_by,
+            }
+            .publish(env);
+        }
+
+        Ok(new_score)
+    }
+
+    fn require_not_paused(env: &Env, scope: PauseScope) -> Result<(), ContractError> {
+        if Self::is_paused(env.clone(), PauseScope::All) || Self::is_paused(env.clone(), scope) {
+            return Err(ContractError::ContractPaused);
+        }
+        Ok(())
+    }
+
+    // Token writes wait for `migrate` to finish, so the batched index pass
+    // never sees tokens created or moved after it started.
+    fn require_migrated(env: &Env) -> Result<(), ContractError> {
+        if Self::get_data_version(env.clone()) < storage::DATA_VERSION {
+            return Err(ContractError::MigrationPending);
+        }
+        Ok(())
+    }
+
+    fn grant_all_roles(env: &Env, account: &Address) {
+        for role in ALL_ROLES {
+            storage::write_persistent(env, &DataKey::Role(role, account.clone()), &true);
+            RoleGrantedEvent {
+                role,
+                account: account.clone(),
+                granted_by: account.clone(),
+            }
+            .publish(env);
+        }
+    }
+
+    fn move_roles(env: &Env, from: &Address, to: &Address) {
+        for role in ALL_ROLES {
+            if !Self::has_role(env.clone(), role, from.clone()) {
+                continue;
+            }
+            env.storage()
+                .persistent()
+                .remove(&DataKey::Role(role, from.clone()));
+            RoleRevokedEvent {
+                role,
+                account: from.clone(),
+                revoked_by: to.clone(),
+            }
+            .publish(env);
+
+            if Self::has_role(env.clone(), role, to.clone()) {
+                continue;
+            }
+            storage::write_persistent(env, &DataKey::Role(role, to.clone()), &true);
+            RoleGrantedEvent {
+                role,
+                account: to.clone(),
+                granted_by: to.clone(),
+            }
+            .publish(env);
+        }
     }
 
-    fn transfer_token_internal(
+    fn set_status_internal(
         env: Env,
-        from: Address,
-        to: Address,
         token_id: u32,
-        require_auth: bool,
+        new_status: AssetStatus,
+        changed_by: Address,
     ) -> Result<(), ContractError> {
-        if require_auth {
-            from.require_auth();
+        let current = Self::stored_status(&env, token_id)?;
+        if current == new_status {
+            return Ok(());
         }
 
-        let owner = Self::owner_of(env.clone(), token_id)?;
-        if owner != from {
-            return Err(ContractError::NotOwner);
+        // Leaving `Listed` for any reason withdraws the offer.
+        if current == AssetStatus::Listed {
+            if let Some(listing) = Self::get_listing(env.clone(), token_id) {
+                env.storage()
+                    .persistent()
+                    .remove(&DataKey::Listing(token_id));
+                DelistedEvent {
+                    token_id,
+                    seller: listing.seller,
+                }
+                .publish(&env);
+            }
         }
 
-        let burned = Self::is_burned(env.clone(), token_id)?;
-        if burned {
-            return Err(ContractError::TokenAlreadyBurned);
-        }
+        storage::write_persistent(&env, &DataKey::Status(token_id), &new_status);
 
-        let status = Self::get_status(env.clone(), token_id)?;
-        if status != AssetStatus::Issued && status != AssetStatus::Listed {
-            return Err(ContractError::TransferNotAllowed);
+        StatusChangeEvent {
+            token_id,
+            old_status: Some(current),
+            new_status,
+            changed_by,
         }
+        .publish(&env);
 
-        if !Self::before_transfer(env.clone(), from.clone(), to.clone(), token_id)? {
-            return Err(ContractError::ComplianceFailed);
-        }
+        Ok(())
+    }
 
-        Self::remove_token_from_owner(env.clone(), from.clone(), token_id)?;
-        Self::add_token_to_owner(env.clone(), to.clone(), token_id);
-        env.storage()
-            .persistent()
-            .set(&DataKey::Owner(token_id), &to);
+    fn set_project_state(
+        env: Env,
+        caller: Address,
+        project_id: String,
+        state: IssuanceState,
+    ) -> Result<(), ContractError> {
+        Self::require_role(&env, &caller, Role::StatusManager)?;
 
-        TransferEvent {
-            token_id,
-            from: from.clone(),
-            to: to.clone(),
+        let current = Self::get_project_state(env.clone(), project_id.clone());
+        if current == IssuanceState::Invalidated {
+            return Err(ContractError::StatusFrozen);
+        }
+        if current == state {
+            return Ok(());
         }
-        .publish(&env);
 
-        let retirement_tracker = Self::get_retirement_tracker(env.clone())?;
-        if to == retirement_tracker {
-            Self::set_status_internal(env, token_id, AssetStatus::Retired, from)?;
+        let key = DataKey::ProjectState(project_id.clone());
+        if state == IssuanceState::Active {
+            env.storage().persistent().remove(&key);
+        } else {
+            storage::write_persistent(&env, &key, &state);
+        }
+
+        ProjectStateEvent {
+            project_id,
+            state,
+            changed_by: caller,
         }
+        .publish(&env);
 
         Ok(())
     }
 
-    fn transfer_amount_internal(
+    fn set_vintage_state(
         env: Env,
-        from: Address,
-        to: Address,
-        amount: i128,
+        caller: Address,
+        project_id: String,
+        vintage_year: u64,
+        state: IssuanceState,
     ) -> Result<(), ContractError> {
-        if amount <= 0 {
-            return Err(ContractError::InvalidStatusTransition);
+        Self::require_role(&env, &caller, Role::StatusManager)?;
+
+        let current = Self::get_vintage_state(env.clone(), project_id.clone(), vintage_year);
+        if current == IssuanceState::Invalidated {
+            return Err(ContractError::StatusFrozen);
+        }
+        if current == state {
+            return Ok(());
         }
 
-        let token_ids = Self::collect_transferable_tokens(env.clone(), from.clone(), amount)?;
-        for i in 0..token_ids.len() {
-            let token_id = token_ids.get(i).unwrap();
-            Self::transfer_token_internal(env.clone(), from.clone(), to.clone(), token_id, false)?;
+        let key = DataKey::VintageState(project_id.clone(), vintage_year);
+        if state == IssuanceState::Active {
+            env.storage().persistent().remove(&key);
+        } else {
+            storage::write_persistent(&env, &key, &state);
         }
 
-        Sep41TransferEvent { from, to, amount }.publish(&env);
+        VintageStateEvent {
+            project_id,
+            vintage_year,
+            state,
+            changed_by: caller,
+        }
+        .publish(&env);
 
         Ok(())
     }
 
-    fn burn_amount_internal(
-        env: Env,
-        from: Address,
-        amount: i128,
-    ) -> Result<(), ContractError> {
-        if amount <= 0 {
-            return Err(ContractError::InvalidStatusTransition);
+    fn validate_metadata(env: Env, metadata: &CarbonAssetMetadata) -> Result<(), ContractError> {
+        if metadata.country.len() != 2 || metadata.sdg_flags >> MAX_SDG != 0 {
+            return Err(ContractError::InvalidMetadata);
+        }
+        if Self::issuance_state(&env, &metadata.project_id, metadata.vintage_year)
+            != IssuanceState::Active
+        {
+            return Err(ContractError::IssuanceFrozen);
         }
 
-        let token_ids = Self::collect_retired_tokens(env.clone(), from.clone(), amount)?;
-        for i in 0..token_ids.len() {
-            let token_id = token_ids.get(i).unwrap();
-            Self::burn_token(env.clone(), token_id, from.clone())?;
+        if let Some(library) = Self::get_methodology_library(env.clone()) {
+            let mut args = Vec::new(&env);
+            args.push_back(metadata.methodology_id.into_val(&env));
+            let valid: bool =
+                env.invoke_contract(&library, &Symbol::new(&env, "is_valid_methodology"), args);
+            if !valid {
+                return Err(ContractError::MethodologyNotValid);
+            }
         }
 
-        Sep41BurnEvent { from, amount }.publish(&env);
+        if let Some(registry) = Self::get_project_registry(env.clone()) {
+            let mut args = Vec::new(&env);
+            args.push_back(metadata.project_id.into_val(&env));
+            // ProjectRegistry::get_project_owner errors for unknown projects.
+            let owner = env.try_invoke_contract::<Address, soroban_sdk::Error>(
+                &registry,
+                &Symbol::new(&env, "get_project_owner"),
+                args,
+            );
+            if !matches!(owner, Ok(Ok(_))) {
+                return Err(ContractError::ProjectNotRegistered);
+            }
+        }
 
         Ok(())
     }
 
-    fn collect_transferable_tokens(
+    // Replenishment rate of the configured buffer pool, in basis points.
+    fn buffer_rate(env: Env) -> i128 {
+        match Self::get_buffer_pool(env.clone()) {
+            Some(pool) => {
+                let rate: i64 = env.invoke_contract(
+                    &pool,
+                    &Symbol::new(&env, "get_replenishment_rate"),
+                    Vec::new(&env),
+                );
+                rate as i128
+            }
+            None => 0,
+        }
+    }
+
+    // Mints the withheld share of an issuance to the buffer pool and records
+    // it in the pool's custody ledger.
+    fn deposit_to_buffer(
         env: Env,
-        owner: Address,
-        amount: i128,
-    ) -> Result<Vec<u32>, ContractError> {
-        let tokens: Vec<u32> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::OwnerTokens(owner))
-            .unwrap_or(Vec::new(&env));
+        source_token_id: Option<u32>,
+        batch_id: Option<u32>,
+        metadata: &CarbonAssetMetadata,
+        quantity: i128,
+        serial: Option<TokenSerial>,
+    ) -> Result<u32, ContractError> {
+        let pool = Self::get_buffer_pool(env.clone()).ok_or(ContractError::BufferPoolNotSet)?;
+        let buffer_token_id = Self::create_token(
+            env.clone(),
+            pool.clone(),
+            metadata,
+            quantity,
+            AssetStatus::Issued,
+            0,
+            serial,
+        )?;
+        Self::adjust_total(&env, DataKey::TotalSupply, quantity);
 
-        let mut transferable = Vec::new(&env);
-        for i in 0..tokens.len() {
-            let token_id = tokens.get(i).unwrap();
-            let burned = Self::is_burned(env.clone(), token_id)?;
-            if burned {
-                continue;
+        let mut args = Vec::new(&env);
+        args.push_back(env.current_contract_address().into_val(&env));
+        args.push_back(buffer_token_id.into_val(&env));
+        args.push_back(metadata.project_id.into_val(&env));
+        env.invoke_contract::<()>(&pool, &Symbol::new(&env, "deposit"), args);
+
+        BufferContributionEvent {
+            token_id: source_token_id,
+            batch_id,
+            buffer_token_id,
+            buffer_pool: pool,
+            quantity,
+        }
+        .publish(&env);
+
+        Ok(buffer_token_id)
+    }
+
+    fn reverse_token(
+        env: &Env,
+        caller: &Address,
+        pool: &Address,
+        token_id: u32,
+        buffer_token_ids: &Vec<u32>,
+    ) -> Result<(), ContractError> {
+        if Self::is_burned(env.clone(), token_id)? {
+            return Err(ContractError::TokenAlreadyBurned);
+        }
+        let status = Self::stored_status(env, token_id)?;
+        if status == AssetStatus::Retired || status == AssetStatus::Invalidated {
+            return Err(ContractError::StatusFrozen);
+        }
+
+        let holder = Self::owner_of(env.clone(), token_id)?;
+        let metadata = Self::get_metadata(env.clone(), token_id)?;
+        let mut remaining = Self::quantity_of(env.clone(), token_id)?;
+        Self::set_status_internal(
+            env.clone(),
+            token_id,
+            AssetStatus::Invalidated,
+            caller.clone(),
+        )?;
+
+        // The token stays with its holder as a record but no longer counts
+        // towards their balance or the supply.
+        Self::adjust_balance(env.clone(), holder.clone(), -remaining);
+        Self::adjust_total(env, DataKey::TotalSupply, -remaining);
+
+        // A reversed buffer credit has no buyer to compensate.
+        if holder == *pool {
+            return Ok(());
+        }
+
+        // Replacements go to the holder, even when that is a vault such as
+        // CreditPool, which re-records them through `replace_reversed`.
+        let mut replacements = Self::get_replacements(env.clone(), token_id);
+        for buffer_token_id in buffer_token_ids.iter() {
+            if remaining == 0 {
+                break;
             }
-            let status = Self::get_status(env.clone(), token_id)?;
-            if status == AssetStatus::Issued || status == AssetStatus::Listed {
-                transferable.push_back(token_id);
+            if !Self::can_replace(env, pool, buffer_token_id, &metadata)? {
+                continue;
             }
-            if transferable.len() as i128 == amount {
-                break;
+
+            let available = Self::quantity_of(env.clone(), buffer_token_id)?;
+            let (replacement_token_id, quantity) = if available > remaining {
+                let split_id =
+                    Self::split_internal(env.clone(), pool.clone(), buffer_token_id, remaining)?;
+                (split_id, remaining)
+            } else {
+                (buffer_token_id, available)
+            };
+
+            Self::transfer_token_internal(
+                env.clone(),
+                pool.clone(),
+                holder.clone(),
+                replacement_token_id,
+                false,
+                false,
+            )?;
+
+            replacements.push_back(replacement_token_id);
+            storage::write_persistent(
+                env,
+                &DataKey::ReplacedToken(replacement_token_id),
+                &token_id,
+            );
+
+            let mut args = Vec::new(env);
+            args.push_back(env.current_contract_address().into_val(env));
+            args.push_back(buffer_token_id.into_val(env));
+            args.push_back(replacement_token_id.into_val(env));
+            args.push_back(token_id.into_val(env));
+            env.invoke_contract::<()>(pool, &Symbol::new(env, "withdraw_to_replace"), args);
+
+            ReversalEvent {
+                token_id,
+                holder: holder.clone(),
+                replacement_token_id,
+                buffer_token_id,
+                quantity,
             }
+            .publish(env);
+
+            remaining -= quantity;
         }
 
-        if transferable.len() as i128 != amount {
-            return Err(ContractError::TransferNotAllowed);
+        if remaining > 0 {
+            return Err(ContractError::InsufficientBuffer);
         }
+        storage::write_persistent(env, &DataKey::Replacements(token_id), &replacements);
 
-        Ok(transferable)
+        Ok(())
     }
 
-    fn collect_retired_tokens(
+    fn can_replace(
+        env: &Env,
+        pool: &Address,
+        buffer_token_id: u32,
+        reversed: &CarbonAssetMetadata,
+    ) -> Result<bool, ContractError> {
+        if Self::is_burned(env.clone(), buffer_token_id)? {
+            return Ok(false);
+        }
+        if Self::owner_of(env.clone(), buffer_token_id)? != *pool {
+            return Ok(false);
+        }
+        let status = Self::get_status(env.clone(), buffer_token_id)?;
+        if status != AssetStatus::Issued && status != AssetStatus::Listed {
+            return Ok(false);
+        }
+
+        let metadata = Self::get_metadata(env.clone(), buffer_token_id)?;
+        Ok(metadata.credit_type == reversed.credit_type
+            && metadata.vintage_year >= reversed.vintage_year)
+    }
+
+    fn create_token(
         env: Env,
         owner: Address,
-        amount: i128,
-    ) -> Result<Vec<u32>, ContractError> {
-        let tokens: Vec<u32> = env
+        metadata: &CarbonAssetMetadata,
+        quantity: i128,
+        status: AssetStatus,
+        quality_score: i128,
+        serial: Option<TokenSerial>,
+    ) -> Result<u32, ContractError> {
+        let token_id: u32 = env
             .storage()
-            .persistent()
-            .get(&DataKey::OwnerTokens(owner))
-            .unwrap_or(Vec::new(&env));
+            .instance()
+            .get(&DataKey::NextTokenId)
+            .ok_or(ContractError::NotInitialized)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::NextTokenId, &(token_id + 1));
 
-        let mut retired = Vec::new(&env);
-        for i in 0..tokens.len() {
-            let token_id = tokens.get(i).unwrap();
-            let burned = Self::is_burned(env.clone(), token_id)?;
-            if burned {
-                continue;
+        storage::write_persistent(&env, &DataKey::Owner(token_id), &owner);
+        storage::write_persistent(&env, &DataKey::Quantity(token_id), &quantity);
+        Self::add_token_to_owner(env.clone(), owner, token_id);
+        storage::write_persistent(&env, &DataKey::Metadata(token_id), metadata);
+        storage::write_persistent(&env, &DataKey::Status(token_id), &status);
+        storage::write_persistent(&env, &DataKey::QualityScore(token_id), &quality_score);
+        storage::write_persistent(&env, &DataKey::Burned(token_id), &false);
+        if let Some(serial) = serial {
+            storage::write_persistent(&env, &DataKey::TokenSerial(token_id), &serial);
+        }
+        Self::index_token(&env, metadata, token_id);
+
+        Ok(token_id)
+    }
+
+    fn index_token(env: &Env, metadata: &CarbonAssetMetadata, token_id: u32) {
+        let project_key = DataKey::ProjectTokenCount(metadata.project_id.clone());
+        let position: u32 = storage::read_persistent(env, &project_key).unwrap_or(0);
+        storage::write_persistent(
+            env,
+            &DataKey::ProjectToken(metadata.project_id.clone(), position),
+            &token_id,
+        );
+        storage::write_persistent(env, &project_key, &(position + 1));
+
+        let vintage_key =
+            DataKey::VintageTokenCount(metadata.project_id.clone(), metadata.vintage_year);
+        let position: u32 = storage::read_persistent(env, &vintage_key).unwrap_or(0);
+        storage::write_persistent(
+            env,
+            &DataKey::VintageToken(metadata.project_id.clone(), metadata.vintage_year, position),
+            &token_id,
+        );
+        storage::write_persistent(env, &vintage_key, &(position + 1));
+    }
+
+    fn index_page(
+        env: &Env,
+        count: u32,
+        cursor: u32,
+        limit: u32,
+        entry: impl Fn(u32) -> DataKey,
+    ) -> TokenPage {
+        let end = count.min(cursor.saturating_add(limit.min(MAX_PAGE_SIZE)));
+        let mut token_ids = Vec::new(env);
+        for position in cursor..end {
+            let token_id: u32 = storage::read_persistent(env, &entry(position)).unwrap();
+            if env.storage().persistent().has(&DataKey::Metadata(token_id)) {
+                token_ids.push_back(token_id);
+            }
+        }
+
+        TokenPage {
+            token_ids,
+            next_cursor: if end < count { Some(end) } else { None },
+        }
+    }
+
+    fn adjust_total(env: &Env, key: DataKey, delta: i128) {
+        let total: i128 = env.storage().instance().get(&key).unwrap_or(0);
+        env.storage().instance().set(&key, &(total + delta));
+    }
+
+    // Indexes the next batch of pre-v3 tokens. The first call fixes the
+    // upper token id, so the pass covers exactly the tokens that existed when
+    // it started. Returns true once every one of them has been covered.
+    fn migrate_indexes(env: &Env) -> Result<bool, ContractError> {
+        let cursor: Option<(u32, u32)> = env.storage().instance().get(&DataKey::MigrationCursor);
+        let (start, last) = match cursor {
+            Some(cursor) => cursor,
+            None => {
+                let next_token_id: u32 = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::NextTokenId)
+                    .ok_or(ContractError::NotInitialized)?;
+                (1, next_token_id)
             }
-            let status = Self::get_status(env.clone(), token_id)?;
-            if status == AssetStatus::Retired {
-                retired.push_back(token_id);
+        };
+        let end = last.min(start.saturating_add(storage::MIGRATION_BATCH_SIZE));
+
+        for token_id in start..end {
+            Self::migrate_legacy_token(env, token_id);
+
+            // Tokens merged away have no metadata left and are not indexed.
+            let metadata: Option<CarbonAssetMetadata> =
+                storage::read_persistent(env, &DataKey::Metadata(token_id));
+            let Some(metadata) = metadata else {
+                continue;
+            };
+            Self::index_token(env, &metadata, token_id);
+
+            let quantity = Self::quantity_of(env.clone(), token_id)?;
+            let burned: bool =
+                storage::read_persistent(env, &DataKey::Burned(token_id)).unwrap_or(false);
+            if burned {
+                Self::adjust_total(env, DataKey::TotalRetired, quantity);
+            } else {
+                Self::adjust_total(env, DataKey::TotalSupply, quantity);
             }
-            if retired.len() as i128 == amount {
-                break;
+
+            let owner: Option<Address> = storage::read_persistent(env, &DataKey::Owner(token_id));
+            if let Some(owner) = owner {
+                Self::migrate_owner_tokens(env, &owner);
             }
         }
 
-        if retired.len() as i128 != amount {
-            return Err(ContractError::TransferNotAllowed);
+        if end < last {
+            env.storage()
+                .instance()
+                .set(&DataKey::MigrationCursor, &(end, last));
+            return Ok(false);
+        }
+        env.storage().instance().remove(&DataKey::MigrationCursor);
+        Ok(true)
+    }
+
+    // Rewrites a baseline (v0) token as one tonne with the metadata fields
+    // added since left empty. Tokens written by later builds already have
+    // the new layout and are left alone.
+    fn migrate_legacy_token(env: &Env, token_id: u32) {
+        let key = DataKey::Metadata(token_id);
+        let stored: Option<Map<Symbol, Val>> = storage::read_persistent(env, &key);
+        let is_legacy = stored
+            .map(|fields| !fields.contains_key(Symbol::new(env, "registry")))
+            .unwrap_or(false);
+        if !is_legacy {
+            return;
         }
 
-        Ok(retired)
+        let old: CarbonAssetMetadataV0 = storage::read_persistent(env, &key).unwrap();
+        let empty = String::from_str(env, "");
+        let metadata = CarbonAssetMetadata {
+            project_id: old.project_id,
+            vintage_year: old.vintage_year,
+            methodology_id: old.methodology_id,
+            geo_hash: old.geo_hash,
+            registry: empty.clone(),
+            registry_serial: empty.clone(),
+            issuance_date: 0,
+            country: empty.clone(),
+            project_type: empty.clone(),
+            credit_type: CreditType::Avoidance,
+            sdg_flags: 0,
+            corresponding_adjustment: CorrespondingAdjustment::NotApplicable,
+            verification_uri: empty,
+        };
+        storage::write_persistent(env, &key, &metadata);
+        storage::write_persistent(env, &DataKey::Quantity(token_id), &UNITS_PER_TONNE);
+
+        // Baseline balances were token counts; burned tokens have no owner.
+        let owner: Option<Address> = storage::read_persistent(env, &DataKey::Owner(token_id));
+        if let Some(owner) = owner {
+            Self::adjust_balance(env.clone(), owner, UNITS_PER_TONNE);
+        }
     }
 
-    // ====================================================================
-    // Internal Helpers
-    // ====================================================================
+    // Moves a pre-v3 `OwnerTokens` list into per-token entries, appending
+    // after any entries the owner already has and re-pointing `TokenIndex`.
+    fn migrate_owner_tokens(env: &Env, owner: &Address) {
+        let legacy_key = DataKey::OwnerTokens(owner.clone());
+        let tokens: Option<Vec<u32>> = storage::read_persistent(env, &legacy_key);
+        let Some(tokens) = tokens else {
+            return;
+        };
 
-    fn set_status_internal(
+        let mut count = Self::owner_token_count(env.clone(), owner.clone());
+        for token_id in tokens.iter() {
+            storage::write_persistent(env, &DataKey::OwnerToken(owner.clone(), count), &token_id);
+            storage::write_persistent(env, &DataKey::TokenIndex(token_id), &count);
+            count += 1;
+        }
+        storage::write_persistent(env, &DataKey::OwnerTokenCount(owner.clone()), &count);
+        env.storage().persistent().remove(&legacy_key);
+    }
+
+    // Moves `amount` out of `token_id` into a freshly minted token held by the
+    // same owner, copying metadata, status, quality score and serial.
+    fn split_internal(
         env: Env,
+        owner: Address,
         token_id: u32,
-        new_status: AssetStatus,
-        changed_by: Address,
-    ) -> Result<(), ContractError> {
-        let current = Self::get_status(env.clone(), token_id)?;
-        if current == new_status {
-            return Ok(());
+        amount: i128,
+    ) -> Result<u32, ContractError> {
+        let quantity = Self::quantity_of(env.clone(), token_id)?;
+        if amount <= 0 || amount >= quantity {
+            return Err(ContractError::InvalidQuantity);
         }
 
-        env.storage()
-            .persistent()
-            .set(&DataKey::Status(token_id), &new_status);
+        let metadata = Self::get_metadata(env.clone(), token_id)?;
+        let status = Self::stored_status(&env, token_id)?;
+        let quality_score = Self::get_quality_score(env.clone(), token_id)?;
+        let serial = Self::get_token_serial(env.clone(), token_id);
 
-        StatusChangeEvent {
+        storage::write_persistent(&env, &DataKey::Quantity(token_id), &(quantity - amount));
+        Self::adjust_balance(env.clone(), owner.clone(), -amount);
+        Self::clear_token_approval(&env, token_id);
+
+        let new_token_id = Self::create_token(
+            env.clone(),
+            owner.clone(),
+            &metadata,
+            amount,
+            status,
+            quality_score,
+            serial,
+        )?;
+
+        SplitEvent {
             token_id,
-            old_status: Some(current),
-            new_status,
-            changed_by,
+            new_token_id,
+            owner,
+            quantity: amount,
         }
         .publish(&env);
 
-        Ok(())
+        Ok(new_token_id)
     }
 
-    fn add_token_to_owner(env: Env, owner: Address, token_id: u32) {
-        let mut tokens: Vec<u32> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::OwnerTokens(owner.clone()))
-            .unwrap_or(Vec::new(&env));
+    fn adjust_balance(env: Env, owner: Address, delta: i128) {
+        let balance = Self::balance_of(env.clone(), owner.clone()) + delta;
+        if balance == 0 {
+            env.storage().persistent().remove(&DataKey::Balance(owner));
+        } else {
+            storage::write_persistent(&env, &DataKey::Balance(owner), &balance);
+        }
+    }
 
-        tokens.push_back(token_id);
-        let index = tokens.len() - 1;
-        env.storage()
-            .persistent()
-            .set(&DataKey::OwnerTokens(owner), &tokens);
-        env.storage()
-            .persistent()
-            .set(&DataKey::TokenIndex(token_id), &index);
+    fn owner_token_range(env: &Env, owner: &Address, start: u32, end: u32) -> Vec<u32> {
+        let mut tokens = Vec::new(env);
+        for index in start..end {
+            let token_id: u32 =
+                storage::read_persistent(env, &DataKey::OwnerToken(owner.clone(), index)).unwrap();
+            tokens.push_back(token_id);
+        }
+        tokens
+    }
+
+    fn add_token_to_owner(env: Env, owner: Address, token_id: u32) {
+        let index = Self::owner_token_count(env.clone(), owner.clone());
+        storage::write_persistent(&env, &DataKey::OwnerToken(owner.clone(), index), &token_id);
+        storage::write_persistent(&env, &DataKey::OwnerTokenCount(owner.clone()), &(index + 1));
+        storage::write_persistent(&env, &DataKey::TokenIndex(token_id), &index);
+
+        let quantity: i128 =
+            storage::read_persistent(&env, &DataKey::Quantity(token_id)).unwrap_or(0);
+        Self::adjust_balance(env, owner, quantity);
     }
 
     fn remove_token_from_owner(
@@ -790,45 +3111,46 @@ impl CarbonAsset {
         owner: Address,
         token_id: u32,
     ) -> Result<(), ContractError> {
-        let mut tokens: Vec<u32> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::OwnerTokens(owner.clone()))
-            .ok_or(ContractError::TokenNotFound)?;
-
-        let index: u32 = env
-            .storage()
-            .persistent()
-            .get(&DataKey::TokenIndex(token_id))
+        let count = Self::owner_token_count(env.clone(), owner.clone());
+        let index: u32 = storage::read_persistent(&env, &DataKey::TokenIndex(token_id))
             .ok_or(ContractError::TokenNotFound)?;
 
-        if tokens.len() == 0 {
+        if count == 0 {
             return Err(ContractError::TokenNotFound);
         }
 
-        let last_index = tokens.len() - 1;
-        let last_token = tokens.get(last_index).ok_or(ContractError::TokenNotFound)?;
-
+        // Swap the last entry into the freed position so the list stays dense.
+        let last_index = count - 1;
         if index != last_index {
-            tokens.set(index, last_token);
-            env.storage()
-                .persistent()
-                .set(&DataKey::TokenIndex(last_token), &index);
+            let last_token: u32 =
+                storage::read_persistent(&env, &DataKey::OwnerToken(owner.clone(), last_index))
+                    .ok_or(ContractError::TokenNotFound)?;
+            storage::write_persistent(
+                &env,
+                &DataKey::OwnerToken(owner.clone(), index),
+                &last_token,
+            );
+            storage::write_persistent(&env, &DataKey::TokenIndex(last_token), &index);
         }
 
-        tokens.pop_back();
-        if tokens.len() == 0 {
-            env.storage().persistent().remove(&DataKey::OwnerTokens(owner));
-        } else {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::OwnerToken(owner.clone(), last_index));
+        if last_index == 0 {
             env.storage()
                 .persistent()
-                .set(&DataKey::OwnerTokens(owner), &tokens);
+                .remove(&DataKey::OwnerTokenCount(owner.clone()));
+        } else {
+            storage::write_persistent(&env, &DataKey::OwnerTokenCount(owner.clone()), &last_index);
         }
 
         env.storage()
             .persistent()
             .remove(&DataKey::TokenIndex(token_id));
 
+        let quantity = Self::quantity_of(env.clone(), token_id)?;
+        Self::adjust_balance(env, owner, -quantity);
+
         Ok(())
     }
 
@@ -839,11 +3161,8 @@ impl CarbonAsset {
         amount: i128,
     ) -> Result<AllowanceData, ContractError> {
         let key = DataKey::Allowance(from, spender);
-        let mut allowance = env
-            .storage()
-            .persistent()
-            .get::<DataKey, AllowanceData>(&key)
-            .unwrap_or(AllowanceData {
+        let mut allowance =
+            storage::read_persistent::<AllowanceData>(&env, &key).unwrap_or(AllowanceData {
                 amount: 0,
                 live_until_ledger: 0,
             });
@@ -1,4 +1,19 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, Address, BytesN, Env, IntoVal, String, TryFromVal, Val};
+
+use crate::types::{PauseScope, Role};
+
+const DAY_IN_LEDGERS: u32 = 17280; // Approximately 1 day worth of ledgers (5s per ledger)
+const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS; // 30 days
+const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+const PERSISTENT_BUMP_AMOUNT: u32 = 120 * DAY_IN_LEDGERS; // 120 days
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - 30 * DAY_IN_LEDGERS;
+
+// Storage layout version written by this build. Bump it together with a
+// matching step in `CarbonAsset::migrate` whenever the layout changes.
+pub const DATA_VERSION: u32 = 3;
+
+// Tokens re-indexed per `migrate` call while moving a deployment to v3.
+pub const MIGRATION_BATCH_SIZE: u32 = 25;
 
 #[derive(Clone)]
 #[contracttype]
@@ -12,12 +27,108 @@ pub enum DataKey {
     RegulatoryCheck,
     HostJurisdiction,
     Oracle,
+    BufferPool,
+    MethodologyLibrary,
+    ProjectRegistry,
     Owner(u32),
+    // Pre-v3 layout holding an owner's whole token list in one entry. Only
+    // read by `migrate`, which moves it into `OwnerToken` entries.
     OwnerTokens(Address),
+    // Indexes keep one entry per token so they never approach the ledger
+    // entry size limit, however many tokens they cover.
+    OwnerTokenCount(Address),
+    OwnerToken(Address, u32),
     TokenIndex(u32),
+    ProjectTokenCount(String),
+    ProjectToken(String, u32),
+    VintageTokenCount(String, u64),
+    VintageToken(String, u64, u32),
+    TotalSupply,
+    TotalRetired,
+    // (next token id, first id past the pass) while `migrate` runs in batches.
+    MigrationCursor,
     Allowance(Address, Address),
     Metadata(u32),
     Status(u32),
     QualityScore(u32),
     Burned(u32),
+    Quantity(u32),
+    Balance(Address),
+    NextBatchId,
+    Batch(u32),
+    TokenSerial(u32),
+    SerialPrefixEnd(String),
+    PickingPolicy(Address),
+    ApprovalNonce,
+    PendingTransfer(BytesN<32>),
+    DataVersion,
+    Role(Role, Address),
+    PendingAdmin,
+    Paused(PauseScope),
+    ProjectState(String),
+    VintageState(String, u64),
+    Replacements(u32),
+    ReplacedToken(u32),
+    Listing(u32),
+    TokenApproval(u32),
+    OperatorApproval(Address, Address),
+    Score(ScoreKey),
+}
+
+// Quality score oracle keys, nested under `DataKey::Score` because a
+// contract type enum is limited to 50 variants.
+#[derive(Clone)]
+#[contracttype]
+pub enum ScoreKey {
+    OracleWeight(Address),
+    OracleCount,
+    Config,
+    Sources(u32),
+    Latest(u32, Address),
+    HistoryCount(u32),
+    History(u32, u32),
+}
+
+/// Extend the TTL of instance storage
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+/// Extend the TTL of a persistent entry, if it exists
+pub fn extend_persistent_ttl(env: &Env, key: &DataKey) {
+    if env.storage().persistent().has(key) {
+        env.storage().persistent().extend_ttl(
+            key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+}
+
+// Every persistent read and write goes through these helpers so that live
+// entries, and the instance holding the contract code, never drift towards
+// archival while they are still being used.
+pub fn read_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        env.storage().persistent().extend_ttl(
+            key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+        extend_instance_ttl(env);
+    }
+    value
+}
+
+pub fn write_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage().persistent().extend_ttl(
+        key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+    extend_instance_ttl(env);
 }
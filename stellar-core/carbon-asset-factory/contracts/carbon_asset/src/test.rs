@@ -1,9 +1,36 @@
 #![cfg(test)]
 
 use super::{CarbonAsset, CarbonAssetClient};
-use crate::types::{AssetStatus, CarbonAssetMetadata};
-use soroban_sdk::testutils::Address as _;
-use soroban_sdk::{Address, BytesN, Env, String};
+use crate::errors::ContractError;
+use crate::storage::DataKey;
+use crate::types::{
+    AssetStatus, CarbonAssetMetadata, CarbonAssetMetadataV0, CorrespondingAdjustment,
+    CreditContext, CreditType, IssuanceState, OperationType, PauseScope, PickingPolicy, Role,
+    ScoreAggregation, SerialRange, TokenFilter, TokenSerial, ValidationResult, MAX_PAGE_SIZE,
+    UNITS_PER_TONNE,
+};
+use buffer_pool::{BufferPoolContract, BufferPoolContractClient};
+use soroban_sdk::testutils::storage::Persistent as _;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, String, Vec};
+
+fn base_metadata(env: &Env) -> CarbonAssetMetadata {
+    CarbonAssetMetadata {
+        project_id: String::from_str(env, "PROJ-0"),
+        vintage_year: 1704067200,
+        methodology_id: 1,
+        geo_hash: BytesN::from_array(env, &[0u8; 32]),
+        registry: String::from_str(env, "VERRA"),
+        registry_serial: String::from_str(env, "VCS-0000-2024-001"),
+        issuance_date: 1735689600,
+        country: String::from_str(env, "BR"),
+        project_type: String::from_str(env, "REDD+"),
+        credit_type: CreditType::Avoidance,
+        sdg_flags: (1 << 12) | (1 << 14), // SDG 13 and 15
+        corresponding_adjustment: CorrespondingAdjustment::NotApplicable,
+        verification_uri: String::from_str(env, "ipfs://bafybeibverificationreport"),
+    }
+}
 
 fn setup_env() -> (Env, Address, Address, Address) {
     let env = Env::default();
@@ -33,17 +60,18 @@ fn test_mint_and_transfer_token() {
         vintage_year: 1704067200,
         methodology_id: 1,
         geo_hash: BytesN::from_array(&env, &[7u8; 32]),
+        ..base_metadata(&env)
     };
 
-    let token_id = client.mint(&admin, &owner, &meta);
+    let token_id = client.mint(&admin, &owner, &meta, &UNITS_PER_TONNE);
     assert_eq!(token_id, 1);
-    assert_eq!(client.balance(&owner), 1);
+    assert_eq!(client.balance(&owner), UNITS_PER_TONNE);
     assert_eq!(client.owner_of(&token_id), owner);
 
     let buyer = Address::generate(&env);
-    client.transfer(&owner, &buyer, &1);
+    client.transfer(&owner, &buyer, &UNITS_PER_TONNE);
     assert_eq!(client.balance(&owner), 0);
-    assert_eq!(client.balance(&buyer), 1);
+    assert_eq!(client.balance(&buyer), UNITS_PER_TONNE);
     assert_eq!(client.owner_of(&token_id), buyer);
 }
 
@@ -66,10 +94,11 @@ fn test_transfer_amount_and_allowance() {
         vintage_year: 1704067200,
         methodology_id: 1,
         geo_hash: BytesN::from_array(&env, &[9u8; 32]),
+        ..base_metadata(&env)
     };
 
-    client.mint(&admin, &owner, &meta);
-    client.mint(&admin, &owner, &meta);
+    client.mint(&admin, &owner, &meta, &1);
+    client.mint(&admin, &owner, &meta, &1);
 
     let buyer = Address::generate(&env);
     client.transfer(&owner, &buyer, &2);
@@ -103,9 +132,1759 @@ fn test_transfer_to_retirement_tracker_sets_status() {
         vintage_year: 1704067200,
         methodology_id: 2,
         geo_hash: BytesN::from_array(&env, &[3u8; 32]),
+        ..base_metadata(&env)
     };
 
-    let token_id = client.mint(&admin, &owner, &meta);
-    client.transfer(&owner, &retirement_tracker, &1);
+    let token_id = client.mint(&admin, &owner, &meta, &UNITS_PER_TONNE);
+    client.transfer(&owner, &retirement_tracker, &UNITS_PER_TONNE);
     assert_eq!(client.get_status(&token_id), AssetStatus::Retired);
 }
+
+#[test]
+fn test_partial_transfer_splits_token() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+    assert_eq!(client.decimals(), 3);
+
+    let meta = CarbonAssetMetadata {
+        project_id: String::from_str(&env, "PROJ-3"),
+        vintage_year: 1704067200,
+        methodology_id: 1,
+        geo_hash: BytesN::from_array(&env, &[5u8; 32]),
+        ..base_metadata(&env)
+    };
+
+    let token_id = client.mint(&admin, &owner, &meta, &(10 * UNITS_PER_TONNE));
+
+    // 2.5 t out of a 10 t lot
+    let buyer = Address::generate(&env);
+    client.transfer(&owner, &buyer, &2_500);
+    assert_eq!(client.balance(&owner), 7_500);
+    assert_eq!(client.balance(&buyer), 2_500);
+    assert_eq!(client.quantity_of(&token_id), 7_500);
+    assert_eq!(client.owner_of(&token_id), owner);
+
+    let bought = client.tokens_of_owner(&buyer, &0, &10).get(0).unwrap();
+    assert_ne!(bought, token_id);
+    assert_eq!(client.quantity_of(&bought), 2_500);
+    assert_eq!(client.get_metadata(&bought), meta);
+
+    assert!(client.try_transfer(&owner, &buyer, &7_501).is_err());
+}
+
+#[test]
+fn test_split_and_merge() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let meta = CarbonAssetMetadata {
+        project_id: String::from_str(&env, "PROJ-4"),
+        vintage_year: 1704067200,
+        methodology_id: 1,
+        geo_hash: BytesN::from_array(&env, &[4u8; 32]),
+        ..base_metadata(&env)
+    };
+
+    let token_id = client.mint(&admin, &owner, &meta, &(3 * UNITS_PER_TONNE));
+    let new_token_id = client.split(&owner, &token_id, &UNITS_PER_TONNE);
+    assert_eq!(client.quantity_of(&token_id), 2 * UNITS_PER_TONNE);
+    assert_eq!(client.quantity_of(&new_token_id), UNITS_PER_TONNE);
+    assert_eq!(client.balance(&owner), 3 * UNITS_PER_TONNE);
+    assert_eq!(client.tokens_of_owner(&owner, &0, &10).len(), 2);
+
+    assert_eq!(
+        client.try_split(&owner, &token_id, &(2 * UNITS_PER_TONNE)),
+        Err(Ok(ContractError::InvalidQuantity))
+    );
+
+    client.merge(&owner, &token_id, &new_token_id);
+    assert_eq!(client.quantity_of(&token_id), 3 * UNITS_PER_TONNE);
+    assert_eq!(client.balance(&owner), 3 * UNITS_PER_TONNE);
+    assert_eq!(client.tokens_of_owner(&owner, &0, &10).len(), 1);
+    assert!(client.try_owner_of(&new_token_id).is_err());
+
+    let other_meta = CarbonAssetMetadata {
+        project_id: String::from_str(&env, "PROJ-5"),
+        ..meta
+    };
+    let other_id = client.mint(&admin, &owner, &other_meta, &UNITS_PER_TONNE);
+    assert_eq!(
+        client.try_merge(&owner, &token_id, &other_id),
+        Err(Ok(ContractError::MergeMismatch))
+    );
+}
+
+#[test]
+fn test_mint_batch_assigns_serial_range() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let meta = CarbonAssetMetadata {
+        project_id: String::from_str(&env, "VCS-1234"),
+        vintage_year: 1609459200,
+        methodology_id: 1,
+        geo_hash: BytesN::from_array(&env, &[6u8; 32]),
+        ..base_metadata(&env)
+    };
+    let serial = SerialRange {
+        prefix: String::from_str(&env, "VCS-1234-2021"),
+        start: 1,
+        end: 5,
+    };
+
+    let batch_id = client.mint_batch(&admin, &owner, &meta, &serial, &UNITS_PER_TONNE);
+    let batch = client.get_batch(&batch_id);
+    assert_eq!(batch.token_id, 1);
+    assert_eq!(batch.buffer_token_id, None);
+    assert_eq!(batch.serial, serial);
+    assert_eq!(client.balance(&owner), 5 * UNITS_PER_TONNE);
+    assert_eq!(client.quantity_of(&1), 5 * UNITS_PER_TONNE);
+    assert_eq!(client.tokens_of_owner(&owner, &0, &10).len(), 1);
+    assert_eq!(
+        client.get_token_serial(&1),
+        Some(TokenSerial {
+            batch_id,
+            start: 1,
+            end: 5
+        })
+    );
+
+    // A piece split off the token keeps its serial range.
+    let piece = client.split(&owner, &1, &UNITS_PER_TONNE);
+    assert_eq!(client.get_token_serial(&piece), client.get_token_serial(&1));
+
+    let overlapping = SerialRange {
+        prefix: String::from_str(&env, "VCS-1234-2021"),
+        start: 5,
+        end: 8,
+    };
+    assert_eq!(
+        client.try_mint_batch(&admin, &owner, &meta, &overlapping, &UNITS_PER_TONNE),
+        Err(Ok(ContractError::SerialRangeOverlap))
+    );
+    let next = SerialRange {
+        prefix: String::from_str(&env, "VCS-1234-2021"),
+        start: 6,
+        end: 8,
+    };
+    client.mint_batch(&admin, &owner, &meta, &next, &UNITS_PER_TONNE);
+
+    // Serials under a prefix are issued in ascending order, so a gap
+    // below the last issued range cannot be filled later.
+    let earlier = SerialRange {
+        prefix: String::from_str(&env, "VCS-1234-2021"),
+        start: 100,
+        end: 100,
+    };
+    client.mint_batch(&admin, &owner, &meta, &earlier, &UNITS_PER_TONNE);
+    assert_eq!(
+        client.try_mint_batch(&admin, &owner, &meta, &next, &UNITS_PER_TONNE),
+        Err(Ok(ContractError::SerialRangeOverlap))
+    );
+
+    let full_range = SerialRange {
+        prefix: String::from_str(&env, "VCS-1234-2023"),
+        start: 0,
+        end: u64::MAX,
+    };
+    assert_eq!(
+        client.try_mint_batch(&admin, &owner, &meta, &full_range, &UNITS_PER_TONNE),
+        Err(Ok(ContractError::InvalidSerialRange))
+    );
+    let top_of_range = SerialRange {
+        prefix: String::from_str(&env, "VCS-1234-2023"),
+        start: u64::MAX - 1,
+        end: u64::MAX,
+    };
+    let top_batch = client.mint_batch(&admin, &owner, &meta, &top_of_range, &UNITS_PER_TONNE);
+    assert_eq!(
+        client.get_token_serial(&client.get_batch(&top_batch).token_id),
+        Some(TokenSerial {
+            batch_id: top_batch,
+            start: u64::MAX - 1,
+            end: u64::MAX
+        })
+    );
+}
+
+#[test]
+fn test_filtered_transfer_and_picking_policy() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let recent = CarbonAssetMetadata {
+        project_id: String::from_str(&env, "PROJ-A"),
+        vintage_year: 1704067200,
+        methodology_id: 1,
+        geo_hash: BytesN::from_array(&env, &[1u8; 32]),
+        ..base_metadata(&env)
+    };
+    let old = CarbonAssetMetadata {
+        vintage_year: 1577836800,
+        ..recent.clone()
+    };
+    let other_project = CarbonAssetMetadata {
+        project_id: String::from_str(&env, "PROJ-B"),
+        ..recent.clone()
+    };
+
+    let recent_id = client.mint(&admin, &owner, &recent, &UNITS_PER_TONNE);
+    let old_id = client.mint(&admin, &owner, &old, &UNITS_PER_TONNE);
+    let other_id = client.mint(&admin, &owner, &other_project, &UNITS_PER_TONNE);
+
+    let buyer = Address::generate(&env);
+    let filter = TokenFilter {
+        min_vintage: None,
+        max_vintage: None,
+        project_id: Some(String::from_str(&env, "PROJ-B")),
+        methodology_id: None,
+        min_quality_score: None,
+        country: None,
+    };
+    client.transfer_with_filter(&owner, &buyer, &UNITS_PER_TONNE, &filter);
+    assert_eq!(client.owner_of(&other_id), buyer);
+    assert!(client
+        .try_transfer_with_filter(&owner, &buyer, &UNITS_PER_TONNE, &filter)
+        .is_err());
+
+    assert_eq!(client.get_picking_policy(&owner), PickingPolicy::Fifo);
+    client.set_picking_policy(&owner, &PickingPolicy::OldestVintageFirst);
+    client.transfer(&owner, &buyer, &UNITS_PER_TONNE);
+    assert_eq!(client.owner_of(&old_id), buyer);
+    assert_eq!(client.owner_of(&recent_id), owner);
+
+    client.update_quality_score(&admin, &other_id, &90);
+    client.update_quality_score(&admin, &old_id, &40);
+    client.set_picking_policy(&buyer, &PickingPolicy::HighestQualityFirst);
+    client.transfer(&buyer, &owner, &UNITS_PER_TONNE);
+    assert_eq!(client.owner_of(&other_id), owner);
+    assert_eq!(client.owner_of(&old_id), buyer);
+}
+
+#[test]
+fn test_amount_transfer_walks_past_the_first_page() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    for _ in 0..MAX_PAGE_SIZE {
+        client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+    }
+    let other_project = CarbonAssetMetadata {
+        project_id: String::from_str(&env, "PROJ-B"),
+        ..base_metadata(&env)
+    };
+    let first_other = client.mint(&admin, &owner, &other_project, &UNITS_PER_TONNE);
+    let second_other = client.mint(&admin, &owner, &other_project, &UNITS_PER_TONNE);
+
+    // Only tokens past the first page match the filter.
+    let buyer = Address::generate(&env);
+    let filter = TokenFilter {
+        min_vintage: None,
+        max_vintage: None,
+        project_id: Some(String::from_str(&env, "PROJ-B")),
+        methodology_id: None,
+        min_quality_score: None,
+        country: None,
+    };
+    client.transfer_with_filter(&owner, &buyer, &(2 * UNITS_PER_TONNE), &filter);
+    assert_eq!(client.owner_of(&first_other), buyer);
+    assert_eq!(client.owner_of(&second_other), buyer);
+
+    // The oldest vintage sits past the first page of the wallet.
+    let old = CarbonAssetMetadata {
+        vintage_year: 1577836800,
+        ..base_metadata(&env)
+    };
+    let old_id = client.mint(&admin, &owner, &old, &UNITS_PER_TONNE);
+    client.set_picking_policy(&owner, &PickingPolicy::OldestVintageFirst);
+    client.transfer(&owner, &buyer, &UNITS_PER_TONNE);
+    assert_eq!(client.owner_of(&old_id), buyer);
+    assert_eq!(
+        client.balance(&owner),
+        MAX_PAGE_SIZE as i128 * UNITS_PER_TONNE
+    );
+}
+
+#[test]
+fn test_select_for_retirement_by_country() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let kenya = CarbonAssetMetadata {
+        country: String::from_str(&env, "KE"),
+        ..base_metadata(&env)
+    };
+    let first = client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+    client.mint(&admin, &owner, &kenya, &(2 * UNITS_PER_TONNE));
+    let last = client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+
+    let brazil = TokenFilter {
+        min_vintage: None,
+        max_vintage: None,
+        project_id: None,
+        methodology_id: None,
+        min_quality_score: None,
+        country: Some(String::from_str(&env, "BR")),
+    };
+    assert_eq!(
+        client.try_select_for_retirement(&owner, &(3 * UNITS_PER_TONNE), &brazil),
+        Err(Ok(ContractError::TransferNotAllowed))
+    );
+
+    // The last pick is split so the selection covers the amount exactly.
+    let selected = client.select_for_retirement(&owner, &(UNITS_PER_TONNE * 3 / 2), &brazil);
+    assert_eq!(selected.len(), 2);
+    assert_eq!(selected.get(0), Some(first));
+    let piece = selected.get(1).unwrap();
+    assert_eq!(client.quantity_of(&piece), UNITS_PER_TONNE / 2);
+    assert_eq!(client.quantity_of(&last), UNITS_PER_TONNE / 2);
+
+    for token_id in selected.iter() {
+        client.burn_token(&token_id, &owner);
+    }
+    assert_eq!(client.total_retired(), UNITS_PER_TONNE * 3 / 2);
+}
+
+#[test]
+fn test_token_entries_ttl_is_extended() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let meta = CarbonAssetMetadata {
+        project_id: String::from_str(&env, "PROJ-TTL"),
+        vintage_year: 1704067200,
+        methodology_id: 1,
+        geo_hash: BytesN::from_array(&env, &[8u8; 32]),
+        ..base_metadata(&env)
+    };
+    let token_id = client.mint(&admin, &owner, &meta, &UNITS_PER_TONNE);
+
+    let ttl_of =
+        |key: &DataKey| env.as_contract(&contract_id, || env.storage().persistent().get_ttl(key));
+    let minted_ttl = ttl_of(&DataKey::Owner(token_id));
+    assert!(minted_ttl >= 90 * 17280);
+
+    env.ledger().with_mut(|li| li.sequence_number += 60 * 17280);
+    let aged_ttl = ttl_of(&DataKey::Metadata(token_id));
+    assert!(aged_ttl < minted_ttl);
+
+    let mut ids = Vec::new(&env);
+    ids.push_back(token_id);
+    client.extend_token_ttl(&ids);
+    assert!(ttl_of(&DataKey::Metadata(token_id)) > aged_ttl);
+    assert!(ttl_of(&DataKey::OwnerTokenCount(owner.clone())) > aged_ttl);
+    assert!(ttl_of(&DataKey::OwnerToken(owner.clone(), 0)) > aged_ttl);
+
+    // reads bump entries too
+    env.ledger().with_mut(|li| li.sequence_number += 60 * 17280);
+    let before_read = ttl_of(&DataKey::Status(token_id));
+    client.get_status(&token_id);
+    assert!(ttl_of(&DataKey::Status(token_id)) > before_read);
+}
+
+#[test]
+fn test_rich_metadata_roundtrip_and_validation() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let meta = CarbonAssetMetadata {
+        project_id: String::from_str(&env, "PROJ-DAC"),
+        credit_type: CreditType::Removal,
+        project_type: String::from_str(&env, "Direct Air Capture"),
+        country: String::from_str(&env, "IS"),
+        corresponding_adjustment: CorrespondingAdjustment::Applied,
+        ..base_metadata(&env)
+    };
+    let token_id = client.mint(&admin, &owner, &meta, &UNITS_PER_TONNE);
+    let stored = client.get_metadata(&token_id);
+    assert_eq!(stored, meta);
+    assert_eq!(stored.credit_type, CreditType::Removal);
+
+    let bad_country = CarbonAssetMetadata {
+        country: String::from_str(&env, "Iceland"),
+        ..meta.clone()
+    };
+    assert_eq!(
+        client.try_mint(&admin, &owner, &bad_country, &UNITS_PER_TONNE),
+        Err(Ok(ContractError::InvalidMetadata))
+    );
+
+    let bad_sdg = CarbonAssetMetadata {
+        sdg_flags: 1 << 17,
+        ..meta
+    };
+    assert_eq!(
+        client.try_mint(&admin, &owner, &bad_sdg, &UNITS_PER_TONNE),
+        Err(Ok(ContractError::InvalidMetadata))
+    );
+}
+
+#[contract]
+struct MockMethodologyLibrary;
+
+#[contractimpl]
+impl MockMethodologyLibrary {
+    pub fn is_valid_methodology(_env: Env, token_id: u32) -> bool {
+        token_id == 1
+    }
+}
+
+#[contract]
+struct MockProjectRegistry;
+
+#[contractimpl]
+impl MockProjectRegistry {
+    pub fn get_project_owner(env: Env, project_id: String) -> Address {
+        if project_id != String::from_str(&env, "PROJ-0") {
+            panic!("project not found");
+        }
+        env.current_contract_address()
+    }
+}
+
+#[test]
+fn test_mint_validates_methodology_and_project() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let library = env.register(MockMethodologyLibrary, ());
+    let registry = env.register(MockProjectRegistry, ());
+    client.set_methodology_library(&admin, &library);
+    client.set_project_registry(&admin, &registry);
+
+    client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+
+    let revoked_methodology = CarbonAssetMetadata {
+        methodology_id: 7,
+        ..base_metadata(&env)
+    };
+    assert_eq!(
+        client.try_mint(&admin, &owner, &revoked_methodology, &UNITS_PER_TONNE),
+        Err(Ok(ContractError::MethodologyNotValid))
+    );
+
+    let unregistered_project = CarbonAssetMetadata {
+        project_id: String::from_str(&env, "PROJ-TYPO"),
+        ..base_metadata(&env)
+    };
+    assert_eq!(
+        client.try_mint(&admin, &owner, &unregistered_project, &UNITS_PER_TONNE),
+        Err(Ok(ContractError::ProjectNotRegistered))
+    );
+}
+
+#[test]
+fn test_mint_withholds_buffer_share() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let pool_id = env.register(BufferPoolContract, ());
+    let pool = BufferPoolContractClient::new(&env, &pool_id);
+    let governance = Address::generate(&env);
+    pool.initialize(&admin, &governance, &contract_id, &1000); // 10%
+    client.set_buffer_pool(&admin, &pool_id);
+
+    let token_id = client.mint(
+        &admin,
+        &owner,
+        &base_metadata(&env),
+        &(10 * UNITS_PER_TONNE),
+    );
+    assert_eq!(client.quantity_of(&token_id), 9 * UNITS_PER_TONNE);
+    assert_eq!(client.balance(&owner), 9 * UNITS_PER_TONNE);
+    assert_eq!(client.balance(&pool_id), UNITS_PER_TONNE);
+
+    let buffer_token = client.tokens_of_owner(&pool_id, &0, &10).get(0).unwrap();
+    assert_eq!(client.owner_of(&buffer_token), pool_id);
+    assert!(pool.is_token_in_pool(&buffer_token));
+    assert_eq!(pool.get_total_value_locked(), 1);
+
+    assert_eq!(
+        client.try_mint(&admin, &owner, &base_metadata(&env), &i128::MAX),
+        Err(Ok(ContractError::InvalidQuantity))
+    );
+
+    let serial = SerialRange {
+        prefix: String::from_str(&env, "VCS-0000-2024"),
+        start: 1,
+        end: 10,
+    };
+    let batch = client.get_batch(&client.mint_batch(
+        &admin,
+        &owner,
+        &base_metadata(&env),
+        &serial,
+        &UNITS_PER_TONNE,
+    ));
+    let buffer_token = batch.buffer_token_id.unwrap();
+    assert_eq!(client.owner_of(&buffer_token), pool_id);
+    assert!(pool.is_token_in_pool(&buffer_token));
+    assert_eq!(client.quantity_of(&buffer_token), UNITS_PER_TONNE);
+    assert_eq!(client.owner_of(&batch.token_id), owner);
+    assert_eq!(client.quantity_of(&batch.token_id), 9 * UNITS_PER_TONNE);
+    assert_eq!(client.get_token_serial(&batch.token_id).unwrap().end, 9);
+    assert_eq!(client.get_token_serial(&buffer_token).unwrap().start, 10);
+    assert_eq!(client.balance(&owner), 18 * UNITS_PER_TONNE);
+    assert_eq!(pool.get_total_value_locked(), 2);
+
+    // A batch too small to give up a whole serial still withholds its share,
+    // which shares the owner's last serial.
+    let small = SerialRange {
+        prefix: String::from_str(&env, "VCS-0000-2025"),
+        start: 1,
+        end: 3,
+    };
+    let batch = client.get_batch(&client.mint_batch(
+        &admin,
+        &owner,
+        &base_metadata(&env),
+        &small,
+        &UNITS_PER_TONNE,
+    ));
+    assert_eq!(client.quantity_of(&batch.token_id), 2_700);
+    let remainder = batch.buffer_token_id.unwrap();
+    assert_eq!(client.owner_of(&remainder), pool_id);
+    assert_eq!(client.quantity_of(&remainder), UNITS_PER_TONNE * 3 / 10);
+    assert_eq!(client.get_token_serial(&batch.token_id).unwrap().end, 3);
+    assert_eq!(client.get_token_serial(&remainder).unwrap().start, 3);
+    assert_eq!(client.balance(&owner), 18 * UNITS_PER_TONNE + 2_700);
+}
+
+#[contract]
+struct MockRegulatoryCheck;
+
+#[contractimpl]
+impl MockRegulatoryCheck {
+    pub fn validate_transaction(
+        env: Env,
+        _source: Address,
+        _destination: Address,
+        _operation: OperationType,
+        _host_jurisdiction: String,
+        credit: CreditContext,
+    ) -> ValidationResult {
+        // Pre-2021 vintages are not eligible for export.
+        if credit.vintage_year < 1609459200 {
+            return ValidationResult {
+                is_compliant: false,
+                rule_id: Some(String::from_str(&env, "BR-VINTAGE")),
+                requires_authorization: false,
+                authority_address: None,
+                error_message: None,
+            };
+        }
+        ValidationResult {
+            is_compliant: true,
+            rule_id: Some(String::from_str(&env, "BR-EXPORT")),
+            requires_authorization: true,
+            authority_address: Some(env.current_contract_address()),
+            error_message: None,
+        }
+    }
+
+    pub fn create_pending_approval(
+        env: Env,
+        approval_key: BytesN<32>,
+        _token_id: u32,
+        _source: Address,
+        _destination: Address,
+        _operation: OperationType,
+    ) {
+        env.storage().persistent().set(&approval_key, &false);
+    }
+
+    pub fn record_authorization(env: Env, approval_key: BytesN<32>) {
+        env.storage().persistent().set(&approval_key, &true);
+    }
+
+    pub fn check_approval(env: Env, approval_key: BytesN<32>) -> bool {
+        env.storage()
+            .persistent()
+            .get(&approval_key)
+            .unwrap_or(false)
+    }
+}
+
+#[test]
+fn test_transfer_requiring_regulatory_approval() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let regulator = env.register(MockRegulatoryCheck, ());
+    let regulator_client = MockRegulatoryCheckClient::new(&env, &regulator);
+    client.set_regulatory_check(&admin, &regulator);
+
+    let token_id = client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+    let buyer = Address::generate(&env);
+
+    assert_eq!(
+        client.try_transfer(&owner, &buyer, &UNITS_PER_TONNE),
+        Err(Ok(ContractError::ApprovalRequired))
+    );
+
+    let approval_key = client.request_transfer_approval(&owner, &buyer, &token_id);
+    assert_eq!(
+        client.try_transfer_with_approval(&approval_key),
+        Err(Ok(ContractError::ApprovalNotGranted))
+    );
+
+    regulator_client.record_authorization(&approval_key);
+    client.transfer_with_approval(&approval_key);
+    assert_eq!(client.owner_of(&token_id), buyer);
+    assert_eq!(client.balance(&buyer), UNITS_PER_TONNE);
+
+    // The key is consumed and cannot move the token again.
+    assert_eq!(
+        client.try_transfer_with_approval(&approval_key),
+        Err(Ok(ContractError::ApprovalNotFound))
+    );
+
+    // A second request for the same pair gets a fresh key.
+    let next_key = client.request_transfer_approval(&buyer, &owner, &token_id);
+    assert_ne!(next_key, approval_key);
+
+    // Rules see the credit itself, not only the parties.
+    let old_vintage = CarbonAssetMetadata {
+        vintage_year: 1546300800,
+        ..base_metadata(&env)
+    };
+    let old_token = client.mint(&admin, &owner, &old_vintage, &UNITS_PER_TONNE);
+    assert_eq!(
+        client.try_request_transfer_approval(&owner, &buyer, &old_token),
+        Err(Ok(ContractError::ComplianceFailed))
+    );
+}
+
+#[test]
+fn test_upgrade_and_migrate_are_admin_only() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+    assert_eq!(client.get_data_version(), 3);
+
+    assert_eq!(
+        client.try_upgrade(&owner, &BytesN::from_array(&env, &[0u8; 32])),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    assert_eq!(
+        client.try_migrate(&owner),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+
+    // Simulate a deployment from before data versioning and roles existed.
+    env.as_contract(&contract_id, || {
+        env.storage().instance().remove(&DataKey::DataVersion);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Role(Role::Issuer, admin.clone()));
+    });
+    assert_eq!(client.get_data_version(), 0);
+    assert!(!client.has_role(&Role::Issuer, &admin));
+
+    assert_eq!(client.migrate(&admin), 3);
+    assert_eq!(client.get_data_version(), 3);
+    assert!(client.has_role(&Role::Issuer, &admin));
+    assert_eq!(client.migrate(&admin), 3);
+}
+
+#[test]
+fn test_roles_separate_issuer_from_config_admin() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let operator = Address::generate(&env);
+    assert_eq!(
+        client.try_mint(&operator, &owner, &base_metadata(&env), &UNITS_PER_TONNE),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+
+    client.grant_role(&admin, &Role::Issuer, &operator);
+    assert!(client.has_role(&Role::Issuer, &operator));
+    let token_id = client.mint(&operator, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+
+    // Issuing does not carry configuration or status powers.
+    assert_eq!(
+        client.try_set_regulatory_check(&operator, &operator),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    assert_eq!(
+        client.try_set_status(&operator, &token_id, &AssetStatus::Listed),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    assert_eq!(
+        client.try_grant_role(&operator, &Role::ConfigAdmin, &operator),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+
+    // The admin can drop its own issuing power and keep configuration.
+    client.revoke_role(&admin, &Role::Issuer, &admin);
+    assert_eq!(
+        client.try_mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    client.set_host_jurisdiction(&admin, &String::from_str(&env, "CH"));
+
+    client.revoke_role(&admin, &Role::Issuer, &operator);
+    assert!(!client.has_role(&Role::Issuer, &operator));
+}
+
+#[test]
+fn test_two_step_admin_handover() {
+    let (env, admin, retirement_tracker, _) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let typo = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let expiration = env.ledger().sequence() + 100;
+
+    client.propose_admin(&admin, &typo, &expiration);
+    assert_eq!(
+        client.try_accept_admin(&new_admin),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+
+    // A wrong proposal can be replaced before anyone accepts it.
+    client.propose_admin(&admin, &new_admin, &expiration);
+    assert_eq!(
+        client.try_accept_admin(&typo),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+
+    env.ledger()
+        .with_mut(|li| li.sequence_number = expiration + 1);
+    assert_eq!(
+        client.try_accept_admin(&new_admin),
+        Err(Ok(ContractError::AdminProposalExpired))
+    );
+    assert_eq!(client.get_admin(), admin);
+
+    let expiration = env.ledger().sequence() + 100;
+    client.propose_admin(&admin, &new_admin, &expiration);
+    client.accept_admin(&new_admin);
+    assert_eq!(client.get_admin(), new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+    assert_eq!(
+        client.try_accept_admin(&new_admin),
+        Err(Ok(ContractError::NoPendingAdmin))
+    );
+
+    assert_eq!(
+        client.try_grant_role(&admin, &Role::Issuer, &admin),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    client.grant_role(&new_admin, &Role::Issuer, &new_admin);
+    client.revoke_role(&new_admin, &Role::Issuer, &admin);
+}
+
+#[test]
+fn test_admin_handover_moves_admin_roles() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    // A role handed to a separate key and one the admin dropped itself.
+    let pauser = Address::generate(&env);
+    client.grant_role(&admin, &Role::Pauser, &pauser);
+    client.revoke_role(&admin, &Role::Oracle, &admin);
+
+    let new_admin = Address::generate(&env);
+    client.propose_admin(&admin, &new_admin, &(env.ledger().sequence() + 100));
+    client.accept_admin(&new_admin);
+
+    for role in [
+        Role::Issuer,
+        Role::StatusManager,
+        Role::ConfigAdmin,
+        Role::Pauser,
+    ] {
+        assert!(!client.has_role(&role, &admin));
+        assert!(client.has_role(&role, &new_admin));
+    }
+    assert!(!client.has_role(&Role::Oracle, &new_admin));
+    assert!(client.has_role(&Role::Pauser, &pauser));
+
+    assert_eq!(
+        client.try_mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    client.mint(&new_admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+}
+
+#[test]
+fn test_pause_blocks_mint_transfer_and_retire() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let pauser = Address::generate(&env);
+    assert_eq!(
+        client.try_pause(&pauser, &PauseScope::All),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    client.grant_role(&admin, &Role::Pauser, &pauser);
+
+    let token_id = client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+    let buyer = Address::generate(&env);
+
+    // A per-operation pause only stops that operation.
+    client.pause(&pauser, &PauseScope::Transfer);
+    assert!(client.is_paused(&PauseScope::Transfer));
+    assert_eq!(
+        client.try_transfer(&owner, &buyer, &UNITS_PER_TONNE),
+        Err(Ok(ContractError::ContractPaused))
+    );
+    let second = client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+
+    // The global pause overrides everything and leaves the per-operation
+    // flags untouched when lifted.
+    client.pause(&pauser, &PauseScope::All);
+    assert_eq!(
+        client.try_mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE),
+        Err(Ok(ContractError::ContractPaused))
+    );
+    assert_eq!(
+        client.try_burn_token(&second, &owner),
+        Err(Ok(ContractError::ContractPaused))
+    );
+    client.unpause(&pauser, &PauseScope::All);
+    assert!(!client.is_paused(&PauseScope::All));
+    assert!(client.is_paused(&PauseScope::Transfer));
+    client.burn_token(&second, &owner);
+
+    client.pause(&pauser, &PauseScope::Retire);
+    assert_eq!(
+        client.try_burn_token(&token_id, &owner),
+        Err(Ok(ContractError::ContractPaused))
+    );
+
+    client.unpause(&pauser, &PauseScope::Transfer);
+    client.transfer(&owner, &buyer, &UNITS_PER_TONNE);
+    assert_eq!(client.owner_of(&token_id), buyer);
+}
+
+#[test]
+fn test_project_freeze_and_vintage_invalidation() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let project_id = String::from_str(&env, "PROJ-1");
+    let meta_2021 = CarbonAssetMetadata {
+        project_id: project_id.clone(),
+        vintage_year: 1609459200,
+        ..base_metadata(&env)
+    };
+    let meta_2022 = CarbonAssetMetadata {
+        vintage_year: 1640995200,
+        ..meta_2021.clone()
+    };
+    let token_2021 = client.mint(&admin, &owner, &meta_2021, &UNITS_PER_TONNE);
+    let token_2022 = client.mint(&admin, &owner, &meta_2022, &UNITS_PER_TONNE);
+    let other_project = client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_freeze_project(&outsider, &project_id),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+
+    // A project freeze locks every credit of the project, and only those.
+    client.freeze_project(&admin, &project_id);
+    assert_eq!(client.get_project_state(&project_id), IssuanceState::Frozen);
+    assert_eq!(client.get_status(&token_2021), AssetStatus::Locked);
+    assert_eq!(client.get_status(&token_2022), AssetStatus::Locked);
+    assert_eq!(client.get_status(&other_project), AssetStatus::Issued);
+    assert_eq!(
+        client.try_burn_token(&token_2021, &owner),
+        Err(Ok(ContractError::IssuanceFrozen))
+    );
+    assert_eq!(
+        client.try_mint(&admin, &owner, &meta_2021, &UNITS_PER_TONNE),
+        Err(Ok(ContractError::IssuanceFrozen))
+    );
+
+    // Only the untouched project's credit is left to transfer.
+    let buyer = Address::generate(&env);
+    client.transfer(&owner, &buyer, &UNITS_PER_TONNE);
+    assert_eq!(client.owner_of(&other_project), buyer);
+
+    // Lifting the freeze restores each token's own status.
+    client.unfreeze_project(&admin, &project_id);
+    assert_eq!(client.get_status(&token_2021), AssetStatus::Issued);
+
+    client.invalidate_vintage(&admin, &project_id, &meta_2021.vintage_year);
+    assert_eq!(
+        client.get_vintage_state(&project_id, &meta_2021.vintage_year),
+        IssuanceState::Invalidated
+    );
+    assert_eq!(client.get_status(&token_2021), AssetStatus::Invalidated);
+    assert_eq!(client.get_status(&token_2022), AssetStatus::Issued);
+    assert_eq!(
+        client.try_unfreeze_vintage(&admin, &project_id, &meta_2021.vintage_year),
+        Err(Ok(ContractError::StatusFrozen))
+    );
+
+    // Credits retired before a project-level invalidation stay retired.
+    client.burn_token(&token_2022, &owner);
+    client.invalidate_project(&admin, &project_id);
+    assert_eq!(client.get_status(&token_2022), AssetStatus::Retired);
+}
+
+#[test]
+fn test_reversal_replaces_from_buffer_pool() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let pool_id = env.register(BufferPoolContract, ());
+    let pool = BufferPoolContractClient::new(&env, &pool_id);
+    let governance = Address::generate(&env);
+    pool.initialize(&admin, &governance, &contract_id, &5000); // 50%
+    client.set_buffer_pool(&admin, &pool_id);
+
+    let large = client.mint(
+        &admin,
+        &owner,
+        &base_metadata(&env),
+        &(10 * UNITS_PER_TONNE),
+    );
+    let large_buffer = large + 1;
+    let small = client.mint(&admin, &owner, &base_metadata(&env), &(4 * UNITS_PER_TONNE));
+    let small_buffer = small + 1;
+    assert_eq!(pool.get_total_value_locked(), 2);
+
+    let buffer = Vec::from_array(&env, [large_buffer, small_buffer]);
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_reverse(&outsider, &Vec::from_array(&env, [small]), &buffer),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    assert_eq!(client.balance(&owner), 7 * UNITS_PER_TONNE);
+    assert_eq!(client.total_supply(), 14 * UNITS_PER_TONNE);
+
+    // Only part of the first pool credit is needed, so it is split and the
+    // remainder stays in custody. The reversed quantity leaves the holder's
+    // balance and the supply; the replacement comes back from the pool.
+    client.reverse(&admin, &Vec::from_array(&env, [small]), &buffer);
+    assert_eq!(client.get_status(&small), AssetStatus::Invalidated);
+    assert_eq!(client.owner_of(&small), owner);
+    assert_eq!(client.balance(&owner), 7 * UNITS_PER_TONNE);
+    assert_eq!(client.balance(&pool_id), 5 * UNITS_PER_TONNE);
+    assert_eq!(client.total_supply(), 12 * UNITS_PER_TONNE);
+    let replacements = client.get_replacements(&small);
+    assert_eq!(replacements.len(), 1);
+    let replacement = replacements.get(0).unwrap();
+    assert_eq!(client.owner_of(&replacement), owner);
+    assert_eq!(client.quantity_of(&replacement), 2 * UNITS_PER_TONNE);
+    assert_eq!(client.get_replaced_token(&replacement), Some(small));
+    assert_eq!(client.quantity_of(&large_buffer), 3 * UNITS_PER_TONNE);
+    assert!(pool.is_token_in_pool(&large_buffer));
+    assert_eq!(
+        pool.get_replacement_record(&replacement)
+            .unwrap()
+            .buffer_token_id,
+        large_buffer
+    );
+
+    // Whole pool credits are handed over and leave custody. Candidates the
+    // pool does not hold are skipped.
+    let candidates = Vec::from_array(&env, [replacement, large_buffer, small_buffer]);
+    client.reverse(&admin, &Vec::from_array(&env, [large]), &candidates);
+    assert_eq!(
+        client.get_replacements(&large),
+        Vec::from_array(&env, [large_buffer, small_buffer])
+    );
+    assert_eq!(client.owner_of(&large_buffer), owner);
+    assert_eq!(client.owner_of(&small_buffer), owner);
+    assert_eq!(pool.get_total_value_locked(), 0);
+    assert_eq!(client.balance(&owner), 7 * UNITS_PER_TONNE);
+    assert_eq!(client.balance(&pool_id), 0);
+    assert_eq!(client.total_supply(), 7 * UNITS_PER_TONNE);
+
+    // Invalidated tokens are not picked by amount-based transfers.
+    let buyer = Address::generate(&env);
+    client.transfer(&owner, &buyer, &(7 * UNITS_PER_TONNE));
+    assert_eq!(client.balance(&owner), 0);
+
+    assert_eq!(
+        client.try_reverse(&admin, &Vec::from_array(&env, [large]), &buffer),
+        Err(Ok(ContractError::StatusFrozen))
+    );
+
+    // Holders are never left short: without enough matching pool credits the
+    // whole reversal is rejected.
+    pool.set_replenishment_rate(&governance, &1000);
+    let uncovered = client.mint(
+        &admin,
+        &owner,
+        &base_metadata(&env),
+        &(10 * UNITS_PER_TONNE),
+    );
+    let uncovered_buffer = Vec::from_array(&env, [uncovered + 1]);
+    assert_eq!(
+        client.try_reverse(
+            &admin,
+            &Vec::from_array(&env, [uncovered]),
+            &uncovered_buffer
+        ),
+        Err(Ok(ContractError::InsufficientBuffer))
+    );
+    assert_eq!(client.get_status(&uncovered), AssetStatus::Issued);
+    assert_eq!(client.balance(&owner), 9 * UNITS_PER_TONNE);
+}
+
+#[test]
+fn test_owner_listing_and_delisting() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let listed = client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+    let unlisted = client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+    let usdc = Address::generate(&env);
+
+    assert_eq!(
+        client.try_list(&listed, &0, &usdc),
+        Err(Ok(ContractError::InvalidPrice))
+    );
+    assert_eq!(
+        client.try_set_status(&admin, &listed, &AssetStatus::Listed),
+        Err(Ok(ContractError::InvalidStatusTransition))
+    );
+
+    client.list(&listed, &25_000_000, &usdc);
+    assert_eq!(client.get_status(&listed), AssetStatus::Listed);
+    let listing = client.get_listing(&listed).unwrap();
+    assert_eq!(listing.seller, owner);
+    assert_eq!(listing.price, 25_000_000);
+    assert_eq!(listing.currency, usdc);
+
+    // A listed token is held back from amount-based transfers and cannot
+    // be retired, split or merged until it is delisted.
+    let buyer = Address::generate(&env);
+    client.transfer(&owner, &buyer, &UNITS_PER_TONNE);
+    assert_eq!(client.owner_of(&unlisted), buyer);
+    assert_eq!(client.owner_of(&listed), owner);
+    assert_eq!(
+        client.try_burn_token(&listed, &owner),
+        Err(Ok(ContractError::TokenListed))
+    );
+    assert_eq!(
+        client.try_split(&owner, &listed, &(UNITS_PER_TONNE / 2)),
+        Err(Ok(ContractError::TokenListed))
+    );
+
+    client.delist(&listed);
+    assert_eq!(client.get_status(&listed), AssetStatus::Issued);
+    assert_eq!(client.get_listing(&listed), None);
+    assert_eq!(
+        client.try_delist(&listed),
+        Err(Ok(ContractError::NotListed))
+    );
+
+    // Any status change made by a status manager also withdraws the offer.
+    client.list(&listed, &30_000_000, &usdc);
+    client.set_status(&admin, &listed, &AssetStatus::Locked);
+    assert_eq!(client.get_listing(&listed), None);
+}
+
+#[test]
+fn test_token_and_operator_approvals() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let promised = client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+    let other = client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+    let escrow = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    // A per-token approval covers that token only.
+    client.approve_token(&promised, &escrow);
+    assert_eq!(client.get_approved(&promised), Some(escrow.clone()));
+    assert_eq!(
+        client.try_transfer_token_from(&escrow, &owner, &escrow, &other),
+        Err(Ok(ContractError::NotApproved))
+    );
+    client.transfer_token_from(&escrow, &owner, &escrow, &promised);
+    assert_eq!(client.owner_of(&promised), escrow);
+    assert_eq!(client.get_approved(&promised), None);
+
+    // The new holder can move its own token without any approval.
+    client.transfer_token_from(&escrow, &escrow, &owner, &promised);
+    assert_eq!(client.owner_of(&promised), owner);
+
+    // An operator may move any of the owner's tokens until revoked.
+    client.set_approval_for_all(&owner, &operator, &true);
+    assert!(client.is_approved_for_all(&owner, &operator));
+    client.transfer_token_from(&operator, &owner, &buyer, &other);
+    assert_eq!(client.owner_of(&other), buyer);
+
+    client.set_approval_for_all(&owner, &operator, &false);
+    assert_eq!(
+        client.try_transfer_token_from(&operator, &owner, &buyer, &promised),
+        Err(Ok(ContractError::NotApproved))
+    );
+
+    client.approve_token(&promised, &escrow);
+    client.revoke_token_approval(&promised);
+    assert_eq!(client.get_approved(&promised), None);
+    assert_eq!(
+        client.try_revoke_token_approval(&promised),
+        Err(Ok(ContractError::NotApproved))
+    );
+}
+
+#[test]
+fn test_burn_token_from_with_approval_or_allowance() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let approved = client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+    let allowed = client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+    let broker = Address::generate(&env);
+
+    assert_eq!(
+        client.try_burn_token_from(&broker, &owner, &approved),
+        Err(Ok(ContractError::NotApproved))
+    );
+    client.approve_token(&approved, &broker);
+    client.burn_token_from(&broker, &owner, &approved);
+    assert!(client.is_burned(&approved));
+
+    // Without a token approval, an allowance covering the quantity is spent.
+    client.approve(&owner, &broker, &(UNITS_PER_TONNE / 2), &1000);
+    assert_eq!(
+        client.try_burn_token_from(&broker, &owner, &allowed),
+        Err(Ok(ContractError::NotApproved))
+    );
+    client.approve(&owner, &broker, &(UNITS_PER_TONNE * 3 / 2), &1000);
+    client.burn_token_from(&broker, &owner, &allowed);
+    assert_eq!(client.allowance(&owner, &broker), UNITS_PER_TONNE / 2);
+    assert_eq!(client.total_retired(), 2 * UNITS_PER_TONNE);
+}
+
+#[test]
+fn test_multi_oracle_score_aggregation_and_history() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let token_id = client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+    let satellite = Address::generate(&env);
+    let lidar = Address::generate(&env);
+    let field = Address::generate(&env);
+    client.register_oracle(&admin, &satellite, &1);
+    client.register_oracle(&admin, &lidar, &1);
+    client.register_oracle(&admin, &field, &2);
+    client.set_score_config(&admin, &ScoreAggregation::Median, &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+    let evidence = BytesN::from_array(&env, &[2u8; 32]);
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_submit_quality_score(&outsider, &token_id, &80, &10_000, &evidence),
+        Err(Ok(ContractError::OracleNotRegistered))
+    );
+    assert_eq!(
+        client.try_submit_quality_score(&satellite, &token_id, &80, &8_000, &evidence),
+        Err(Ok(ContractError::StaleScore))
+    );
+
+    client.submit_quality_score(&satellite, &token_id, &80, &9_500, &evidence);
+    client.submit_quality_score(&lidar, &token_id, &60, &9_800, &evidence);
+    assert_eq!(
+        client.submit_quality_score(&field, &token_id, &90, &9_900, &evidence),
+        80
+    );
+    assert_eq!(client.get_quality_score(&token_id), 80);
+
+    // (80 * 1 + 60 * 1 + 90 * 2) / 4
+    client.set_score_config(&admin, &ScoreAggregation::WeightedMean, &1_000);
+    assert_eq!(client.refresh_quality_score(&token_id), 80);
+    // (80 * 1 + 60 * 4 + 90 * 2) / 7
+    client.register_oracle(&admin, &lidar, &4);
+    assert_eq!(client.refresh_quality_score(&token_id), 71);
+
+    // Once the satellite reading ages out only lidar and field readings count.
+    env.ledger().with_mut(|li| li.timestamp = 10_600);
+    assert_eq!(client.refresh_quality_score(&token_id), 70);
+    client.remove_oracle(&admin, &lidar);
+    assert_eq!(client.refresh_quality_score(&token_id), 90);
+
+    client.update_quality_score(&admin, &token_id, &75);
+    assert_eq!(client.score_history_count(&token_id), 4);
+    let history = client.get_score_history(&token_id, &2, &10);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap().source, field);
+    assert_eq!(history.get(0).unwrap().evidence_hash, Some(evidence));
+    assert_eq!(history.get(1).unwrap().score, 75);
+    assert_eq!(history.get(1).unwrap().evidence_hash, None);
+}
+
+#[test]
+fn test_score_aggregation_rejects_overflow() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let token_id = client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+    let satellite = Address::generate(&env);
+    let lidar = Address::generate(&env);
+    client.register_oracle(&admin, &satellite, &2);
+    client.register_oracle(&admin, &lidar, &1);
+    client.set_score_config(&admin, &ScoreAggregation::WeightedMean, &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+    let evidence = BytesN::from_array(&env, &[2u8; 32]);
+    assert_eq!(
+        client.try_submit_quality_score(&satellite, &token_id, &i128::MAX, &10_000, &evidence),
+        Err(Ok(ContractError::ScoreOverflow))
+    );
+
+    client.submit_quality_score(&lidar, &token_id, &i128::MAX, &10_000, &evidence);
+    client.set_score_config(&admin, &ScoreAggregation::Median, &1_000);
+    assert_eq!(
+        client.try_submit_quality_score(&satellite, &token_id, &1, &10_000, &evidence),
+        Err(Ok(ContractError::ScoreOverflow))
+    );
+    assert_eq!(client.get_quality_score(&token_id), i128::MAX);
+}
+
+#[test]
+fn test_paginated_enumeration_and_totals() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    let project_id = String::from_str(&env, "PROJ-1");
+    let meta_2021 = CarbonAssetMetadata {
+        project_id: project_id.clone(),
+        vintage_year: 1609459200,
+        ..base_metadata(&env)
+    };
+    let meta_2022 = CarbonAssetMetadata {
+        vintage_year: 1640995200,
+        ..meta_2021.clone()
+    };
+    for _ in 0..3 {
+        client.mint(&admin, &owner, &meta_2021, &UNITS_PER_TONNE);
+    }
+    let newest = client.mint(&admin, &owner, &meta_2022, &UNITS_PER_TONNE);
+    client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+
+    assert_eq!(client.owner_token_count(&owner), 5);
+    assert_eq!(
+        client.tokens_of_owner(&owner, &0, &2),
+        Vec::from_array(&env, [1, 2])
+    );
+    assert_eq!(
+        client.tokens_of_owner(&owner, &4, &10),
+        Vec::from_array(&env, [5])
+    );
+    assert!(client.tokens_of_owner(&owner, &5, &10).is_empty());
+
+    let first = client.tokens_by_project(&project_id, &0, &3);
+    assert_eq!(first.token_ids, Vec::from_array(&env, [1, 2, 3]));
+    assert_eq!(first.next_cursor, Some(3));
+    let rest = client.tokens_by_project(&project_id, &3, &3);
+    assert_eq!(rest.token_ids, Vec::from_array(&env, [newest]));
+    assert_eq!(rest.next_cursor, None);
+
+    let vintage = client.tokens_by_vintage(&project_id, &meta_2022.vintage_year, &0, &10);
+    assert_eq!(vintage.token_ids, Vec::from_array(&env, [newest]));
+
+    // Retiring moves quantity from supply to the retired total, and the
+    // owner's list stays dense after the swap-remove.
+    assert_eq!(client.total_supply(), 5 * UNITS_PER_TONNE);
+    client.burn_token(&1, &owner);
+    assert_eq!(client.total_supply(), 4 * UNITS_PER_TONNE);
+    assert_eq!(client.total_retired(), UNITS_PER_TONNE);
+    assert_eq!(client.owner_token_count(&owner), 4);
+    assert_eq!(
+        client.tokens_of_owner(&owner, &0, &10),
+        Vec::from_array(&env, [5, 2, 3, 4])
+    );
+
+    // Splits are indexed; merged-away tokens drop out of pages.
+    let piece = client.split(&owner, &2, &(UNITS_PER_TONNE / 2));
+    assert_eq!(client.total_supply(), 4 * UNITS_PER_TONNE);
+    client.merge(&owner, &2, &piece);
+    let all = client.tokens_by_project(&project_id, &0, &10);
+    assert_eq!(all.token_ids, Vec::from_array(&env, [1, 2, 3, newest]));
+}
+
+#[test]
+fn test_migrate_builds_indexes_in_batches() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+
+    // More tokens than one migration batch covers.
+    let count = 30u32;
+    for _ in 0..count {
+        client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+    }
+    client.burn_token(&1, &owner);
+
+    // Rewrite storage into the v2 layout: one vector per owner, no project
+    // or vintage indexes and no running totals.
+    let project_id = base_metadata(&env).project_id;
+    let vintage_year = base_metadata(&env).vintage_year;
+    env.as_contract(&contract_id, || {
+        let storage = env.storage().persistent();
+        let mut legacy = Vec::new(&env);
+        for index in 0..count - 1 {
+            let key = DataKey::OwnerToken(owner.clone(), index);
+            legacy.push_back(storage.get::<_, u32>(&key).unwrap());
+            storage.remove(&key);
+        }
+        storage.remove(&DataKey::OwnerTokenCount(owner.clone()));
+        storage.set(&DataKey::OwnerTokens(owner.clone()), &legacy);
+        for position in 0..count {
+            storage.remove(&DataKey::ProjectToken(project_id.clone(), position));
+            storage.remove(&DataKey::VintageToken(
+                project_id.clone(),
+                vintage_year,
+                position,
+            ));
+        }
+        storage.remove(&DataKey::ProjectTokenCount(project_id.clone()));
+        storage.remove(&DataKey::VintageTokenCount(
+            project_id.clone(),
+            vintage_year,
+        ));
+        env.storage().instance().remove(&DataKey::TotalSupply);
+        env.storage().instance().remove(&DataKey::TotalRetired);
+        env.storage().instance().set(&DataKey::DataVersion, &2u32);
+    });
+
+    assert_eq!(client.migrate(&admin), 2);
+    assert_eq!(client.get_data_version(), 2);
+
+    // Token writes between batches would be indexed and counted twice.
+    let buyer = Address::generate(&env);
+    assert_eq!(
+        client.try_mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE),
+        Err(Ok(ContractError::MigrationPending))
+    );
+    assert_eq!(
+        client.try_transfer(&owner, &buyer, &UNITS_PER_TONNE),
+        Err(Ok(ContractError::MigrationPending))
+    );
+    assert_eq!(
+        client.try_burn_token(&2, &owner),
+        Err(Ok(ContractError::MigrationPending))
+    );
+
+    assert_eq!(client.migrate(&admin), 3);
+    assert_eq!(client.get_data_version(), 3);
+
+    assert_eq!(client.owner_token_count(&owner), count - 1);
+    assert_eq!(client.tokens_of_owner(&owner, &0, &100).len(), count - 1);
+    assert_eq!(client.total_supply(), (count - 1) as i128 * UNITS_PER_TONNE);
+    assert_eq!(client.total_retired(), UNITS_PER_TONNE);
+    assert_eq!(
+        client
+            .tokens_by_project(&project_id, &0, &100)
+            .token_ids
+            .len(),
+        count
+    );
+
+    // Tokens minted once migration is done are indexed exactly once.
+    let minted = client.mint(&admin, &owner, &base_metadata(&env), &UNITS_PER_TONNE);
+    assert_eq!(client.owner_token_count(&owner), count);
+    assert_eq!(client.total_supply(), count as i128 * UNITS_PER_TONNE);
+    assert_eq!(
+        client
+            .tokens_by_project(&project_id, &0, &100)
+            .token_ids
+            .len(),
+        count + 1
+    );
+    assert_eq!(client.migrate(&admin), 3);
+    assert_eq!(client.total_supply(), count as i128 * UNITS_PER_TONNE);
+
+    client.transfer(&owner, &buyer, &UNITS_PER_TONNE);
+    assert_eq!(client.owner_token_count(&owner), count - 1);
+    assert_eq!(client.owner_of(&minted), owner);
+}
+
+// Storage writes of the baseline (data version 0) contract: one tonne per
+// token, a single owner vector, four metadata fields and no decimals.
+#[contract]
+struct BaselineCarbonAsset;
+
+#[contractimpl]
+impl BaselineCarbonAsset {
+    pub fn initialize(env: Env, admin: Address, retirement_tracker: Address) {
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Admin, &admin);
+        storage.set(&DataKey::Name, &String::from_str(&env, "Carbon Asset"));
+        storage.set(&DataKey::Symbol, &String::from_str(&env, "C01"));
+        storage.set(&DataKey::Decimals, &0u32);
+        storage.set(&DataKey::RetirementTracker, &retirement_tracker);
+        storage.set(&DataKey::HostJurisdiction, &String::from_str(&env, "US"));
+        storage.set(&DataKey::NextTokenId, &1u32);
+    }
+
+    pub fn mint(env: Env, owner: Address, metadata: CarbonAssetMetadataV0) -> u32 {
+        let token_id: u32 = env.storage().instance().get(&DataKey::NextTokenId).unwrap();
+        env.storage()
+            .instance()
+            .set(&DataKey::NextTokenId, &(token_id + 1));
+
+        let storage = env.storage().persistent();
+        let mut tokens: Vec<u32> = storage
+            .get(&DataKey::OwnerTokens(owner.clone()))
+            .unwrap_or(Vec::new(&env));
+        tokens.push_back(token_id);
+        storage.set(&DataKey::Owner(token_id), &owner);
+        storage.set(&DataKey::OwnerTokens(owner), &tokens);
+        storage.set(&DataKey::TokenIndex(token_id), &(tokens.len() - 1));
+        storage.set(&DataKey::Metadata(token_id), &metadata);
+        storage.set(&DataKey::Status(token_id), &AssetStatus::Issued);
+        storage.set(&DataKey::QualityScore(token_id), &0i128);
+        storage.set(&DataKey::Burned(token_id), &false);
+        token_id
+    }
+
+    // Burns the owner's most recent token, which keeps the vector swap-free.
+    pub fn burn_last(env: Env, owner: Address) -> u32 {
+        let storage = env.storage().persistent();
+        let mut tokens: Vec<u32> = storage.get(&DataKey::OwnerTokens(owner.clone())).unwrap();
+        let token_id = tokens.pop_back().unwrap();
+        storage.set(&DataKey::OwnerTokens(owner), &tokens);
+        storage.remove(&DataKey::TokenIndex(token_id));
+        storage.set(&DataKey::Status(token_id), &AssetStatus::Retired);
+        storage.set(&DataKey::Burned(token_id), &true);
+        storage.remove(&DataKey::Owner(token_id));
+        token_id
+    }
+}
+
+#[test]
+fn test_migrate_from_baseline_layout() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(BaselineCarbonAsset, ());
+    let baseline = BaselineCarbonAssetClient::new(&env, &contract_id);
+
+    let legacy = CarbonAssetMetadataV0 {
+        project_id: String::from_str(&env, "PROJ-0"),
+        vintage_year: 1704067200,
+        methodology_id: 1,
+        geo_hash: BytesN::from_array(&env, &[7u8; 32]),
+    };
+    let other = Address::generate(&env);
+    baseline.initialize(&admin, &retirement_tracker);
+    baseline.mint(&owner, &legacy);
+    baseline.mint(&owner, &legacy);
+    baseline.mint(&other, &legacy);
+    baseline.mint(&owner, &legacy);
+    let burned = baseline.burn_last(&owner);
+
+    // Upgrade the code in place; storage stays as the baseline left it.
+    env.register_at(&contract_id, CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+    assert_eq!(client.get_data_version(), 0);
+    assert_eq!(client.decimals(), 0);
+
+    assert_eq!(client.migrate(&admin), 3);
+    assert_eq!(client.decimals(), 3);
+    assert_eq!(client.balance(&owner), 2 * UNITS_PER_TONNE);
+    assert_eq!(client.balance(&other), UNITS_PER_TONNE);
+    assert_eq!(client.quantity_of(&burned), UNITS_PER_TONNE);
+    assert_eq!(client.total_supply(), 3 * UNITS_PER_TONNE);
+    assert_eq!(client.total_retired(), UNITS_PER_TONNE);
+    assert_eq!(client.owner_token_count(&owner), 2);
+    assert!(client.is_burned(&burned));
+
+    let metadata = client.get_metadata(&1);
+    assert_eq!(metadata.project_id, legacy.project_id);
+    assert_eq!(metadata.geo_hash, legacy.geo_hash);
+    assert_eq!(metadata.registry, String::from_str(&env, ""));
+    assert_eq!(metadata.credit_type, CreditType::Avoidance);
+    assert_eq!(
+        client
+            .tokens_by_project(&legacy.project_id, &0, &10)
+            .token_ids
+            .len(),
+        4
+    );
+
+    // Migrated tokens behave like ones minted after the upgrade.
+    let buyer = Address::generate(&env);
+    client.transfer(&owner, &buyer, &UNITS_PER_TONNE);
+    assert_eq!(client.balance(&buyer), UNITS_PER_TONNE);
+    client.burn_token(&3, &other);
+    assert_eq!(client.total_retired(), 2 * UNITS_PER_TONNE);
+    assert_eq!(client.migrate(&admin), 3);
+}
+
+// Stellar mainnet per-transaction resource limits (protocol 23).
+const TX_MAX_INSTRUCTIONS: i64 = 100_000_000;
+const TX_MAX_WRITE_ENTRIES: u32 = 50;
+const TX_MAX_WRITE_BYTES: u32 = 132_096;
+
+#[test]
+fn test_mint_batch_fits_transaction_limits() {
+    let (env, admin, retirement_tracker, owner) = setup_env();
+    let contract_id = env.register(CarbonAsset, ());
+    let client = CarbonAssetClient::new(&env, &contract_id);
+
+    client.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &retirement_tracker,
+        &String::from_str(&env, "US"),
+    );
+    let pool_id = env.register(BufferPoolContract, ());
+    let pool = BufferPoolContractClient::new(&env, &pool_id);
+    pool.initialize(&admin, &Address::generate(&env), &contract_id, &1000);
+    client.set_buffer_pool(&admin, &pool_id);
+
+    // A million-serial block costs the same as a single serial, buffer
+    // deposit included.
+    for (prefix, end) in [("VCS-0000-2024", 1u64), ("VCS-0000-2025", 1_000_000)] {
+        let serial = SerialRange {
+            prefix: String::from_str(&env, prefix),
+            start: 1,
+            end,
+        };
+        let batch_id = client.mint_batch(
+            &admin,
+            &owner,
+            &base_metadata(&env),
+            &serial,
+            &UNITS_PER_TONNE,
+        );
+        let resources = env.cost_estimate().resources();
+        assert!(resources.instructions < TX_MAX_INSTRUCTIONS);
+        assert!(resources.write_entries <= TX_MAX_WRITE_ENTRIES);
+        assert!(resources.write_bytes <= TX_MAX_WRITE_BYTES);
+
+        let batch = client.get_batch(&batch_id);
+        assert_eq!(
+            client.quantity_of(&batch.token_id)
+                + client.quantity_of(&batch.buffer_token_id.unwrap()),
+            end as i128 * UNITS_PER_TONNE
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, BytesN, Env, String};
+use soroban_sdk::{contracttype, Address, BytesN, String, Vec};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
@@ -10,16 +10,192 @@ pub enum AssetStatus {
     Invalidated,
 }
 
-#[derive(Clone)]
+/// Quantities are tracked in kilograms of CO2e, so one tonne is 1,000 units
+/// and the SEP-41 `decimals` value is 3.
+pub const UNITS_PER_TONNE: i128 = 1_000;
+pub const QUANTITY_DECIMALS: u32 = 3;
+
+/// Upper bound on token ids returned by one page of an enumeration call.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Upper bound on registered score oracles, which also bounds the per-token
+/// list of score sources walked on every aggregation.
+pub const MAX_ORACLES: u32 = 16;
+
+/// Default staleness window (seconds): older observations are left out of
+/// the aggregated quality score.
+pub const DEFAULT_SCORE_MAX_AGE: u64 = 90 * 24 * 60 * 60;
+
+/// Highest SDG number that can be flagged in `sdg_flags` (bit `n - 1` is SDG `n`).
+pub const MAX_SDG: u32 = 17;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum CreditType {
+    Avoidance,
+    Removal,
+}
+
+// Corresponding-adjustment status under Article 6 / CORSIA.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum CorrespondingAdjustment {
+    NotApplicable,
+    Pending,
+    Applied,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct CarbonAssetMetadata {
     pub project_id: String,
     pub vintage_year: u64,
     pub methodology_id: u32,
     pub geo_hash: BytesN<32>,
+    pub registry: String,
+    pub registry_serial: String,
+    pub issuance_date: u64,
+    pub country: String, // ISO 3166-1 alpha-2
+    pub project_type: String,
+    pub credit_type: CreditType,
+    pub sdg_flags: u32,
+    pub corresponding_adjustment: CorrespondingAdjustment,
+    pub verification_uri: String, // e.g. ipfs://<cid> of the verification report
+}
+
+/// Metadata layout stored before quantities were tracked (data version 0).
+#[derive(Clone)]
+#[contracttype]
+pub struct CarbonAssetMetadataV0 {
+    pub project_id: String,
+    pub vintage_year: u64,
+    pub methodology_id: u32,
+    pub geo_hash: BytesN<32>,
+}
+
+/// Registry serial block, e.g. prefix `VCS-1234-2021` with serials 1..=500.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SerialRange {
+    pub prefix: String,
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct IssuanceBatch {
+    pub batch_id: u32,
+    pub serial: SerialRange,
+    pub metadata: CarbonAssetMetadata,
+    pub token_id: u32,                // Owner's share of the range
+    pub buffer_token_id: Option<u32>, // Buffer pool's withheld share, if any
+    pub quantity_per_serial: i128,
+    pub issued_at: u64,
+}
+
+/// Serials, under the batch's prefix, that a token's quantity is drawn from.
+/// Pieces split off a token keep its range.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TokenSerial {
+    pub batch_id: u32,
+    pub start: u64,
+    pub end: u64,
+}
+
+/// One page of an index walk. Pass `next_cursor` back to continue; it is
+/// `None` once the end of the index has been reached.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TokenPage {
+    pub token_ids: Vec<u32>,
+    pub next_cursor: Option<u32>,
+}
+
+/// Owner's offer to sell a token. `currency` is the token contract the
+/// price is quoted in, e.g. a USDC SAC, and `price` is in its base units.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Listing {
+    pub seller: Address,
+    pub price: i128,
+    pub currency: Address,
+    pub listed_at: u64,
+}
+
+/// Optional constraints on which tokens an amount-based transfer may pick.
+/// Vintage bounds use the same timestamp encoding as `vintage_year`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TokenFilter {
+    pub min_vintage: Option<u64>,
+    pub max_vintage: Option<u64>,
+    pub project_id: Option<String>,
+    pub methodology_id: Option<u32>,
+    pub min_quality_score: Option<i128>,
+    pub country: Option<String>, // ISO 3166-1 alpha-2
+}
+
+// Admin handover waiting for the proposed address to accept it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PendingAdmin {
+    pub new_admin: Address,
+    pub expiration_ledger: u32,
+}
+
+// Privileges that can be held independently of the admin key.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Role {
+    Issuer,
+    StatusManager,
+    Oracle,
+    ConfigAdmin,
+    Pauser,
+}
+
+pub const ALL_ROLES: [Role; 5] = [
+    Role::Issuer,
+    Role::StatusManager,
+    Role::Oracle,
+    Role::ConfigAdmin,
+    Role::Pauser,
+];
+
+// Issuance-wide control applied to a whole project or one of its vintages.
+// It overrides the per-token status: `Frozen` reads as `Locked` and
+// `Invalidated` as `Invalidated`, except for credits already retired.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum IssuanceState {
+    Active,
+    Frozen,
+    Invalidated,
+}
+
+// Operations that can be halted by a pauser. `All` stops every one of them
+// regardless of the per-operation flags.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum PauseScope {
+    All,
+    Mint,
+    Transfer,
+    Retire,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum PickingPolicy {
+    Fifo,
+    OldestVintageFirst,
+    HighestQualityFirst,
 }
 
 // Shared with RegulatoryCheck contract for validation.
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub enum OperationType {
@@ -27,6 +203,29 @@ pub enum OperationType {
     RETIREMENT,
 }
 
+// Credit attributes sent with each compliance check so RegulatoryCheck rules
+// can match on vintage, methodology, country and adjustment status.
+#[derive(Clone)]
+#[contracttype]
+pub struct CreditContext {
+    pub token_id: u32,
+    pub project_id: String,
+    pub vintage_year: u64,
+    pub methodology_id: u32,
+    pub country: String,
+    pub corresponding_adjustment: CorrespondingAdjustment,
+}
+
+// Transfer parked until the regulatory authority approves it.
+#[derive(Clone)]
+#[contracttype]
+pub struct PendingTransfer {
+    pub token_id: u32,
+    pub from: Address,
+    pub to: Address,
+    pub operation: OperationType,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub struct ValidationResult {
@@ -44,12 +243,32 @@ pub struct AllowanceData {
     pub live_until_ledger: u32,
 }
 
-#[allow(dead_code)]
-pub trait CarbonAssetValueOracle {
-    fn update_quality_score(
-        env: Env,
-        caller: Address,
-        token_id: u32,
-        new_score: i128,
-    ) -> Result<(), super::errors::ContractError>;
+// How the latest fresh submission of each registered oracle is combined into
+// a token's quality score.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ScoreAggregation {
+    Median,
+    WeightedMean,
+}
+
+// `max_age` is in seconds, measured from a submission's `observed_at`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ScoreConfig {
+    pub method: ScoreAggregation,
+    pub max_age: u64,
+}
+
+// One entry of a token's score history. Oracle submissions carry the time of
+// the observation and a hash of the evidence behind it; manual overrides
+// through `update_quality_score` have no evidence.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ScoreSubmission {
+    pub source: Address,
+    pub score: i128,
+    pub observed_at: u64,
+    pub evidence_hash: Option<BytesN<32>>,
+    pub recorded_at: u64,
 }
@@ -0,0 +1,21 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    Unauthorized = 3,
+    EmptyBatch = 4,
+    CreditNotEligible = 5,
+    AlreadyDeposited = 6,
+    NotInPool = 7,
+    PoolTokenNotControlled = 8,
+    NoPendingAdmin = 9,
+    ProposalExpired = 10,
+    CreditInvalidated = 11,
+    NotReversed = 12,
+    NotInvalidated = 13,
+    CreditReplaced = 14,
+}
@@ -0,0 +1,71 @@
+use soroban_sdk::{contractevent, Address, BytesN, Vec};
+
+use crate::storage::TokenFilter;
+
+#[contractevent]
+pub struct DepositEvent {
+    pub depositor: Address,
+    pub token_ids: Vec<u32>,
+    pub quantity: i128,
+    pub pool_amount: i128,
+}
+
+#[contractevent]
+pub struct RedeemEvent {
+    pub holder: Address,
+    pub token_ids: Vec<u32>,
+    pub quantity: i128,
+    pub pool_amount: i128,
+}
+
+#[contractevent]
+pub struct PoolRetireEvent {
+    pub holder: Address,
+    pub token_ids: Vec<u32>,
+    pub quantity: i128,
+    pub pool_amount: i128,
+}
+
+#[contractevent]
+pub struct CreditReplacedEvent {
+    pub token_id: u32,
+    pub replacement_token_ids: Vec<u32>,
+    pub quantity: i128,
+}
+
+#[contractevent]
+pub struct WriteOffEvent {
+    pub token_id: u32,
+    pub depositor: Address,
+    pub quantity: i128,
+}
+
+#[contractevent]
+pub struct FilterUpdatedEvent {
+    pub filter: TokenFilter,
+    pub updated_by: Address,
+}
+
+#[contractevent]
+pub struct AdminProposedEvent {
+    pub current_admin: Address,
+    pub proposed_admin: Address,
+    pub expiration_ledger: u32,
+}
+
+#[contractevent]
+pub struct AdminTransferredEvent {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent]
+pub struct UpgradeEvent {
+    pub new_wasm_hash: BytesN<32>,
+}
+
+#[contractevent]
+pub struct MigrateEvent {
+    pub from_version: u32,
+    pub to_version: u32,
+}
@@ -0,0 +1,515 @@
+#![no_std]
+
+mod errors;
+mod events;
+mod storage;
+#[cfg(test)]
+mod test;
+
+use errors::Error;
+use events::*;
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::{
+    contract, contractimpl, Address, BytesN, Env, IntoVal, String, Symbol, Val, Vec,
+};
+use storage::*;
+
+/// Vault that turns unique CarbonAsset credits into a fungible Stellar asset.
+///
+/// Credits matching the pool's filter are deposited into the vault and the
+/// depositor receives pool tokens 1:1 by quantity. The pool token is a Stellar
+/// Asset Contract whose admin is this contract, so it trades on DEXes and AMMs
+/// like any other asset. Pool tokens are burned to redeem chosen credits or to
+/// retire them through `RetirementTracker`.
+#[contract]
+pub struct CreditPool;
+
+#[contractimpl]
+impl CreditPool {
+    /// One-time setup. `pool_token` must be a Stellar Asset Contract already
+    /// administered by this contract, since deposits mint through it.
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        carbon_asset: Address,
+        retirement_tracker: Address,
+        pool_token: Address,
+        filter: TokenFilter,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+
+        if get_admin(&env).is_some() {
+            return Err(Error::AlreadyInitialized);
+        }
+        if StellarAssetClient::new(&env, &pool_token).admin() != env.current_contract_address() {
+            return Err(Error::PoolTokenNotControlled);
+        }
+
+        set_admin(&env, &admin);
+        set_address(&env, &DataKey::CarbonAsset, &carbon_asset);
+        set_address(&env, &DataKey::RetirementTracker, &retirement_tracker);
+        set_address(&env, &DataKey::PoolToken, &pool_token);
+        storage::set_filter(&env, &filter);
+        set_data_version(&env, DATA_VERSION);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Propose a new admin. The handover only takes effect once `new_admin`
+    /// calls `accept_admin` on or before `expiration_ledger`.
+    pub fn propose_admin(
+        env: Env,
+        caller: Address,
+        new_admin: Address,
+        expiration_ledger: u32,
+    ) -> Result<(), Error> {
+        let admin = Self::require_admin(&env, &caller)?;
+
+        if expiration_ledger < env.ledger().sequence() {
+            return Err(Error::ProposalExpired);
+        }
+
+        set_pending_admin(
+            &env,
+            &PendingAdmin {
+                new_admin: new_admin.clone(),
+                expiration_ledger,
+            },
+        );
+
+        AdminProposedEvent {
+            current_admin: admin,
+            proposed_admin: new_admin,
+            expiration_ledger,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Complete a pending admin handover. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        new_admin.require_auth();
+
+        let pending = get_pending_admin(&env).ok_or(Error::NoPendingAdmin)?;
+        if pending.new_admin != new_admin {
+            return Err(Error::Unauthorized);
+        }
+        if env.ledger().sequence() > pending.expiration_ledger {
+            return Err(Error::ProposalExpired);
+        }
+
+        let previous_admin = get_admin(&env).ok_or(Error::NotInitialized)?;
+        set_admin(&env, &new_admin);
+        remove_pending_admin(&env);
+
+        AdminTransferredEvent {
+            previous_admin,
+            new_admin,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Replace the contract WASM in place, keeping the pooled credits and the
+    /// pool token's admin. Admin only.
+    pub fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        Self::require_admin(&env, &caller)?;
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        UpgradeEvent { new_wasm_hash }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Bring stored data up to the current layout version after an upgrade.
+    /// Returns the resulting version; a no-op when already current.
+    pub fn migrate(env: Env, caller: Address) -> Result<u32, Error> {
+        Self::require_admin(&env, &caller)?;
+
+        let from_version = get_data_version(&env);
+        if from_version >= DATA_VERSION {
+            return Ok(from_version);
+        }
+
+        set_data_version(&env, DATA_VERSION);
+
+        MigrateEvent {
+            from_version,
+            to_version: DATA_VERSION,
+        }
+        .publish(&env);
+
+        Ok(DATA_VERSION)
+    }
+
+    /// Change the eligibility criteria. Only future deposits are checked
+    /// against it; credits already pooled stay redeemable.
+    pub fn set_filter(env: Env, caller: Address, filter: TokenFilter) -> Result<(), Error> {
+        Self::require_admin(&env, &caller)?;
+
+        storage::set_filter(&env, &filter);
+
+        FilterUpdatedEvent {
+            filter,
+            updated_by: caller,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Move `token_ids` from `depositor` into the vault and mint pool tokens
+    /// for their combined quantity. Every credit must match the filter and be
+    /// transferable; one that is not fails the whole deposit. Returns the
+    /// pool token amount minted.
+    pub fn deposit(env: Env, depositor: Address, token_ids: Vec<u32>) -> Result<i128, Error> {
+        depositor.require_auth();
+
+        if token_ids.is_empty() {
+            return Err(Error::EmptyBatch);
+        }
+        let carbon_asset = Self::config(&env, DataKey::CarbonAsset)?;
+        let filter = storage::get_filter(&env).ok_or(Error::NotInitialized)?;
+
+        let mut quantity: i128 = 0;
+        for token_id in token_ids.iter() {
+            if get_credit(&env, token_id).is_some() {
+                return Err(Error::AlreadyDeposited);
+            }
+
+            let eligible: bool = env.invoke_contract(
+                &carbon_asset,
+                &Symbol::new(&env, "token_matches_filter"),
+                (token_id, filter.clone()).into_val(&env),
+            );
+            if !eligible {
+                return Err(Error::CreditNotEligible);
+            }
+            let credit_quantity: i128 = env.invoke_contract(
+                &carbon_asset,
+                &Symbol::new(&env, "quantity_of"),
+                (token_id,).into_val(&env),
+            );
+
+            Self::move_credit(
+                &env,
+                &carbon_asset,
+                &depositor,
+                &depositor,
+                &env.current_contract_address(),
+                token_id,
+            );
+            add_credit(
+                &env,
+                &PooledCredit {
+                    token_id,
+                    depositor: depositor.clone(),
+                    quantity: credit_quantity,
+                    deposited_at: env.ledger().timestamp(),
+                },
+            );
+            quantity += credit_quantity;
+        }
+
+        set_total_quantity(&env, get_total_quantity(&env) + quantity);
+        let pool_amount = quantity * POOL_UNITS_PER_CREDIT_UNIT;
+        let pool_token = Self::config(&env, DataKey::PoolToken)?;
+        StellarAssetClient::new(&env, &pool_token).mint(&depositor, &pool_amount);
+
+        DepositEvent {
+            depositor,
+            token_ids,
+            quantity,
+            pool_amount,
+        }
+        .publish(&env);
+
+        Ok(pool_amount)
+    }
+
+    /// Burn pool tokens from `holder` and hand them the chosen credits.
+    /// Returns the pool token amount burned.
+    pub fn redeem(env: Env, holder: Address, token_ids: Vec<u32>) -> Result<i128, Error> {
+        holder.require_auth();
+
+        let (quantity, pool_amount) = Self::withdraw(&env, &holder, &token_ids)?;
+
+        RedeemEvent {
+            holder,
+            token_ids,
+            quantity,
+            pool_amount,
+        }
+        .publish(&env);
+
+        Ok(pool_amount)
+    }
+
+    /// Burn pool tokens from `holder` and retire the chosen credits in their
+    /// name through `RetirementTracker`, which records `holder` as the
+    /// retiring entity. Returns the pool token amount burned.
+    pub fn retire(
+        env: Env,
+        holder: Address,
+        token_ids: Vec<u32>,
+        reason: Option<String>,
+    ) -> Result<i128, Error> {
+        holder.require_auth();
+
+        let (quantity, pool_amount) = Self::withdraw(&env, &holder, &token_ids)?;
+
+        let tracker = Self::config(&env, DataKey::RetirementTracker)?;
+        for token_id in token_ids.iter() {
+            env.invoke_contract::<Val>(
+                &tracker,
+                &Symbol::new(&env, "retire"),
+                (token_id, holder.clone(), reason.clone()).into_val(&env),
+            );
+        }
+
+        PoolRetireEvent {
+            holder,
+            token_ids,
+            quantity,
+            pool_amount,
+        }
+        .publish(&env);
+
+        Ok(pool_amount)
+    }
+
+    /// Swap a pooled credit that CarbonAsset has reversed for the credits it
+    /// was replaced with. `reverse` hands replacements to the credit's
+    /// holder, so they are already in the vault and cover the reversed
+    /// quantity; this records them against the same depositor. Anyone may
+    /// call it. Returns the replacement token ids now pooled.
+    pub fn replace_reversed(env: Env, token_id: u32) -> Result<Vec<u32>, Error> {
+        let credit = get_credit(&env, token_id).ok_or(Error::NotInPool)?;
+        let carbon_asset = Self::config(&env, DataKey::CarbonAsset)?;
+        let replacements: Vec<u32> = env.invoke_contract(
+            &carbon_asset,
+            &Symbol::new(&env, "get_replacements"),
+            (token_id,).into_val(&env),
+        );
+        if replacements.is_empty() {
+            return Err(Error::NotReversed);
+        }
+
+        remove_credit(&env, token_id);
+        let vault = env.current_contract_address();
+        let mut quantity: i128 = 0;
+        for replacement_token_id in replacements.iter() {
+            let owner: Address = env.invoke_contract(
+                &carbon_asset,
+                &Symbol::new(&env, "owner_of"),
+                (replacement_token_id,).into_val(&env),
+            );
+            if owner != vault {
+                return Err(Error::NotReversed);
+            }
+            let replacement_quantity: i128 = env.invoke_contract(
+                &carbon_asset,
+                &Symbol::new(&env, "quantity_of"),
+                (replacement_token_id,).into_val(&env),
+            );
+            add_credit(
+                &env,
+                &PooledCredit {
+                    token_id: replacement_token_id,
+                    depositor: credit.depositor.clone(),
+                    quantity: replacement_quantity,
+                    deposited_at: credit.deposited_at,
+                },
+            );
+            quantity += replacement_quantity;
+        }
+        set_total_quantity(&env, get_total_quantity(&env) - credit.quantity + quantity);
+
+        CreditReplacedEvent {
+            token_id,
+            replacement_token_ids: replacements.clone(),
+            quantity,
+        }
+        .publish(&env);
+
+        Ok(replacements)
+    }
+
+    /// Drop a pooled credit that CarbonAsset has invalidated without
+    /// replacing it, e.g. when its whole project was invalidated. Its
+    /// quantity moves from `total_quantity` to `written_off_quantity`; the
+    /// pool tokens minted against it stay in circulation, so the loss is
+    /// shared by every holder. Anyone may call it.
+    pub fn write_off(env: Env, token_id: u32) -> Result<i128, Error> {
+        let credit = get_credit(&env, token_id).ok_or(Error::NotInPool)?;
+        let carbon_asset = Self::config(&env, DataKey::CarbonAsset)?;
+        let status: AssetStatus = env.invoke_contract(
+            &carbon_asset,
+            &Symbol::new(&env, "get_status"),
+            (token_id,).into_val(&env),
+        );
+        if status != AssetStatus::Invalidated {
+            return Err(Error::NotInvalidated);
+        }
+        let replacements: Vec<u32> = env.invoke_contract(
+            &carbon_asset,
+            &Symbol::new(&env, "get_replacements"),
+            (token_id,).into_val(&env),
+        );
+        if !replacements.is_empty() {
+            return Err(Error::CreditReplaced);
+        }
+
+        remove_credit(&env, token_id);
+        set_total_quantity(&env, get_total_quantity(&env) - credit.quantity);
+        set_written_off_quantity(&env, get_written_off_quantity(&env) + credit.quantity);
+
+        WriteOffEvent {
+            token_id,
+            depositor: credit.depositor,
+            quantity: credit.quantity,
+        }
+        .publish(&env);
+
+        Ok(credit.quantity)
+    }
+
+    // -----------------------------------------------------------------------
+    // Queries
+    // -----------------------------------------------------------------------
+
+    pub fn get_admin(env: Env) -> Option<Address> {
+        get_admin(&env)
+    }
+
+    pub fn get_pending_admin(env: Env) -> Option<PendingAdmin> {
+        get_pending_admin(&env)
+    }
+
+    pub fn get_data_version(env: Env) -> u32 {
+        get_data_version(&env)
+    }
+
+    pub fn get_carbon_asset(env: Env) -> Option<Address> {
+        get_address(&env, &DataKey::CarbonAsset)
+    }
+
+    pub fn get_retirement_tracker(env: Env) -> Option<Address> {
+        get_address(&env, &DataKey::RetirementTracker)
+    }
+
+    pub fn get_pool_token(env: Env) -> Option<Address> {
+        get_address(&env, &DataKey::PoolToken)
+    }
+
+    pub fn get_filter(env: Env) -> Option<TokenFilter> {
+        storage::get_filter(&env)
+    }
+
+    /// Combined CarbonAsset quantity of the pooled credits. Pool token supply
+    /// is always this plus `written_off_quantity`, times
+    /// `POOL_UNITS_PER_CREDIT_UNIT`.
+    pub fn total_quantity(env: Env) -> i128 {
+        get_total_quantity(&env)
+    }
+
+    /// Quantity of invalidated credits dropped through `write_off`.
+    pub fn written_off_quantity(env: Env) -> i128 {
+        get_written_off_quantity(&env)
+    }
+
+    pub fn pooled_count(env: Env) -> u32 {
+        get_pooled_count(&env)
+    }
+
+    /// Up to `limit` (capped at `MAX_PAGE_SIZE`) pooled token ids from
+    /// `start`. Order changes as credits are withdrawn.
+    pub fn pooled_tokens(env: Env, start: u32, limit: u32) -> Vec<u32> {
+        let end = get_pooled_count(&env).min(start.saturating_add(limit.min(MAX_PAGE_SIZE)));
+        let mut tokens = Vec::new(&env);
+        for index in start..end {
+            tokens.push_back(get_pooled_token(&env, index));
+        }
+        tokens
+    }
+
+    pub fn get_credit(env: Env, token_id: u32) -> Option<PooledCredit> {
+        get_credit(&env, token_id)
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal helpers
+    // -----------------------------------------------------------------------
+
+    fn require_admin(env: &Env, caller: &Address) -> Result<Address, Error> {
+        caller.require_auth();
+        let admin = get_admin(env).ok_or(Error::NotInitialized)?;
+        if *caller != admin {
+            return Err(Error::Unauthorized);
+        }
+        Ok(admin)
+    }
+
+    fn config(env: &Env, key: DataKey) -> Result<Address, Error> {
+        get_address(env, &key).ok_or(Error::NotInitialized)
+    }
+
+    // Takes `token_ids` out of the pool, burns the matching pool tokens from
+    // `holder` and moves the credits to them. Returns the credit quantity and
+    // the pool token amount. A credit CarbonAsset has invalidated no longer
+    // backs anything and cannot be withdrawn; a reversed one is swapped for
+    // its replacements through `replace_reversed`.
+    fn withdraw(env: &Env, holder: &Address, token_ids: &Vec<u32>) -> Result<(i128, i128), Error> {
+        if token_ids.is_empty() {
+            return Err(Error::EmptyBatch);
+        }
+
+        let carbon_asset = Self::config(env, DataKey::CarbonAsset)?;
+        let mut quantity: i128 = 0;
+        for token_id in token_ids.iter() {
+            let credit = get_credit(env, token_id).ok_or(Error::NotInPool)?;
+            let status: AssetStatus = env.invoke_contract(
+                &carbon_asset,
+                &Symbol::new(env, "get_status"),
+                (token_id,).into_val(env),
+            );
+            if status == AssetStatus::Invalidated {
+                return Err(Error::CreditInvalidated);
+            }
+            remove_credit(env, token_id);
+            quantity += credit.quantity;
+        }
+        set_total_quantity(env, get_total_quantity(env) - quantity);
+
+        let pool_amount = quantity * POOL_UNITS_PER_CREDIT_UNIT;
+        let pool_token = Self::config(env, DataKey::PoolToken)?;
+        TokenClient::new(env, &pool_token).burn(holder, &pool_amount);
+
+        let vault = env.current_contract_address();
+        for token_id in token_ids.iter() {
+            Self::move_credit(env, &carbon_asset, &vault, &vault, holder, token_id);
+        }
+
+        Ok((quantity, pool_amount))
+    }
+
+    fn move_credit(
+        env: &Env,
+        carbon_asset: &Address,
+        spender: &Address,
+        from: &Address,
+        to: &Address,
+        token_id: u32,
+    ) {
+        env.invoke_contract::<()>(
+            carbon_asset,
+            &Symbol::new(env, "transfer_token_from"),
+            (spender.clone(), from.clone(), to.clone(), token_id).into_val(env),
+        );
+    }
+}
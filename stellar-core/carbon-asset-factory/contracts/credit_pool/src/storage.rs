@@ -0,0 +1,226 @@
+use soroban_sdk::{contracttype, Address, Env, IntoVal, String, TryFromVal, Val};
+
+const DAY_IN_LEDGERS: u32 = 17280; // Approximately 1 day worth of ledgers (5s per ledger)
+const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS; // 30 days
+const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+const PERSISTENT_BUMP_AMOUNT: u32 = 120 * DAY_IN_LEDGERS; // 120 days
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - 30 * DAY_IN_LEDGERS;
+
+/// Storage layout version written by this build. Bump it together with a
+/// migration step in `migrate` whenever the layout changes.
+pub const DATA_VERSION: u32 = 1;
+
+/// Pool token base units minted per CarbonAsset quantity unit. Credit
+/// quantities carry 3 decimals (kilograms) and Stellar assets 7, so one whole
+/// pool token stands for one tonne of deposited credits.
+pub const POOL_UNITS_PER_CREDIT_UNIT: i128 = 10_000;
+
+/// Upper bound on token ids returned by one page of `pooled_tokens`.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Eligibility criteria for deposits. Field for field the same as
+/// CarbonAsset's `TokenFilter`, so it is passed straight through to
+/// `token_matches_filter`. Vintage bounds use the `vintage_year` encoding.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenFilter {
+    pub min_vintage: Option<u64>,
+    pub max_vintage: Option<u64>,
+    pub project_id: Option<String>,
+    pub methodology_id: Option<u32>,
+    pub min_quality_score: Option<i128>,
+    pub country: Option<String>,
+}
+
+/// CarbonAsset's per-token status, variant for variant, as returned by its
+/// `get_status`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AssetStatus {
+    Issued,
+    Listed,
+    Locked,
+    Retired,
+    Invalidated,
+}
+
+/// A credit held by the pool and the pool tokens minted against it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PooledCredit {
+    pub token_id: u32,
+    pub depositor: Address,
+    pub quantity: i128,
+    pub deposited_at: u64,
+}
+
+/// Admin handover waiting for the proposed address to accept it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingAdmin {
+    pub new_admin: Address,
+    pub expiration_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    PendingAdmin,
+    CarbonAsset,
+    RetirementTracker,
+    PoolToken,
+    Filter,
+    DataVersion,
+    TotalQuantity,
+    WrittenOffQuantity,
+    // One entry per pooled credit, swap-removed on withdrawal.
+    PooledCount,
+    PooledToken(u32),
+    PooledIndex(u32),
+    Credit(u32),
+}
+
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+// Every persistent read and write goes through these helpers so that pooled
+// credits, and the instance holding the contract code, never drift towards
+// archival while they are still in custody.
+fn read_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        env.storage().persistent().extend_ttl(
+            key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+        extend_instance_ttl(env);
+    }
+    value
+}
+
+fn write_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage().persistent().extend_ttl(
+        key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+    extend_instance_ttl(env);
+}
+
+pub fn get_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Admin)
+}
+
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::Admin, admin);
+}
+
+pub fn get_pending_admin(env: &Env) -> Option<PendingAdmin> {
+    env.storage().instance().get(&DataKey::PendingAdmin)
+}
+
+pub fn set_pending_admin(env: &Env, pending: &PendingAdmin) {
+    env.storage()
+        .instance()
+        .set(&DataKey::PendingAdmin, pending);
+}
+
+pub fn remove_pending_admin(env: &Env) {
+    env.storage().instance().remove(&DataKey::PendingAdmin);
+}
+
+pub fn get_address(env: &Env, key: &DataKey) -> Option<Address> {
+    env.storage().instance().get(key)
+}
+
+pub fn set_address(env: &Env, key: &DataKey, address: &Address) {
+    env.storage().instance().set(key, address);
+}
+
+pub fn get_filter(env: &Env) -> Option<TokenFilter> {
+    env.storage().instance().get(&DataKey::Filter)
+}
+
+pub fn set_filter(env: &Env, filter: &TokenFilter) {
+    env.storage().instance().set(&DataKey::Filter, filter);
+}
+
+pub fn get_data_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::DataVersion)
+        .unwrap_or(0)
+}
+
+pub fn set_data_version(env: &Env, version: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::DataVersion, &version);
+}
+
+pub fn get_total_quantity(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::TotalQuantity)
+        .unwrap_or(0)
+}
+
+pub fn set_total_quantity(env: &Env, quantity: i128) {
+    env.storage()
+        .instance()
+        .set(&DataKey::TotalQuantity, &quantity);
+}
+
+pub fn get_written_off_quantity(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::WrittenOffQuantity)
+        .unwrap_or(0)
+}
+
+pub fn set_written_off_quantity(env: &Env, quantity: i128) {
+    env.storage()
+        .instance()
+        .set(&DataKey::WrittenOffQuantity, &quantity);
+}
+
+pub fn get_credit(env: &Env, token_id: u32) -> Option<PooledCredit> {
+    read_persistent(env, &DataKey::Credit(token_id))
+}
+
+pub fn get_pooled_count(env: &Env) -> u32 {
+    read_persistent(env, &DataKey::PooledCount).unwrap_or(0)
+}
+
+pub fn get_pooled_token(env: &Env, index: u32) -> u32 {
+    read_persistent(env, &DataKey::PooledToken(index)).unwrap()
+}
+
+pub fn add_credit(env: &Env, credit: &PooledCredit) {
+    let index = get_pooled_count(env);
+    write_persistent(env, &DataKey::PooledToken(index), &credit.token_id);
+    write_persistent(env, &DataKey::PooledIndex(credit.token_id), &index);
+    write_persistent(env, &DataKey::PooledCount, &(index + 1));
+    write_persistent(env, &DataKey::Credit(credit.token_id), credit);
+}
+
+pub fn remove_credit(env: &Env, token_id: u32) {
+    let index: u32 = read_persistent(env, &DataKey::PooledIndex(token_id)).unwrap();
+    let last = get_pooled_count(env) - 1;
+    if index != last {
+        let moved = get_pooled_token(env, last);
+        write_persistent(env, &DataKey::PooledToken(index), &moved);
+        write_persistent(env, &DataKey::PooledIndex(moved), &index);
+    }
+    let storage = env.storage().persistent();
+    storage.remove(&DataKey::PooledToken(last));
+    storage.remove(&DataKey::PooledIndex(token_id));
+    storage.remove(&DataKey::Credit(token_id));
+    write_persistent(env, &DataKey::PooledCount, &last);
+}
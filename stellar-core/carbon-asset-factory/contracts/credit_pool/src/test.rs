@@ -0,0 +1,493 @@
+#![cfg(test)]
+
+use crate::errors::Error;
+use crate::storage::{AssetStatus, DataKey, TokenFilter, POOL_UNITS_PER_CREDIT_UNIT};
+use crate::{CreditPool, CreditPoolClient};
+use buffer_pool::{BufferPoolContract, BufferPoolContractClient};
+use carbon_asset::types::{CarbonAssetMetadata, CorrespondingAdjustment, CreditType};
+use carbon_asset::{CarbonAsset, CarbonAssetClient};
+use soroban_sdk::testutils::storage::Persistent as _;
+use soroban_sdk::testutils::{Address as _, MockAuth, MockAuthInvoke};
+use soroban_sdk::token::TokenClient;
+use soroban_sdk::{
+    contract, contractimpl, symbol_short, vec, Address, BytesN, Env, IntoVal, String, Symbol, Val,
+    Vec,
+};
+
+// Minimal stand-in for CarbonAsset's per-token interface.
+#[contract]
+struct MockCarbonAsset;
+
+#[contractimpl]
+impl MockCarbonAsset {
+    pub fn mint(env: Env, owner: Address, token_id: u32, quantity: i128, quality_score: i128) {
+        let storage = env.storage().persistent();
+        storage.set(&(symbol_short!("owner"), token_id), &owner);
+        storage.set(&(symbol_short!("qty"), token_id), &quantity);
+        storage.set(&(symbol_short!("score"), token_id), &quality_score);
+    }
+
+    pub fn owner_of(env: Env, token_id: u32) -> Address {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("owner"), token_id))
+            .unwrap()
+    }
+
+    pub fn quantity_of(env: Env, token_id: u32) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("qty"), token_id))
+            .unwrap()
+    }
+
+    pub fn get_status(_env: Env, _token_id: u32) -> AssetStatus {
+        AssetStatus::Issued
+    }
+
+    pub fn token_matches_filter(env: Env, token_id: u32, filter: TokenFilter) -> bool {
+        let score: i128 = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("score"), token_id))
+            .unwrap();
+        filter.min_quality_score.is_none_or(|min| score >= min)
+    }
+
+    pub fn transfer_token_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        token_id: u32,
+    ) {
+        spender.require_auth();
+        assert_eq!(Self::owner_of(env.clone(), token_id), from);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("owner"), token_id), &to);
+    }
+}
+
+// Records who retired what, the way RetirementTracker attributes a retirement.
+#[contract]
+struct MockRetirementTracker;
+
+#[contractimpl]
+impl MockRetirementTracker {
+    pub fn retire(env: Env, token_id: u32, retiring_entity: Address, _reason: Option<String>) {
+        retiring_entity.require_auth();
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("retired"), token_id), &retiring_entity);
+    }
+
+    pub fn retired_by(env: Env, token_id: u32) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("retired"), token_id))
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    admin: Address,
+    carbon_asset: Address,
+    tracker: Address,
+    pool_token: Address,
+    client: CreditPoolClient<'a>,
+}
+
+fn quality_filter(min_quality_score: Option<i128>) -> TokenFilter {
+    TokenFilter {
+        min_vintage: None,
+        max_vintage: None,
+        project_id: None,
+        methodology_id: None,
+        min_quality_score,
+        country: None,
+    }
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let carbon_asset = env.register(MockCarbonAsset, ());
+    let tracker = env.register(MockRetirementTracker, ());
+    let pool_id = env.register(CreditPool, ());
+    let pool_token = env
+        .register_stellar_asset_contract_v2(pool_id.clone())
+        .address();
+
+    let client = CreditPoolClient::new(&env, &pool_id);
+    client.initialize(
+        &admin,
+        &carbon_asset,
+        &tracker,
+        &pool_token,
+        &quality_filter(Some(70)),
+    );
+
+    Setup {
+        env,
+        admin,
+        carbon_asset,
+        tracker,
+        pool_token,
+        client,
+    }
+}
+
+fn mint(s: &Setup, owner: &Address, token_id: u32, quantity: i128, quality_score: i128) {
+    s.env.invoke_contract::<()>(
+        &s.carbon_asset,
+        &Symbol::new(&s.env, "mint"),
+        (owner.clone(), token_id, quantity, quality_score).into_val(&s.env),
+    );
+}
+
+fn owner_of(s: &Setup, token_id: u32) -> Address {
+    s.env.invoke_contract(
+        &s.carbon_asset,
+        &Symbol::new(&s.env, "owner_of"),
+        (token_id,).into_val(&s.env),
+    )
+}
+
+#[test]
+fn test_initialize_requires_pool_token_admin() {
+    let s = setup();
+    let other_token = s
+        .env
+        .register_stellar_asset_contract_v2(s.admin.clone())
+        .address();
+    let client = CreditPoolClient::new(&s.env, &s.env.register(CreditPool, ()));
+
+    assert_eq!(
+        client.try_initialize(
+            &s.admin,
+            &s.carbon_asset,
+            &s.tracker,
+            &other_token,
+            &quality_filter(None),
+        ),
+        Err(Ok(Error::PoolTokenNotControlled))
+    );
+    assert_eq!(
+        s.client.try_initialize(
+            &s.admin,
+            &s.carbon_asset,
+            &s.tracker,
+            &s.pool_token,
+            &quality_filter(None),
+        ),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
+
+#[test]
+fn test_deposit_mints_pool_tokens_for_eligible_credits() {
+    let s = setup();
+    let depositor = Address::generate(&s.env);
+    mint(&s, &depositor, 1, 2_000, 80);
+    mint(&s, &depositor, 2, 500, 90);
+    mint(&s, &depositor, 3, 1_000, 40);
+
+    assert_eq!(
+        s.client.try_deposit(&depositor, &vec![&s.env, 1, 3]),
+        Err(Ok(Error::CreditNotEligible))
+    );
+    assert_eq!(owner_of(&s, 1), depositor);
+
+    let minted = s.client.deposit(&depositor, &vec![&s.env, 1, 2]);
+    assert_eq!(minted, 2_500 * POOL_UNITS_PER_CREDIT_UNIT);
+    assert_eq!(
+        TokenClient::new(&s.env, &s.pool_token).balance(&depositor),
+        minted
+    );
+    assert_eq!(owner_of(&s, 1), s.client.address);
+    assert_eq!(s.client.total_quantity(), 2_500);
+    assert_eq!(s.client.pooled_tokens(&0, &10), vec![&s.env, 1, 2]);
+    assert_eq!(s.client.get_credit(&2).unwrap().depositor, depositor);
+
+    assert_eq!(
+        s.client.try_deposit(&depositor, &vec![&s.env, 2]),
+        Err(Ok(Error::AlreadyDeposited))
+    );
+}
+
+#[test]
+fn test_redeem_and_retire_burn_pool_tokens() {
+    let s = setup();
+    let depositor = Address::generate(&s.env);
+    let trader = Address::generate(&s.env);
+    mint(&s, &depositor, 1, 1_000, 80);
+    mint(&s, &depositor, 2, 1_000, 80);
+    mint(&s, &depositor, 3, 3_000, 80);
+    s.client.deposit(&depositor, &vec![&s.env, 1, 2, 3]);
+
+    // Pool tokens trade freely; whoever holds them can claim any credit.
+    let pool_token = TokenClient::new(&s.env, &s.pool_token);
+    pool_token.transfer(&depositor, &trader, &(2_000 * POOL_UNITS_PER_CREDIT_UNIT));
+
+    assert_eq!(
+        s.client.try_redeem(&trader, &vec![&s.env, 4]),
+        Err(Ok(Error::NotInPool))
+    );
+    assert!(s.client.try_redeem(&trader, &vec![&s.env, 3]).is_err());
+
+    s.client.redeem(&trader, &vec![&s.env, 2]);
+    assert_eq!(owner_of(&s, 2), trader);
+    assert_eq!(
+        pool_token.balance(&trader),
+        1_000 * POOL_UNITS_PER_CREDIT_UNIT
+    );
+
+    let reason = Some(String::from_str(&s.env, "FY2025 offset"));
+    s.client.retire(&trader, &vec![&s.env, 1], &reason);
+    assert_eq!(pool_token.balance(&trader), 0);
+    let retired_by: Option<Address> = s.env.invoke_contract(
+        &s.tracker,
+        &Symbol::new(&s.env, "retired_by"),
+        (1u32,).into_val(&s.env),
+    );
+    assert_eq!(retired_by, Some(trader));
+
+    assert_eq!(s.client.total_quantity(), 3_000);
+    assert_eq!(s.client.pooled_tokens(&0, &10), vec![&s.env, 3]);
+    assert_eq!(s.client.get_credit(&1), None);
+}
+
+#[test]
+fn test_pooled_credits_stay_live() {
+    let s = setup();
+    let depositor = Address::generate(&s.env);
+    mint(&s, &depositor, 1, 1_000, 80);
+    s.client.deposit(&depositor, &vec![&s.env, 1]);
+
+    // Bumped to the pool's 120-day persistent lifetime, well past the
+    // network minimum a plain write gets.
+    let ttl = s.env.as_contract(&s.client.address, || {
+        s.env.storage().persistent().get_ttl(&DataKey::Credit(1))
+    });
+    assert!(ttl > 100 * 17280);
+}
+
+#[test]
+fn test_retire_with_explicit_holder_auth() {
+    let s = setup();
+    let depositor = Address::generate(&s.env);
+    mint(&s, &depositor, 1, 1_000, 80);
+    s.client.deposit(&depositor, &vec![&s.env, 1]);
+
+    let token_ids = vec![&s.env, 1u32];
+    let reason = Some(String::from_str(&s.env, "FY2025 offset"));
+    let pool_amount = 1_000 * POOL_UNITS_PER_CREDIT_UNIT;
+    let burn = MockAuthInvoke {
+        contract: &s.pool_token,
+        fn_name: "burn",
+        args: (depositor.clone(), pool_amount).into_val(&s.env),
+        sub_invokes: &[],
+    };
+    let tracker_retire = MockAuthInvoke {
+        contract: &s.tracker,
+        fn_name: "retire",
+        args: (1u32, depositor.clone(), reason.clone()).into_val(&s.env),
+        sub_invokes: &[],
+    };
+    let sub_invokes = [burn, tracker_retire];
+    let args: Vec<Val> = (depositor.clone(), token_ids.clone(), reason.clone()).into_val(&s.env);
+
+    // The holder's signature over the pool call alone does not cover the
+    // retirement the tracker records in their name.
+    s.env.mock_auths(&[MockAuth {
+        address: &depositor,
+        invoke: &MockAuthInvoke {
+            contract: &s.client.address,
+            fn_name: "retire",
+            args: args.clone(),
+            sub_invokes: &sub_invokes[..1],
+        },
+    }]);
+    assert!(s
+        .client
+        .try_retire(&depositor, &token_ids, &reason)
+        .is_err());
+
+    s.env.mock_auths(&[MockAuth {
+        address: &depositor,
+        invoke: &MockAuthInvoke {
+            contract: &s.client.address,
+            fn_name: "retire",
+            args,
+            sub_invokes: &sub_invokes,
+        },
+    }]);
+    s.client.retire(&depositor, &token_ids, &reason);
+    let retired_by: Option<Address> = s.env.invoke_contract(
+        &s.tracker,
+        &Symbol::new(&s.env, "retired_by"),
+        (1u32,).into_val(&s.env),
+    );
+    assert_eq!(retired_by, Some(depositor));
+    assert_eq!(s.client.total_quantity(), 0);
+}
+
+// A CreditPool over the real CarbonAsset and BufferPool, with half of each
+// mint withheld for the buffer.
+struct AssetSetup<'a> {
+    env: Env,
+    admin: Address,
+    depositor: Address,
+    pool_token: Address,
+    asset: CarbonAssetClient<'a>,
+    client: CreditPoolClient<'a>,
+}
+
+fn asset_setup<'a>() -> AssetSetup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let tracker = Address::generate(&env);
+
+    let asset_id = env.register(CarbonAsset, ());
+    let asset = CarbonAssetClient::new(&env, &asset_id);
+    asset.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &tracker,
+        &String::from_str(&env, "US"),
+    );
+    let buffer_id = env.register(BufferPoolContract, ());
+    BufferPoolContractClient::new(&env, &buffer_id).initialize(
+        &admin,
+        &Address::generate(&env),
+        &asset_id,
+        &5000, // 50%
+    );
+    asset.set_buffer_pool(&admin, &buffer_id);
+
+    let pool_id = env.register(CreditPool, ());
+    let pool_token = env
+        .register_stellar_asset_contract_v2(pool_id.clone())
+        .address();
+    let client = CreditPoolClient::new(&env, &pool_id);
+    client.initialize(
+        &admin,
+        &asset_id,
+        &tracker,
+        &pool_token,
+        &quality_filter(None),
+    );
+
+    let depositor = Address::generate(&env);
+    asset.set_approval_for_all(&depositor, &pool_id, &true);
+
+    AssetSetup {
+        env,
+        admin,
+        depositor,
+        pool_token,
+        asset,
+        client,
+    }
+}
+
+fn metadata(env: &Env) -> CarbonAssetMetadata {
+    CarbonAssetMetadata {
+        project_id: String::from_str(env, "PROJ-0"),
+        vintage_year: 1704067200,
+        methodology_id: 1,
+        geo_hash: BytesN::from_array(env, &[0u8; 32]),
+        registry: String::from_str(env, "VERRA"),
+        registry_serial: String::from_str(env, "VCS-0000-2024-001"),
+        issuance_date: 1735689600,
+        country: String::from_str(env, "BR"),
+        project_type: String::from_str(env, "REDD+"),
+        credit_type: CreditType::Avoidance,
+        sdg_flags: 0,
+        corresponding_adjustment: CorrespondingAdjustment::NotApplicable,
+        verification_uri: String::from_str(env, "ipfs://bafybeibverificationreport"),
+    }
+}
+
+#[test]
+fn test_reversed_credit_is_swapped_for_its_replacement() {
+    let s = asset_setup();
+    let env = &s.env;
+    let credit = s.asset.mint(&s.admin, &s.depositor, &metadata(env), &8_000);
+    let buffer_credit = credit + 1;
+    s.client.deposit(&s.depositor, &vec![env, credit]);
+    assert_eq!(
+        s.client.try_write_off(&credit),
+        Err(Ok(Error::NotInvalidated))
+    );
+
+    // The replacement goes to the credit's holder, the vault, and the
+    // invalidated credit can no longer be withdrawn.
+    s.asset
+        .reverse(&s.admin, &vec![env, credit], &vec![env, buffer_credit]);
+    assert_eq!(s.asset.owner_of(&buffer_credit), s.client.address);
+    assert_eq!(
+        s.client.try_redeem(&s.depositor, &vec![env, credit]),
+        Err(Ok(Error::CreditInvalidated))
+    );
+    assert_eq!(
+        s.client.try_write_off(&credit),
+        Err(Ok(Error::CreditReplaced))
+    );
+
+    assert_eq!(s.client.replace_reversed(&credit), vec![env, buffer_credit]);
+    assert_eq!(s.client.get_credit(&credit), None);
+    assert_eq!(
+        s.client.get_credit(&buffer_credit).unwrap().depositor,
+        s.depositor
+    );
+    assert_eq!(s.client.pooled_tokens(&0, &10), vec![env, buffer_credit]);
+    assert_eq!(s.client.total_quantity(), 4_000);
+    assert_eq!(
+        s.client.try_replace_reversed(&buffer_credit),
+        Err(Ok(Error::NotReversed))
+    );
+
+    s.client.redeem(&s.depositor, &vec![env, buffer_credit]);
+    assert_eq!(s.asset.owner_of(&buffer_credit), s.depositor);
+    assert_eq!(
+        TokenClient::new(env, &s.pool_token).balance(&s.depositor),
+        0
+    );
+}
+
+#[test]
+fn test_invalidated_credit_is_written_off() {
+    let s = asset_setup();
+    let env = &s.env;
+    let credit = s.asset.mint(&s.admin, &s.depositor, &metadata(env), &8_000);
+    s.client.deposit(&s.depositor, &vec![env, credit]);
+
+    s.asset
+        .invalidate_project(&s.admin, &metadata(env).project_id);
+    assert_eq!(
+        s.client.try_redeem(&s.depositor, &vec![env, credit]),
+        Err(Ok(Error::CreditInvalidated))
+    );
+    assert_eq!(
+        s.client.try_replace_reversed(&credit),
+        Err(Ok(Error::NotReversed))
+    );
+
+    assert_eq!(s.client.write_off(&credit), 4_000);
+    assert_eq!(s.client.get_credit(&credit), None);
+    assert_eq!(s.client.pooled_count(), 0);
+    assert_eq!(s.client.total_quantity(), 0);
+    assert_eq!(s.client.written_off_quantity(), 4_000);
+
+    // The pool tokens minted against it are still out there.
+    assert_eq!(
+        TokenClient::new(env, &s.pool_token).balance(&s.depositor),
+        4_000 * POOL_UNITS_PER_CREDIT_UNIT
+    );
+}
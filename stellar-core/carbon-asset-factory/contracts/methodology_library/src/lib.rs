@@ -1,9 +1,13 @@
 #![no_std]
 #![allow(deprecated)]
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, String, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env, String,
+    Vec,
 };
 
+/// Storage layout version written by this build. Bump together with a step in `migrate`.
+const DATA_VERSION: u32 = 1;
+
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 pub enum Error {
@@ -14,6 +18,8 @@ pub enum Error {
     TokenNotFound = 5,
     MetadataMismatch = 6,
     InvalidTransfer = 7,
+    NoPendingAdmin = 8,
+    ProposalExpired = 9,
 }
 
 #[contracttype]
@@ -27,6 +33,13 @@ pub struct MethodologyMeta {
     pub ipfs_cid: Option<String>,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingAdmin {
+    pub new_admin: Address,
+    pub expiration_ledger: u32,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
@@ -38,6 +51,8 @@ pub enum DataKey {
     Methodology(u32),
     Owner(u32),
     Approved(u32),
+    DataVersion,
+    PendingAdmin,
 }
 
 #[contract]
@@ -54,6 +69,7 @@ impl MethodologyLibrary {
         env.storage().persistent().set(&DataKey::Symbol, &symbol);
         env.storage().persistent().set(&DataKey::NextTokenId, &1u32);
         env.storage().persistent().set(&DataKey::Authorities, &Vec::<Address>::new(&env));
+        env.storage().persistent().set(&DataKey::DataVersion, &DATA_VERSION);
         Ok(())
     }
 
@@ -198,26 +214,89 @@ impl MethodologyLibrary {
         Ok(())
     }
 
-    pub fn transfer_admin(env: Env, admin_caller: Address, new_admin: Address) -> Result<(), Error> {
+    /// First step of an admin handover; `new_admin` must accept before `expiration_ledger`.
+    pub fn propose_admin(
+        env: Env,
+        admin_caller: Address,
+        new_admin: Address,
+        expiration_ledger: u32,
+    ) -> Result<(), Error> {
         admin_caller.require_auth();
         let admin: Address = env.storage().persistent().get(&DataKey::Admin).ok_or(Error::NotInitialized)?;
         if admin_caller != admin {
             return Err(Error::Unauthorized);
         }
+        if expiration_ledger < env.ledger().sequence() {
+            return Err(Error::ProposalExpired);
+        }
+
+        let pending = PendingAdmin { new_admin: new_admin.clone(), expiration_ledger };
+        env.storage().persistent().set(&DataKey::PendingAdmin, &pending);
+        env.events().publish((symbol_short!("adm_prop"),), (admin_caller, new_admin, expiration_ledger));
+        Ok(())
+    }
+
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        new_admin.require_auth();
+        let pending: PendingAdmin = env.storage().persistent().get(&DataKey::PendingAdmin).ok_or(Error::NoPendingAdmin)?;
+        if pending.new_admin != new_admin {
+            return Err(Error::Unauthorized);
+        }
+        if env.ledger().sequence() > pending.expiration_ledger {
+            return Err(Error::ProposalExpired);
+        }
+
+        let admin: Address = env.storage().persistent().get(&DataKey::Admin).ok_or(Error::NotInitialized)?;
         env.storage().persistent().set(&DataKey::Admin, &new_admin);
-        env.events().publish((symbol_short!("adm_trns"),), (admin_caller, new_admin));
+        env.storage().persistent().remove(&DataKey::PendingAdmin);
+        env.events().publish((symbol_short!("adm_trns"),), (admin, new_admin));
         Ok(())
     }
 
+    pub fn get_pending_admin(env: Env) -> Option<PendingAdmin> {
+        env.storage().persistent().get(&DataKey::PendingAdmin)
+    }
+
     pub fn get_admin(env: Env) -> Result<Address, Error> {
         env.storage().persistent().get(&DataKey::Admin).ok_or(Error::NotInitialized)
     }
+
+    pub fn upgrade(env: Env, admin_caller: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        admin_caller.require_auth();
+        let admin: Address = env.storage().persistent().get(&DataKey::Admin).ok_or(Error::NotInitialized)?;
+        if admin_caller != admin {
+            return Err(Error::Unauthorized);
+        }
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish((symbol_short!("upgrade"),), new_wasm_hash);
+        Ok(())
+    }
+
+    pub fn migrate(env: Env, admin_caller: Address) -> Result<u32, Error> {
+        admin_caller.require_auth();
+        let admin: Address = env.storage().persistent().get(&DataKey::Admin).ok_or(Error::NotInitialized)?;
+        if admin_caller != admin {
+            return Err(Error::Unauthorized);
+        }
+
+        let from_version = Self::get_data_version(env.clone());
+        if from_version >= DATA_VERSION {
+            return Ok(from_version);
+        }
+        env.storage().persistent().set(&DataKey::DataVersion, &DATA_VERSION);
+        env.events().publish((symbol_short!("migrate"),), (from_version, DATA_VERSION));
+        Ok(DATA_VERSION)
+    }
+
+    pub fn get_data_version(env: Env) -> u32 {
+        env.storage().persistent().get(&DataKey::DataVersion).unwrap_or(0)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::testutils::Address as _;
+    use soroban_sdk::testutils::{Address as _, Ledger};
     use soroban_sdk::{Env, String};
 
     #[test]
@@ -300,4 +379,37 @@ mod test {
         let result = client.try_mint_methodology(&non_authority, &owner, &meta);
         assert_eq!(result, Err(Ok(Error::NotAuthorizedAuthority)));
     }
+
+    #[test]
+    fn test_admin_handover() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let new_admin = Address::generate(&env);
+        let stranger = Address::generate(&env);
+
+        let contract_id = env.register(MethodologyLibrary, ());
+        let client = MethodologyLibraryClient::new(&env, &contract_id);
+
+        client.initialize(
+            &admin,
+            &String::from_str(&env, "Carbon methodology"),
+            &String::from_str(&env, "CSC-METH"),
+        );
+
+        let expiration = env.ledger().sequence() + 10;
+        client.propose_admin(&admin, &new_admin, &expiration);
+        assert_eq!(client.get_admin(), admin);
+        assert_eq!(client.try_accept_admin(&stranger), Err(Ok(Error::Unauthorized)));
+
+        env.ledger().with_mut(|li| li.sequence_number = expiration + 1);
+        assert_eq!(client.try_accept_admin(&new_admin), Err(Ok(Error::ProposalExpired)));
+
+        let expiration = env.ledger().sequence() + 10;
+        client.propose_admin(&admin, &new_admin, &expiration);
+        client.accept_admin(&new_admin);
+        assert_eq!(client.get_admin(), new_admin);
+        assert_eq!(client.try_add_authority(&admin, &stranger), Err(Ok(Error::Unauthorized)));
+    }
 }
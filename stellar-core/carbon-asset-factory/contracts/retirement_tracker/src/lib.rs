@@ -1,22 +1,175 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contracterror, contractevent, contractimpl, contracttype, Address, Bytes, BytesN,
-    Env, IntoVal, String, Symbol, Vec,
+    contract, contracterror, contractevent, contractimpl, contracttype, xdr::ToXdr, Address,
+    BytesN, Env, IntoVal, InvokeError, Map, String, Symbol, Val, Vec,
 };
 
+#[cfg(test)]
+mod test;
+
+/// Storage layout version written by this build. Bump together with a step in `migrate`.
+const DATA_VERSION: u32 = 2;
+
+/// CarbonAsset data version from which every token, burned or not, has a stored quantity
+const CARBON_ASSET_DATA_VERSION: u32 = 3;
+
 // ========================================================================
 // Data Structures
 // ========================================================================
 
 /// Core retirement record (immutable once written)
-#[derive(Clone)]
+///
+/// Records form a hash chain in `sequence` order. `record_hash` is the SHA-256 of the
+/// XDR encoding of `(prev_hash, sequence, token_id, retiring_entity, timestamp, quantity,
+/// operator, claim, reason)`, and the first record's `prev_hash` is all zeroes. Walking
+/// `get_record_by_sequence` from 1 and recomputing each hash verifies the whole log.
+#[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct RetirementRecord {
-    pub token_id: u32,            // ID of the retired CarbonAsset
-    pub retiring_entity: Address, // Stellar account who retired the credit
-    pub timestamp: u64,           // Ledger timestamp of retirement
-    pub tx_hash: BytesN<32>,      // Hash of the retirement transaction
-    pub reason: Option<String>,   // Optional field for corporate reporting
+    pub token_id: u32,             // ID of the retired CarbonAsset
+    pub retiring_entity: Address,  // Stellar account who retired the credit
+    pub timestamp: u64,            // Ledger timestamp of retirement
+    pub sequence: u64,             // Position in this contract's retirement log, from 1
+    pub prev_hash: BytesN<32>,     // record_hash of the previous record
+    pub record_hash: BytesN<32>,   // Chained hash committing to this record
+    pub quantity: i128,            // CarbonAsset units (kg CO2e) retired
+    pub operator: Option<Address>, // Spender who submitted a `retire_from`
+    pub claim: RecordedClaim,      // Claim or beneficiary the credit was retired for
+    pub group_id: Option<u64>,     // Grouped retirement from `retire_amount`, if any
+    pub reason: Option<String>,    // Optional field for corporate reporting
+}
+
+/// Record layout written before the hash chain (data version 1)
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RetirementRecordV1 {
+    pub token_id: u32,
+    pub retiring_entity: Address,
+    pub timestamp: u64,
+    pub tx_hash: BytesN<32>,
+    pub reason: Option<String>,
+}
+
+/// How `batch_retire` handles a token that cannot be retired
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum BatchMode {
+    Strict,     // Revert the whole batch
+    BestEffort, // Skip the token and report why
+}
+
+/// Per-token result of `batch_retire`
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum RetirementOutcome {
+    Retired(RetirementRecord),
+    Failed(u32, u32),       // token_id, ContractError code
+    BurnRejected(u32, u32), // token_id, CarbonAsset ContractError code from the burn
+}
+
+/// Who acted for whom in a `retire_from`; kept next to the token's RetirementRecord,
+/// whose `retiring_entity` is the beneficial owner
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DelegatedRetirement {
+    pub token_id: u32,
+    pub operator: Address,         // Spender who submitted the retirement
+    pub beneficial_owner: Address, // Holder whose credit was retired
+    pub beneficiary: String,       // Party the retirement is claimed for
+}
+
+/// Which credits `retire_amount` may pick. Field for field the same as
+/// CarbonAsset's `TokenFilter`; vintage bounds use its `vintage_year` encoding.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TokenFilter {
+    pub min_vintage: Option<u64>,
+    pub max_vintage: Option<u64>,
+    pub project_id: Option<String>,
+    pub methodology_id: Option<u32>,
+    pub min_quality_score: Option<i128>,
+    pub country: Option<String>, // ISO 3166-1 alpha-2
+}
+
+/// One `retire_amount` call: the tokens picked to cover the amount, retired together.
+/// Each token also gets its own `RetirementRecord`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct GroupedRetirement {
+    pub group_id: u64,
+    pub retiring_entity: Address,
+    pub token_ids: Vec<u32>,
+    pub total_quantity: i128, // CarbonAsset units (kg CO2e)
+    pub filter: TokenFilter,
+    pub timestamp: u64,
+    pub reason: Option<String>,
+}
+
+/// Standard under which a retirement is claimed
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ClaimStandard {
+    GhgProtocolScope1,
+    GhgProtocolScope2,
+    GhgProtocolScope3,
+    Corsia,
+    Sbti,
+}
+
+/// Claim a RetirementRecord commits to
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum RecordedClaim {
+    Unclaimed,
+    Claimed(RetirementClaim), // Retired through `retire_with_claim`
+    Beneficiary(String),      // Retired through `retire_from` for the named party
+}
+
+/// Claim details supplied by the retiring entity for a certificate
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RetirementClaim {
+    pub beneficiary: String, // Party the claim is made for; may differ from the signer
+    pub purpose: String,     // Free text shown on the certificate
+    pub claim_standard: ClaimStandard, // Framework the offset is reported under
+    pub period_start: u64,   // Start of the reporting period (Unix timestamp)
+    pub period_end: u64,     // End of the reporting period (Unix timestamp)
+    pub country_of_use: String, // ISO 3166-1 alpha-2
+}
+
+/// Retirement certificate, numbered sequentially from 1 and immutable once written.
+/// Carries everything off-chain tooling needs to render it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RetirementCertificate {
+    pub certificate_id: u64,
+    pub token_id: u32,
+    pub retiring_entity: Address,
+    pub claim: RetirementClaim,
+    pub timestamp: u64,
+    pub record_hash: BytesN<32>,
+}
+
+/// Certificate layout written before the hash chain (data version 1)
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RetirementCertificateV1 {
+    pub certificate_id: u64,
+    pub token_id: u32,
+    pub retiring_entity: Address,
+    pub claim: RetirementClaim,
+    pub timestamp: u64,
+    pub tx_hash: BytesN<32>,
+}
+
+/// Admin handover waiting for the proposed address to accept it
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PendingAdmin {
+    pub new_admin: Address,
+    pub expiration_ledger: u32,
 }
 
 /// Storage keys for the contract
@@ -27,13 +180,26 @@ pub enum DataKey {
     CarbonAssetContract,
     RetirementLedger(u32), // token_id -> RetirementRecord
     EntityIndex(Address),  // retiring_entity -> Vec<u32>
+    DataVersion,           // storage layout version
+    PendingAdmin,          // PendingAdmin awaiting acceptance
+    NextCertificateId,     // next certificate number to assign
+    Certificate(u64),      // certificate_id -> RetirementCertificate
+    TokenCertificate(u32), // token_id -> certificate_id
+    NextGroupId,           // next grouped retirement number to assign
+    Group(u64),            // group_id -> GroupedRetirement
+    TokenGroup(u32),       // token_id -> group_id
+    Delegation(u32),       // token_id -> DelegatedRetirement
+    LastSequence,          // sequence of the latest record, 0 before the first
+    ChainHead,             // record_hash of the latest record
+    SequenceToken(u64),    // sequence -> token_id
+    LegacyRecordCount,     // number of version 1 records `migrate` must link
 }
 
 // ========================================================================
 // Contract Errors
 // ========================================================================
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[contracterror]
 pub enum ContractError {
     NotAuthorized = 1,
@@ -42,6 +208,76 @@ pub enum ContractError {
     InvalidTokenId = 4,
     BurnFailed = 5,
     ContractNotInitialized = 6,
+    NoPendingAdmin = 7,
+    AdminProposalExpired = 8,
+    InvalidClaim = 9,
+    EmptyBatch = 10,
+    InvalidAmount = 11,
+    InsufficientCredits = 12,
+    MigrationPending = 13,
+    MigrationOutOfOrder = 14,
+    TokenFrozen = 15,
+    TokenListed = 16,
+    IssuanceFrozen = 17,
+    AssetPaused = 18,
+    NotApproved = 19,
+    AssetMigrationPending = 20,
+    SelectionFailed = 21,
+    LegacyCountMismatch = 22,
+}
+
+/// CarbonAsset's ContractError codes that this contract reports as errors of its own
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracterror]
+pub enum CarbonAssetError {
+    TokenNotFound = 4,
+    NotOwner = 5,
+    TransferNotAllowed = 6, // From `select_for_retirement`: matching credits fall short
+    StatusFrozen = 7,
+    TokenAlreadyBurned = 12,
+    ContractPaused = 29,
+    IssuanceFrozen = 30,
+    TokenListed = 32,
+    NotApproved = 35,
+    MigrationPending = 40,
+}
+
+impl From<CarbonAssetError> for ContractError {
+    fn from(error: CarbonAssetError) -> Self {
+        match error {
+            CarbonAssetError::TokenNotFound => ContractError::InvalidTokenId,
+            CarbonAssetError::NotOwner => ContractError::TokenNotOwned,
+            CarbonAssetError::TransferNotAllowed => ContractError::InsufficientCredits,
+            CarbonAssetError::StatusFrozen => ContractError::TokenFrozen,
+            CarbonAssetError::TokenAlreadyBurned => ContractError::TokenAlreadyRetired,
+            CarbonAssetError::ContractPaused => ContractError::AssetPaused,
+            CarbonAssetError::IssuanceFrozen => ContractError::IssuanceFrozen,
+            CarbonAssetError::TokenListed => ContractError::TokenListed,
+            CarbonAssetError::NotApproved => ContractError::NotApproved,
+            CarbonAssetError::MigrationPending => ContractError::AssetMigrationPending,
+        }
+    }
+}
+
+/// Why `retire_internal` did not retire a token
+enum RetireError {
+    Tracker(ContractError), // Refused by this contract
+    // CarbonAsset's ContractError code from the burn, and the error it is reported as
+    Burn(u32, ContractError),
+}
+
+impl From<ContractError> for RetireError {
+    fn from(error: ContractError) -> Self {
+        RetireError::Tracker(error)
+    }
+}
+
+impl From<RetireError> for ContractError {
+    fn from(error: RetireError) -> Self {
+        match error {
+            RetireError::Tracker(error) | RetireError::Burn(_, error) => error,
+        }
+    }
 }
 
 // ========================================================================
@@ -53,7 +289,42 @@ pub struct RetirementEvent {
     pub token_id: u32,
     pub retiring_entity: Address,
     pub timestamp: u64,
-    pub tx_hash: BytesN<32>,
+    pub sequence: u64,
+    pub record_hash: BytesN<32>,
+}
+
+/// One per `batch_retire` call; `total_quantity` is in CarbonAsset units (kg CO2e)
+#[contractevent]
+pub struct BatchRetirementEvent {
+    pub retiring_entity: Address,
+    pub requested_count: u32,
+    pub retired_count: u32,
+    pub total_quantity: i128,
+}
+
+/// One per `retire_amount` call; `total_quantity` is in CarbonAsset units (kg CO2e)
+#[contractevent]
+pub struct AmountRetirementEvent {
+    pub group_id: u64,
+    pub retiring_entity: Address,
+    pub token_count: u32,
+    pub total_quantity: i128,
+}
+
+#[contractevent]
+pub struct DelegatedRetirementEvent {
+    pub token_id: u32,
+    pub operator: Address,
+    pub beneficial_owner: Address,
+    pub beneficiary: String,
+}
+
+#[contractevent]
+pub struct CertificateIssuedEvent {
+    pub certificate_id: u64,
+    pub token_id: u32,
+    pub retiring_entity: Address,
+    pub beneficiary: String,
 }
 
 #[contractevent]
@@ -63,6 +334,30 @@ pub struct ContractUpdatedEvent {
     pub updated_by: Address,
 }
 
+#[contractevent]
+pub struct AdminProposedEvent {
+    pub current_admin: Address,
+    pub proposed_admin: Address,
+    pub expiration_ledger: u32,
+}
+
+#[contractevent]
+pub struct AdminTransferredEvent {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent]
+pub struct UpgradeEvent {
+    pub new_wasm_hash: BytesN<32>,
+}
+
+#[contractevent]
+pub struct MigrateEvent {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
 // ========================================================================
 // Contract Implementation
 // ========================================================================
@@ -89,6 +384,9 @@ impl RetirementTracker {
         env.storage()
             .instance()
             .set(&DataKey::CarbonAssetContract, &carbon_asset_contract);
+        env.storage()
+            .instance()
+            .set(&DataKey::DataVersion, &DATA_VERSION);
     }
 
     /// Retire a single carbon credit token
@@ -102,9 +400,16 @@ impl RetirementTracker {
     /// The RetirementRecord created for this retirement
     ///
     /// # Errors
-    /// * `ContractError::TokenNotOwned` - Caller does not own the token
+    /// * `ContractError::TokenNotOwned` - `retiring_entity` does not own the token
+    /// * `ContractError::InvalidTokenId` - The token does not exist
     /// * `ContractError::TokenAlreadyRetired` - Token has already been retired
-    /// * `ContractError::BurnFailed` - Failed to burn the token
+    /// * `ContractError::TokenFrozen` - The token has been invalidated
+    /// * `ContractError::TokenListed` - The token is listed for sale
+    /// * `ContractError::IssuanceFrozen` - The token's project or vintage is frozen
+    /// * `ContractError::AssetPaused` - Retirement is paused on CarbonAsset
+    /// * `ContractError::MigrationPending` - `migrate` has not finished linking legacy records
+    /// * `ContractError::AssetMigrationPending` - CarbonAsset has not finished its migration
+    /// * `ContractError::BurnFailed` - CarbonAsset refused the burn for any other reason
     pub fn retire(
         env: Env,
         token_id: u32,
@@ -114,82 +419,149 @@ impl RetirementTracker {
         // Verify caller is authenticated
         retiring_entity.require_auth();
 
-        // Check if token is already retired
-        let ledger_key = DataKey::RetirementLedger(token_id);
-        if env.storage().persistent().has(&ledger_key) {
-            return Err(ContractError::TokenAlreadyRetired);
-        }
+        Self::retire_internal(
+            env.clone(),
+            token_id,
+            retiring_entity,
+            reason,
+            None,
+            RecordedClaim::Unclaimed,
+            None,
+        )
+        .map_err(ContractError::from)
+    }
 
-        // Get carbon asset contract address
-        let carbon_asset_contract: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::CarbonAssetContract)
-            .ok_or(ContractError::ContractNotInitialized)?;
+    /// Retire a token on its owner's behalf
+    ///
+    /// The `spender` must be the token's approved spender or one of the owner's
+    /// operators on CarbonAsset, or hold a CarbonAsset allowance from the owner
+    /// covering the token's quantity, which is then spent. The retirement is
+    /// recorded against the owner, and the operator is kept alongside it.
+    ///
+    /// # Arguments
+    /// * `spender` - The broker or platform account submitting the retirement
+    /// * `owner` - The holder of the credit, recorded as the retiring entity
+    /// * `token_id` - The ID of the CarbonAsset token to retire
+    /// * `beneficiary` - Party the retirement is claimed for
+    ///
+    /// # Returns
+    /// The RetirementRecord written for the token
+    ///
+    /// # Errors
+    /// * `ContractError::NotApproved` - The spender is neither approved for the token nor
+    ///   holds an allowance covering it
+    /// * Any error returned by `retire`
+    pub fn retire_from(
+        env: Env,
+        spender: Address,
+        owner: Address,
+        token_id: u32,
+        beneficiary: String,
+    ) -> Result<RetirementRecord, ContractError> {
+        spender.require_auth();
+
+        let record = Self::retire_internal(
+            env.clone(),
+            token_id,
+            owner.clone(),
+            None,
+            Some(spender.clone()),
+            RecordedClaim::Beneficiary(beneficiary.clone()),
+            None,
+        )
+        .map_err(ContractError::from)?;
 
-        // Get current timestamp
-        let timestamp = env.ledger().timestamp();
+        let delegation = DelegatedRetirement {
+            token_id,
+            operator: spender.clone(),
+            beneficial_owner: owner.clone(),
+            beneficiary: beneficiary.clone(),
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Delegation(token_id), &delegation);
+
+        DelegatedRetirementEvent {
+            token_id,
+            operator: spender,
+            beneficial_owner: owner,
+            beneficiary,
+        }
+        .publish(&env);
+        Ok(record)
+    }
 
-        // Generate a unique transaction hash from current ledger state
-        // This combines ledger sequence, timestamp, and token_id for uniqueness
-        // Note: In production, you might want to pass the actual transaction hash as a parameter
-        // For now, we create a deterministic hash from available ledger data
-        let ledger_seq = env.ledger().sequence();
+    /// Retire a token and issue a numbered certificate for a corporate claim
+    ///
+    /// # Arguments
+    /// * `token_id` - The ID of the CarbonAsset token to retire
+    /// * `retiring_entity` - The Stellar account address retiring the credit
+    /// * `claim` - Beneficiary, purpose, claim standard, reporting period and country of use
+    ///
+    /// # Returns
+    /// The RetirementCertificate issued for this retirement
+    ///
+    /// # Errors
+    /// * `ContractError::InvalidClaim` - Missing beneficiary or country, or an inverted period
+    /// * Any error returned by `retire`
+    pub fn retire_with_claim(
+        env: Env,
+        token_id: u32,
+        retiring_entity: Address,
+        claim: RetirementClaim,
+    ) -> Result<RetirementCertificate, ContractError> {
+        retiring_entity.require_auth();
 
-        // Create hash input from components as a byte array
-        // We'll manually construct bytes from token_id, timestamp, and ledger_seq
-        let mut hash_bytes = [0u8; 20];
-        hash_bytes[0..4].copy_from_slice(&token_id.to_be_bytes());
-        hash_bytes[4..12].copy_from_slice(&timestamp.to_be_bytes());
-        hash_bytes[12..20].copy_from_slice(&ledger_seq.to_be_bytes());
+        if claim.beneficiary.is_empty()
+            || claim.country_of_use.len() != 2
+            || claim.period_end < claim.period_start
+        {
+            return Err(ContractError::InvalidClaim);
+        }
 
-        let hash_input = Bytes::from_array(&env, &hash_bytes);
-        let hash = env.crypto().sha256(&hash_input);
-        let tx_hash = BytesN::from_array(&env, &hash.to_array());
+        let record = Self::retire_internal(
+            env.clone(),
+            token_id,
+            retiring_entity.clone(),
+            Some(claim.purpose.clone()),
+            None,
+            RecordedClaim::Claimed(claim.clone()),
+            None,
+        )
+        .map_err(ContractError::from)?;
 
-        // Call burn_token on CarbonAsset contract
-        // The contract must be pre-authorized as a burner on the CarbonAsset contract
-        // We assume CarbonAsset has a burn_token function that accepts (token_id: u32, from: Address)
-        // The CarbonAsset contract should verify ownership before allowing burn
-        let burn_symbol = Symbol::new(&env, "burn_token");
-        let mut burn_args = Vec::new(&env);
-        burn_args.push_back(token_id.into_val(&env));
-        burn_args.push_back(retiring_entity.clone().into_val(&env));
-        env.invoke_contract::<()>(&carbon_asset_contract, &burn_symbol, burn_args);
+        let certificate_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextCertificateId)
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&DataKey::NextCertificateId, &(certificate_id + 1));
 
-        // Create retirement record
-        let record = RetirementRecord {
+        let certificate = RetirementCertificate {
+            certificate_id,
             token_id,
             retiring_entity: retiring_entity.clone(),
-            timestamp,
-            tx_hash: tx_hash.clone(),
-            reason: reason.clone(),
+            claim: claim.clone(),
+            timestamp: record.timestamp,
+            record_hash: record.record_hash,
         };
-
-        // Store in retirement ledger
-        env.storage().persistent().set(&ledger_key, &record);
-
-        // Update entity index
-        let entity_key = DataKey::EntityIndex(retiring_entity.clone());
-        let mut entity_retirements: Vec<u32> = env
-            .storage()
+        env.storage()
             .persistent()
-            .get(&entity_key)
-            .unwrap_or(Vec::new(&env));
-        entity_retirements.push_back(token_id);
+            .set(&DataKey::Certificate(certificate_id), &certificate);
         env.storage()
             .persistent()
-            .set(&entity_key, &entity_retirements);
+            .set(&DataKey::TokenCertificate(token_id), &certificate_id);
 
-        // Emit event
-        RetirementEvent {
+        CertificateIssuedEvent {
+            certificate_id,
             token_id,
-            retiring_entity: retiring_entity.clone(),
-            timestamp,
-            tx_hash,
+            retiring_entity,
+            beneficiary: claim.beneficiary,
         }
         .publish(&env);
-        Ok(record)
+        Ok(certificate)
     }
 
     /// Retire multiple carbon credit tokens in a single transaction
@@ -198,38 +570,171 @@ impl RetirementTracker {
     /// * `token_ids` - Vector of token IDs to retire
     /// * `retiring_entity` - The Stellar account address retiring the credits
     /// * `reason` - Optional reason for retirement (applied to all tokens)
+    /// * `mode` - `Strict` reverts the whole batch on the first failure;
+    ///   `BestEffort` retires what it can and reports the rest
     ///
     /// # Returns
-    /// Vector of RetirementRecords created
+    /// One outcome per requested token, in request order. In `BestEffort` mode a token
+    /// this contract refuses is `Failed`, and one CarbonAsset would not burn is
+    /// `BurnRejected` with CarbonAsset's error code.
     ///
     /// # Errors
-    /// Returns errors for individual tokens that fail, but continues processing others
+    /// * `ContractError::EmptyBatch` - No token IDs were given
+    /// * In `Strict` mode, the first error returned by `retire`
     pub fn batch_retire(
         env: Env,
         token_ids: Vec<u32>,
         retiring_entity: Address,
         reason: Option<String>,
-    ) -> Vec<RetirementRecord> {
+        mode: BatchMode,
+    ) -> Result<Vec<RetirementOutcome>, ContractError> {
         retiring_entity.require_auth();
 
-        let mut results = Vec::new(&env);
+        if token_ids.is_empty() {
+            return Err(ContractError::EmptyBatch);
+        }
 
-        for i in 0..token_ids.len() {
-            let token_id = token_ids.get(i).unwrap();
+        let mut outcomes = Vec::new(&env);
+        let mut retired_count: u32 = 0;
+        let mut total_quantity: i128 = 0;
 
-            // Attempt to retire each token
-            // Continue even if one fails
-            if let Ok(record) = Self::retire(
+        for token_id in token_ids.iter() {
+            match Self::retire_internal(
                 env.clone(),
                 token_id,
                 retiring_entity.clone(),
                 reason.clone(),
+                None,
+                RecordedClaim::Unclaimed,
+                None,
             ) {
-                results.push_back(record);
+                Ok(record) => {
+                    retired_count += 1;
+                    total_quantity += record.quantity;
+                    outcomes.push_back(RetirementOutcome::Retired(record));
+                }
+                Err(error) if mode == BatchMode::Strict => return Err(error.into()),
+                Err(RetireError::Tracker(error)) => {
+                    outcomes.push_back(RetirementOutcome::Failed(token_id, error as u32));
+                }
+                Err(RetireError::Burn(code, _)) => {
+                    outcomes.push_back(RetirementOutcome::BurnRejected(token_id, code));
+                }
             }
         }
 
-        results
+        BatchRetirementEvent {
+            retiring_entity,
+            requested_count: token_ids.len(),
+            retired_count,
+            total_quantity,
+        }
+        .publish(&env);
+        Ok(outcomes)
+    }
+
+    /// Retire an amount of credits without naming token IDs
+    ///
+    /// CarbonAsset picks the entity's Issued tokens that match `filter`, in the
+    /// entity's picking order, and splits the last one so the total is exact.
+    /// Every picked token is burned through `burn_token` and recorded as usual.
+    ///
+    /// # Arguments
+    /// * `retiring_entity` - The Stellar account address retiring the credits
+    /// * `amount` - Quantity to retire in CarbonAsset units (1,000 per tonne CO2e)
+    /// * `filter` - Vintage range, project, methodology, country and minimum quality score
+    /// * `reason` - Optional reason for retirement (applied to all tokens)
+    ///
+    /// # Returns
+    /// The GroupedRetirement recorded for this call
+    ///
+    /// # Errors
+    /// * `ContractError::InvalidAmount` - `amount` is not positive
+    /// * `ContractError::InsufficientCredits` - Matching credits do not cover `amount`
+    /// * `ContractError::AssetPaused` - Retirement is paused on CarbonAsset
+    /// * `ContractError::AssetMigrationPending` - CarbonAsset has not finished its migration
+    /// * `ContractError::SelectionFailed` - CarbonAsset could not pick credits for any
+    ///   other reason
+    /// * Any error returned by `retire`
+    pub fn retire_amount(
+        env: Env,
+        retiring_entity: Address,
+        amount: i128,
+        filter: TokenFilter,
+        reason: Option<String>,
+    ) -> Result<GroupedRetirement, ContractError> {
+        retiring_entity.require_auth();
+
+        if amount <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        let carbon_asset_contract: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::CarbonAssetContract)
+            .ok_or(ContractError::ContractNotInitialized)?;
+
+        let mut args = Vec::new(&env);
+        args.push_back(retiring_entity.clone().into_val(&env));
+        args.push_back(amount.into_val(&env));
+        args.push_back(filter.clone().into_val(&env));
+        let token_ids = match env.try_invoke_contract::<Vec<u32>, CarbonAssetError>(
+            &carbon_asset_contract,
+            &Symbol::new(&env, "select_for_retirement"),
+            args,
+        ) {
+            Ok(Ok(token_ids)) => token_ids,
+            Err(Ok(error)) => return Err(error.into()),
+            _ => return Err(ContractError::SelectionFailed),
+        };
+
+        let group_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextGroupId)
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&DataKey::NextGroupId, &(group_id + 1));
+
+        for token_id in token_ids.iter() {
+            Self::retire_internal(
+                env.clone(),
+                token_id,
+                retiring_entity.clone(),
+                reason.clone(),
+                None,
+                RecordedClaim::Unclaimed,
+                Some(group_id),
+            )
+            .map_err(ContractError::from)?;
+            env.storage()
+                .persistent()
+                .set(&DataKey::TokenGroup(token_id), &group_id);
+        }
+
+        let group = GroupedRetirement {
+            group_id,
+            retiring_entity: retiring_entity.clone(),
+            token_ids: token_ids.clone(),
+            total_quantity: amount,
+            filter,
+            timestamp: env.ledger().timestamp(),
+            reason,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Group(group_id), &group);
+
+        AmountRetirementEvent {
+            group_id,
+            retiring_entity,
+            token_count: token_ids.len(),
+            total_quantity: amount,
+        }
+        .publish(&env);
+        Ok(group)
     }
 
     /// Check if a token has been retired
@@ -256,6 +761,77 @@ impl RetirementTracker {
         env.storage().persistent().get(&ledger_key)
     }
 
+    /// Get the operator and beneficiary of a token retired through `retire_from`
+    pub fn get_delegated_retirement(env: Env, token_id: u32) -> Option<DelegatedRetirement> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Delegation(token_id))
+    }
+
+    /// Get a retirement certificate by its number
+    pub fn get_certificate(env: Env, certificate_id: u64) -> Option<RetirementCertificate> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Certificate(certificate_id))
+    }
+
+    /// Get the certificate issued when a token was retired, if it was retired with a claim
+    pub fn get_certificate_for_token(env: Env, token_id: u32) -> Option<RetirementCertificate> {
+        let certificate_id: u64 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::TokenCertificate(token_id))?;
+        Self::get_certificate(env, certificate_id)
+    }
+
+    /// Number of certificates issued so far
+    pub fn certificate_count(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get::<_, u64>(&DataKey::NextCertificateId)
+            .unwrap_or(1)
+            - 1
+    }
+
+    /// Get the record at a position in the retirement log
+    pub fn get_record_by_sequence(env: Env, sequence: u64) -> Option<RetirementRecord> {
+        let token_id: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SequenceToken(sequence))?;
+        Self::get_retirement_record(env, token_id)
+    }
+
+    /// Sequence number of the latest retirement (0 before the first)
+    pub fn last_sequence(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::LastSequence)
+            .unwrap_or(0)
+    }
+
+    /// `record_hash` of the latest retirement, or all zeroes before the first
+    pub fn get_chain_head(env: Env) -> BytesN<32> {
+        env.storage()
+            .instance()
+            .get(&DataKey::ChainHead)
+            .unwrap_or(BytesN::from_array(&env, &[0u8; 32]))
+    }
+
+    /// Get a grouped retirement by its number
+    pub fn get_grouped_retirement(env: Env, group_id: u64) -> Option<GroupedRetirement> {
+        env.storage().persistent().get(&DataKey::Group(group_id))
+    }
+
+    /// Get the grouped retirement a token was retired in, if it was retired by amount
+    pub fn get_group_for_token(env: Env, token_id: u32) -> Option<GroupedRetirement> {
+        let group_id: u64 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::TokenGroup(token_id))?;
+        Self::get_grouped_retirement(env, group_id)
+    }
+
     /// Get all token IDs retired by a specific entity
     ///
     /// # Arguments
@@ -320,6 +896,205 @@ impl RetirementTracker {
         Ok(())
     }
 
+    /// Propose a new admin (first step of a two-step handover)
+    ///
+    /// # Arguments
+    /// * `new_admin` - Address that must call `accept_admin`
+    /// * `expiration_ledger` - Last ledger on which the proposal can be accepted
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    /// * `ContractError::AdminProposalExpired` - Expiration ledger already passed
+    pub fn propose_admin(
+        env: Env,
+        caller: Address,
+        new_admin: Address,
+        expiration_ledger: u32,
+    ) -> Result<(), ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        if expiration_ledger < env.ledger().sequence() {
+            return Err(ContractError::AdminProposalExpired);
+        }
+
+        env.storage().instance().set(
+            &DataKey::PendingAdmin,
+            &PendingAdmin {
+                new_admin: new_admin.clone(),
+                expiration_ledger,
+            },
+        );
+
+        AdminProposedEvent {
+            current_admin: caller,
+            proposed_admin: new_admin,
+            expiration_ledger,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Accept a pending admin proposal (second step of the handover)
+    ///
+    /// # Errors
+    /// * `ContractError::NoPendingAdmin` - Nothing has been proposed
+    /// * `ContractError::NotAuthorized` - Caller is not the proposed admin
+    /// * `ContractError::AdminProposalExpired` - Proposal is past its expiration ledger
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), ContractError> {
+        new_admin.require_auth();
+
+        let pending: PendingAdmin = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .ok_or(ContractError::NoPendingAdmin)?;
+
+        if pending.new_admin != new_admin {
+            return Err(ContractError::NotAuthorized);
+        }
+        if env.ledger().sequence() > pending.expiration_ledger {
+            return Err(ContractError::AdminProposalExpired);
+        }
+
+        let previous_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(ContractError::ContractNotInitialized)?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        AdminTransferredEvent {
+            previous_admin,
+            new_admin,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Get the pending admin proposal, if any
+    pub fn get_pending_admin(env: Env) -> Option<PendingAdmin> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    /// Replace the contract WASM, keeping its address and storage
+    ///
+    /// # Arguments
+    /// * `new_wasm_hash` - Hash of WASM already uploaded to the network
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    pub fn upgrade(
+        env: Env,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        UpgradeEvent { new_wasm_hash }.publish(&env);
+        Ok(())
+    }
+
+    /// Bring stored data up to the current layout version after an upgrade
+    ///
+    /// Version 1 records carry a `tx_hash` instead of a place in the hash chain. Pass
+    /// their token IDs in the order they were retired, over as many calls as needed; each
+    /// is linked into the chain and its certificate rewritten to the new `record_hash`.
+    /// Token IDs already linked are skipped. A call with no token IDs finishes the
+    /// migration once all `legacy_count` records are linked, after which retirements are
+    /// accepted again.
+    ///
+    /// Linking reads each credit's quantity from CarbonAsset, so CarbonAsset has to be
+    /// migrated first.
+    ///
+    /// # Arguments
+    /// * `legacy_count` - Number of retirements version 1 recorded, one per
+    ///   `retirement_event` it published. Fixed by the first call; later calls pass the
+    ///   same number.
+    /// * `token_ids` - Legacy records to link, oldest first
+    ///
+    /// Returns the resulting data version, which stays at the old version until the
+    /// finishing call. A no-op when already current.
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    /// * `ContractError::InvalidTokenId` - No retirement is recorded for a token ID
+    /// * `ContractError::MigrationOutOfOrder` - A token was retired before the chain head
+    /// * `ContractError::LegacyCountMismatch` - `legacy_count` differs from the first
+    ///   call's, or more records were linked than it counts
+    /// * `ContractError::MigrationPending` - Finishing while legacy records are unlinked
+    /// * `ContractError::AssetMigrationPending` - CarbonAsset has not been migrated yet
+    pub fn migrate(
+        env: Env,
+        caller: Address,
+        legacy_count: u64,
+        token_ids: Vec<u32>,
+    ) -> Result<u32, ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        let from_version = Self::get_data_version(env.clone());
+        if from_version >= DATA_VERSION {
+            return Ok(from_version);
+        }
+
+        let count_key = DataKey::LegacyRecordCount;
+        match env.storage().instance().get::<_, u64>(&count_key) {
+            Some(count) if count != legacy_count => {
+                return Err(ContractError::LegacyCountMismatch);
+            }
+            Some(_) => {}
+            None => env.storage().instance().set(&count_key, &legacy_count),
+        }
+
+        // No retirement is appended while the migration runs, so every record in the
+        // chain so far is a linked legacy one
+        if !token_ids.is_empty() {
+            let carbon_asset_contract: Address = env
+                .storage()
+                .instance()
+                .get(&DataKey::CarbonAssetContract)
+                .ok_or(ContractError::ContractNotInitialized)?;
+            if Self::asset_data_version(&env, &carbon_asset_contract) < CARBON_ASSET_DATA_VERSION {
+                return Err(ContractError::AssetMigrationPending);
+            }
+            for token_id in token_ids.iter() {
+                Self::link_legacy_record(&env, &carbon_asset_contract, token_id)?;
+            }
+            if Self::last_sequence(env.clone()) > legacy_count {
+                return Err(ContractError::LegacyCountMismatch);
+            }
+            return Ok(from_version);
+        }
+
+        if Self::last_sequence(env.clone()) < legacy_count {
+            return Err(ContractError::MigrationPending);
+        }
+
+        env.storage().instance().remove(&count_key);
+        env.storage()
+            .instance()
+            .set(&DataKey::DataVersion, &DATA_VERSION);
+
+        MigrateEvent {
+            from_version,
+            to_version: DATA_VERSION,
+        }
+        .publish(&env);
+        Ok(DATA_VERSION)
+    }
+
+    /// Get the stored data version (0 for deployments that predate versioning)
+    pub fn get_data_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::DataVersion)
+            .unwrap_or(0)
+    }
+
     /// Get the current admin address
     pub fn get_admin(env: Env) -> Option<Address> {
         env.storage().instance().get(&DataKey::Admin)
@@ -329,4 +1104,268 @@ impl RetirementTracker {
     pub fn get_carbon_asset_contract(env: Env) -> Option<Address> {
         env.storage().instance().get(&DataKey::CarbonAssetContract)
     }
+
+    // ========================================================================
+    // Internal Helpers
+    // ========================================================================
+
+    /// Shared retirement flow; callers are responsible for `retiring_entity` auth,
+    /// or for the `operator`'s when the credit is burned on the owner's behalf
+    fn retire_internal(
+        env: Env,
+        token_id: u32,
+        retiring_entity: Address,
+        reason: Option<String>,
+        operator: Option<Address>,
+        claim: RecordedClaim,
+        group_id: Option<u64>,
+    ) -> Result<RetirementRecord, RetireError> {
+        // Legacy records must be linked into the chain before anything is appended
+        if Self::get_data_version(env.clone()) < DATA_VERSION {
+            return Err(ContractError::MigrationPending.into());
+        }
+
+        // Check if token is already retired
+        let ledger_key = DataKey::RetirementLedger(token_id);
+        if env.storage().persistent().has(&ledger_key) {
+            return Err(ContractError::TokenAlreadyRetired.into());
+        }
+
+        // Get carbon asset contract address
+        let carbon_asset_contract: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::CarbonAssetContract)
+            .ok_or(ContractError::ContractNotInitialized)?;
+
+        // Call burn_token on CarbonAsset contract
+        // The contract must be pre-authorized as a burner on the CarbonAsset contract
+        // We assume CarbonAsset has a burn_token function that accepts (token_id: u32, from: Address)
+        // The CarbonAsset contract should verify ownership before allowing burn
+        let mut burn_args = Vec::new(&env);
+        let burn_symbol = match operator.clone() {
+            // burn_token_from checks the operator's approval or allowance
+            Some(operator) => {
+                burn_args.push_back(operator.into_val(&env));
+                burn_args.push_back(retiring_entity.clone().into_val(&env));
+                burn_args.push_back(token_id.into_val(&env));
+                Symbol::new(&env, "burn_token_from")
+            }
+            None => {
+                burn_args.push_back(token_id.into_val(&env));
+                burn_args.push_back(retiring_entity.clone().into_val(&env));
+                Symbol::new(&env, "burn_token")
+            }
+        };
+        // A failed burn is reported rather than aborting, so batch callers can carry on
+        let burned = env.try_invoke_contract::<(), CarbonAssetError>(
+            &carbon_asset_contract,
+            &burn_symbol,
+            burn_args,
+        );
+        match burned {
+            Ok(Ok(())) => {}
+            Err(Ok(error)) => return Err(RetireError::Burn(error as u32, error.into())),
+            Err(Err(InvokeError::Contract(code))) => {
+                return Err(RetireError::Burn(code, ContractError::BurnFailed));
+            }
+            _ => return Err(ContractError::BurnFailed.into()),
+        }
+
+        // Quantity is kept on the burned token, so it can still be read here
+        let quantity = Self::quantity_of(&env, &carbon_asset_contract, token_id);
+
+        // Append to the hash chain only once the burn has gone through, so a
+        // failed best-effort batch entry leaves no gap in the sequence
+        let record = Self::append_record(
+            &env,
+            RetirementRecord {
+                token_id,
+                retiring_entity: retiring_entity.clone(),
+                timestamp: env.ledger().timestamp(),
+                sequence: 0,
+                prev_hash: BytesN::from_array(&env, &[0u8; 32]),
+                record_hash: BytesN::from_array(&env, &[0u8; 32]),
+                quantity,
+                operator,
+                claim,
+                group_id,
+                reason,
+            },
+        );
+
+        // Update entity index
+        let entity_key = DataKey::EntityIndex(retiring_entity.clone());
+        let mut entity_retirements: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&entity_key)
+            .unwrap_or(Vec::new(&env));
+        entity_retirements.push_back(token_id);
+        env.storage()
+            .persistent()
+            .set(&entity_key, &entity_retirements);
+
+        // Emit event
+        RetirementEvent {
+            token_id,
+            retiring_entity: retiring_entity.clone(),
+            timestamp: record.timestamp,
+            sequence: record.sequence,
+            record_hash: record.record_hash.clone(),
+        }
+        .publish(&env);
+        Ok(record)
+    }
+
+    /// Fills in `record`'s place in the hash chain, stores it and moves the chain head to it
+    fn append_record(env: &Env, mut record: RetirementRecord) -> RetirementRecord {
+        record.sequence = Self::last_sequence(env.clone()) + 1;
+        record.prev_hash = Self::get_chain_head(env.clone());
+        let preimage = (
+            record.prev_hash.clone(),
+            record.sequence,
+            record.token_id,
+            record.retiring_entity.clone(),
+            record.timestamp,
+            record.quantity,
+            record.operator.clone(),
+            record.claim.clone(),
+            record.group_id,
+            record.reason.clone(),
+        )
+            .to_xdr(env);
+        record.record_hash = env.crypto().sha256(&preimage).into();
+
+        env.storage()
+            .instance()
+            .set(&DataKey::LastSequence, &record.sequence);
+        env.storage()
+            .instance()
+            .set(&DataKey::ChainHead, &record.record_hash);
+        env.storage()
+            .persistent()
+            .set(&DataKey::SequenceToken(record.sequence), &record.token_id);
+        env.storage()
+            .persistent()
+            .set(&DataKey::RetirementLedger(record.token_id), &record);
+        record
+    }
+
+    /// Links a data version 1 record into the hash chain, picking up the operator, claim,
+    /// beneficiary and group v1 kept beside it, and rewrites its certificate to carry the
+    /// new hash
+    fn link_legacy_record(
+        env: &Env,
+        carbon_asset_contract: &Address,
+        token_id: u32,
+    ) -> Result<(), ContractError> {
+        let key = DataKey::RetirementLedger(token_id);
+        let fields: Map<Symbol, Val> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(ContractError::InvalidTokenId)?;
+        if !fields.contains_key(Symbol::new(env, "tx_hash")) {
+            // Already linked by an earlier batch
+            return Ok(());
+        }
+        let legacy: RetirementRecordV1 = env.storage().persistent().get(&key).unwrap();
+
+        // The chain is in retirement order, so legacy records must be given oldest first
+        let last_sequence = Self::last_sequence(env.clone());
+        if last_sequence > 0 {
+            let last = Self::get_record_by_sequence(env.clone(), last_sequence).unwrap();
+            if legacy.timestamp < last.timestamp {
+                return Err(ContractError::MigrationOutOfOrder);
+            }
+        }
+
+        let certificate_id: Option<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::TokenCertificate(token_id));
+        let certificate: Option<RetirementCertificateV1> = certificate_id.map(|certificate_id| {
+            env.storage()
+                .persistent()
+                .get(&DataKey::Certificate(certificate_id))
+                .unwrap()
+        });
+        let delegation: Option<DelegatedRetirement> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Delegation(token_id));
+
+        let record = Self::append_record(
+            env,
+            RetirementRecord {
+                token_id,
+                retiring_entity: legacy.retiring_entity,
+                timestamp: legacy.timestamp,
+                sequence: 0,
+                prev_hash: BytesN::from_array(env, &[0u8; 32]),
+                record_hash: BytesN::from_array(env, &[0u8; 32]),
+                quantity: Self::quantity_of(env, carbon_asset_contract, token_id),
+                operator: delegation.clone().map(|delegation| delegation.operator),
+                claim: match (certificate.clone(), delegation) {
+                    (Some(certificate), _) => RecordedClaim::Claimed(certificate.claim),
+                    (None, Some(delegation)) => RecordedClaim::Beneficiary(delegation.beneficiary),
+                    (None, None) => RecordedClaim::Unclaimed,
+                },
+                group_id: env
+                    .storage()
+                    .persistent()
+                    .get(&DataKey::TokenGroup(token_id)),
+                reason: legacy.reason,
+            },
+        );
+
+        if let Some(certificate) = certificate {
+            env.storage().persistent().set(
+                &DataKey::Certificate(certificate.certificate_id),
+                &RetirementCertificate {
+                    certificate_id: certificate.certificate_id,
+                    token_id,
+                    retiring_entity: certificate.retiring_entity,
+                    claim: certificate.claim,
+                    timestamp: certificate.timestamp,
+                    record_hash: record.record_hash,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    fn asset_data_version(env: &Env, carbon_asset_contract: &Address) -> u32 {
+        env.invoke_contract(
+            carbon_asset_contract,
+            &Symbol::new(env, "get_data_version"),
+            Vec::new(env),
+        )
+    }
+
+    fn quantity_of(env: &Env, carbon_asset_contract: &Address, token_id: u32) -> i128 {
+        let mut args = Vec::new(env);
+        args.push_back(token_id.into_val(env));
+        env.invoke_contract(
+            carbon_asset_contract,
+            &Symbol::new(env, "quantity_of"),
+            args,
+        )
+    }
+
+    fn require_admin(env: &Env, caller: &Address) -> Result<(), ContractError> {
+        caller.require_auth();
+
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(ContractError::ContractNotInitialized)?;
+
+        if *caller != admin {
+            return Err(ContractError::NotAuthorized);
+        }
+        Ok(())
+    }
 }
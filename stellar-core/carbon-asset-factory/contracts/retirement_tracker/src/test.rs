@@ -0,0 +1,784 @@
+#![cfg(test)]
+extern crate std;
+
+use super::*;
+use carbon_asset::errors::ContractError as AssetError;
+use carbon_asset::types::{
+    AssetStatus, CarbonAssetMetadata, CorrespondingAdjustment, CreditType, PauseScope,
+    UNITS_PER_TONNE,
+};
+use carbon_asset::{CarbonAsset, CarbonAssetClient};
+use soroban_sdk::testutils::{Address as _, Events as _, Ledger as _};
+use soroban_sdk::{Map, TryFromVal, Val};
+
+struct Setup {
+    env: Env,
+    admin: Address,
+    owner: Address,
+    assets: CarbonAssetClient<'static>,
+    tracker: RetirementTrackerClient<'static>,
+}
+
+// A RetirementTracker wired to a real CarbonAsset that names it as its
+// retirement tracker.
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+
+    let tracker_id = env.register(RetirementTracker, ());
+    let assets_id = env.register(CarbonAsset, ());
+    let assets = CarbonAssetClient::new(&env, &assets_id);
+    assets.initialize(
+        &admin,
+        &String::from_str(&env, "Carbon Asset"),
+        &String::from_str(&env, "C01"),
+        &tracker_id,
+        &String::from_str(&env, "US"),
+    );
+    let tracker = RetirementTrackerClient::new(&env, &tracker_id);
+    tracker.initialize(&admin, &assets_id);
+
+    Setup {
+        env,
+        admin,
+        owner,
+        assets,
+        tracker,
+    }
+}
+
+fn metadata(env: &Env, country: &str) -> CarbonAssetMetadata {
+    CarbonAssetMetadata {
+        project_id: String::from_str(env, "PROJ-0"),
+        vintage_year: 1704067200,
+        methodology_id: 1,
+        geo_hash: BytesN::from_array(env, &[0u8; 32]),
+        registry: String::from_str(env, "VERRA"),
+        registry_serial: String::from_str(env, "VCS-0000-2024-001"),
+        issuance_date: 1735689600,
+        country: String::from_str(env, country),
+        project_type: String::from_str(env, "REDD+"),
+        credit_type: CreditType::Avoidance,
+        sdg_flags: 0,
+        corresponding_adjustment: CorrespondingAdjustment::NotApplicable,
+        verification_uri: String::from_str(env, "ipfs://bafybeibverificationreport"),
+    }
+}
+
+fn mint(s: &Setup, owner: &Address, tonnes: i128) -> u32 {
+    mint_in(s, owner, tonnes, "BR")
+}
+
+fn mint_in(s: &Setup, owner: &Address, tonnes: i128, country: &str) -> u32 {
+    s.assets.mint(
+        &s.admin,
+        owner,
+        &metadata(&s.env, country),
+        &(tonnes * UNITS_PER_TONNE),
+    )
+}
+
+fn filter_country(env: &Env, country: &str) -> TokenFilter {
+    TokenFilter {
+        min_vintage: None,
+        max_vintage: None,
+        project_id: None,
+        methodology_id: None,
+        min_quality_score: None,
+        country: Some(String::from_str(env, country)),
+    }
+}
+
+fn claim(env: &Env) -> RetirementClaim {
+    RetirementClaim {
+        beneficiary: String::from_str(env, "Acme Corp"),
+        purpose: String::from_str(env, "FY2024 scope 3 offset"),
+        claim_standard: ClaimStandard::GhgProtocolScope3,
+        period_start: 1704067200,
+        period_end: 1735689599,
+        country_of_use: String::from_str(env, "US"),
+    }
+}
+
+#[test]
+fn test_retire_with_claim_numbers_certificates() {
+    let s = setup();
+    let first = mint(&s, &s.owner, 1);
+    let second = mint(&s, &s.owner, 2);
+    assert_eq!(s.tracker.certificate_count(), 0);
+
+    let certificate = s
+        .tracker
+        .retire_with_claim(&first, &s.owner, &claim(&s.env));
+    assert_eq!(certificate.certificate_id, 1);
+    assert_eq!(certificate.token_id, first);
+    assert_eq!(certificate.retiring_entity, s.owner);
+    assert_eq!(certificate.claim, claim(&s.env));
+    assert!(s.assets.is_burned(&first));
+
+    let record = s.tracker.get_retirement_record(&first).unwrap();
+    assert_eq!(certificate.record_hash, record.record_hash);
+    assert_eq!(certificate.timestamp, record.timestamp);
+    assert_eq!(record.reason, Some(claim(&s.env).purpose));
+
+    let mut scope1 = claim(&s.env);
+    scope1.claim_standard = ClaimStandard::GhgProtocolScope1;
+    let certificate = s.tracker.retire_with_claim(&second, &s.owner, &scope1);
+    assert_eq!(certificate.certificate_id, 2);
+    assert_eq!(s.tracker.certificate_count(), 2);
+
+    // Certificates are found by number or by the token they cover.
+    assert_eq!(s.tracker.get_certificate(&2), Some(certificate.clone()));
+    assert_eq!(
+        s.tracker.get_certificate_for_token(&second),
+        Some(certificate)
+    );
+    assert_eq!(
+        s.tracker
+            .get_certificate_for_token(&first)
+            .unwrap()
+            .certificate_id,
+        1
+    );
+    assert_eq!(s.tracker.get_certificate(&3), None);
+
+    assert_eq!(
+        s.tracker
+            .try_retire_with_claim(&first, &s.owner, &claim(&s.env)),
+        Err(Ok(ContractError::TokenAlreadyRetired))
+    );
+    assert_eq!(s.tracker.certificate_count(), 2);
+}
+
+#[test]
+fn test_retire_with_claim_rejects_invalid_claims() {
+    let s = setup();
+    let token_id = mint(&s, &s.owner, 1);
+
+    let mut no_beneficiary = claim(&s.env);
+    no_beneficiary.beneficiary = String::from_str(&s.env, "");
+    let mut bad_country = claim(&s.env);
+    bad_country.country_of_use = String::from_str(&s.env, "USA");
+    let mut inverted = claim(&s.env);
+    inverted.period_end = inverted.period_start - 1;
+
+    for invalid in [no_beneficiary, bad_country, inverted] {
+        assert_eq!(
+            s.tracker
+                .try_retire_with_claim(&token_id, &s.owner, &invalid),
+            Err(Ok(ContractError::InvalidClaim))
+        );
+    }
+    assert!(!s.assets.is_burned(&token_id));
+    assert!(!s.tracker.is_retired(&token_id));
+    assert_eq!(s.tracker.certificate_count(), 0);
+
+    // A plain retirement records the credit without a certificate.
+    s.tracker.retire(&token_id, &s.owner, &None);
+    assert!(s.tracker.is_retired(&token_id));
+    assert_eq!(s.tracker.get_certificate_for_token(&token_id), None);
+    assert_eq!(
+        s.tracker.get_retirements_by_entity(&s.owner),
+        Vec::from_array(&s.env, [token_id])
+    );
+}
+
+// The (requested_count, retired_count, total_quantity) of the BatchRetirementEvent
+// published by the last call, if any
+fn batch_event(s: &Setup) -> Option<(u32, u32, i128)> {
+    let topic = Symbol::new(&s.env, "batch_retirement_event");
+    s.env
+        .events()
+        .all()
+        .iter()
+        .find_map(|(contract, topics, data)| {
+            let name = Symbol::try_from_val(&s.env, &topics.get(0)?).ok()?;
+            if contract != s.tracker.address || name != topic {
+                return None;
+            }
+            let fields: Map<Symbol, Val> = Map::try_from_val(&s.env, &data).unwrap();
+            let field = |name: &str| fields.get(Symbol::new(&s.env, name)).unwrap();
+            Some((
+                u32::try_from_val(&s.env, &field("requested_count")).unwrap(),
+                u32::try_from_val(&s.env, &field("retired_count")).unwrap(),
+                i128::try_from_val(&s.env, &field("total_quantity")).unwrap(),
+            ))
+        })
+}
+
+// A listed token is reserved for its buyer, so CarbonAsset refuses to burn it
+fn list(s: &Setup, token_id: u32) {
+    let currency = Address::generate(&s.env);
+    s.assets
+        .list(&token_id, &(100 * UNITS_PER_TONNE), &currency);
+}
+
+#[test]
+fn test_retire_reports_burn_errors_as_its_own() {
+    let s = setup();
+    let token_id = mint(&s, &s.owner, 1);
+    list(&s, token_id);
+
+    assert_eq!(
+        s.tracker.try_retire(&token_id, &s.owner, &None),
+        Err(Ok(ContractError::TokenListed))
+    );
+    assert_eq!(
+        s.tracker.try_retire(&999, &s.owner, &None),
+        Err(Ok(ContractError::InvalidTokenId))
+    );
+    assert!(!s.tracker.is_retired(&token_id));
+    assert_eq!(s.tracker.last_sequence(), 0);
+}
+
+#[test]
+fn test_retire_unowned_token_fails() {
+    let s = setup();
+    let token_id = mint(&s, &s.owner, 1);
+    let outsider = Address::generate(&s.env);
+
+    // CarbonAsset's NotOwner shares its code with BurnFailed, but is reported as TokenNotOwned
+    assert_eq!(
+        s.tracker.try_retire(&token_id, &outsider, &None),
+        Err(Ok(ContractError::TokenNotOwned))
+    );
+    assert!(!s.assets.is_burned(&token_id));
+    assert!(!s.tracker.is_retired(&token_id));
+}
+
+#[test]
+fn test_retire_frozen_token_fails() {
+    let s = setup();
+    let invalidated = mint(&s, &s.owner, 1);
+    let frozen = mint(&s, &s.owner, 1);
+    s.assets
+        .set_status(&s.admin, &invalidated, &AssetStatus::Invalidated);
+
+    // CarbonAsset's StatusFrozen shares its code with NoPendingAdmin
+    assert_eq!(
+        s.tracker.try_retire(&invalidated, &s.owner, &None),
+        Err(Ok(ContractError::TokenFrozen))
+    );
+
+    s.assets
+        .freeze_project(&s.admin, &String::from_str(&s.env, "PROJ-0"));
+    assert_eq!(
+        s.tracker.try_retire(&frozen, &s.owner, &None),
+        Err(Ok(ContractError::IssuanceFrozen))
+    );
+    assert_eq!(s.tracker.last_sequence(), 0);
+}
+
+#[test]
+fn test_strict_batch_rolls_back_on_first_failure() {
+    let s = setup();
+    let first = mint(&s, &s.owner, 1);
+    let listed = mint(&s, &s.owner, 2);
+    list(&s, listed);
+
+    let batch = Vec::from_array(&s.env, [first, listed]);
+    assert_eq!(
+        s.tracker
+            .try_batch_retire(&batch, &s.owner, &None, &BatchMode::Strict),
+        Err(Ok(ContractError::TokenListed))
+    );
+    assert_eq!(batch_event(&s), None);
+
+    // The first token's retirement is undone with the rest of the call
+    assert!(!s.assets.is_burned(&first));
+    assert!(!s.tracker.is_retired(&first));
+    assert_eq!(s.tracker.last_sequence(), 0);
+    assert_eq!(s.assets.total_retired(), 0);
+
+    let retired = mint(&s, &s.owner, 3);
+    s.tracker.retire(&retired, &s.owner, &None);
+    let batch = Vec::from_array(&s.env, [first, retired]);
+    assert_eq!(
+        s.tracker
+            .try_batch_retire(&batch, &s.owner, &None, &BatchMode::Strict),
+        Err(Ok(ContractError::TokenAlreadyRetired))
+    );
+    assert!(!s.tracker.is_retired(&first));
+}
+
+#[test]
+fn test_best_effort_batch_reports_each_failure() {
+    let s = setup();
+    let first = mint(&s, &s.owner, 1);
+    let listed = mint(&s, &s.owner, 2);
+    let retired = mint(&s, &s.owner, 3);
+    let last = mint(&s, &s.owner, 4);
+    list(&s, listed);
+    s.tracker.retire(&retired, &s.owner, &None);
+
+    let batch = Vec::from_array(&s.env, [first, listed, retired, last]);
+    let outcomes = s
+        .tracker
+        .batch_retire(&batch, &s.owner, &None, &BatchMode::BestEffort);
+    // Only the retired tokens count towards the event's total
+    assert_eq!(batch_event(&s), Some((4, 2, 5 * UNITS_PER_TONNE)));
+
+    assert_eq!(outcomes.len(), 4);
+    assert_eq!(
+        outcomes.get(0),
+        Some(RetirementOutcome::Retired(
+            s.tracker.get_retirement_record(&first).unwrap()
+        ))
+    );
+    assert_eq!(
+        outcomes.get(1),
+        Some(RetirementOutcome::BurnRejected(
+            listed,
+            AssetError::TokenListed as u32
+        ))
+    );
+    assert_eq!(
+        outcomes.get(2),
+        Some(RetirementOutcome::Failed(
+            retired,
+            ContractError::TokenAlreadyRetired as u32
+        ))
+    );
+    assert_eq!(
+        outcomes.get(3),
+        Some(RetirementOutcome::Retired(
+            s.tracker.get_retirement_record(&last).unwrap()
+        ))
+    );
+
+    assert!(!s.assets.is_burned(&listed));
+    assert_eq!(s.tracker.last_sequence(), 3);
+    assert_eq!(s.assets.total_retired(), 8 * UNITS_PER_TONNE);
+}
+
+#[test]
+fn test_retire_amount_records_a_group() {
+    let s = setup();
+    let kenya = mint_in(&s, &s.owner, 5, "KE");
+    let first = mint(&s, &s.owner, 1);
+    let second = mint(&s, &s.owner, 1);
+    let filter = filter_country(&s.env, "BR");
+    let reason = Some(String::from_str(&s.env, "Q1 travel"));
+
+    let group = s.tracker.retire_amount(&s.owner, &1500, &filter, &reason);
+
+    // A piece is split off the second token so exactly 1.5 tonnes are retired
+    assert_eq!(group.group_id, 1);
+    assert_eq!(group.retiring_entity, s.owner);
+    assert_eq!(group.total_quantity, 1500);
+    assert_eq!(group.filter, filter);
+    assert_eq!(group.reason, reason);
+    assert_eq!(group.token_ids.len(), 2);
+    assert_eq!(group.token_ids.get(0), Some(first));
+    let piece = group.token_ids.get(1).unwrap();
+    assert_eq!(s.assets.quantity_of(&piece), 500);
+    assert_eq!(s.assets.quantity_of(&second), 500);
+    assert!(!s.assets.is_burned(&second));
+    assert_eq!(s.assets.total_retired(), 1500);
+
+    for token_id in group.token_ids.iter() {
+        assert!(s.assets.is_burned(&token_id));
+        let record = s.tracker.get_retirement_record(&token_id).unwrap();
+        assert_eq!(record.reason, reason);
+        assert_eq!(
+            s.tracker.get_group_for_token(&token_id),
+            Some(group.clone())
+        );
+    }
+    assert_eq!(s.tracker.get_grouped_retirement(&1), Some(group));
+
+    // Credits outside the filter are left alone
+    assert!(!s.assets.is_burned(&kenya));
+    assert!(!s.tracker.is_retired(&kenya));
+    assert_eq!(s.tracker.get_group_for_token(&kenya), None);
+    assert_eq!(s.tracker.get_group_for_token(&second), None);
+
+    let group = s.tracker.retire_amount(&s.owner, &500, &filter, &None);
+    assert_eq!(group.group_id, 2);
+    assert_eq!(s.tracker.get_grouped_retirement(&3), None);
+}
+
+#[test]
+fn test_retire_amount_reports_why_nothing_was_retired() {
+    let s = setup();
+    let kenya = mint_in(&s, &s.owner, 5, "KE");
+    mint(&s, &s.owner, 1);
+    let filter = filter_country(&s.env, "BR");
+
+    assert_eq!(
+        s.tracker.try_retire_amount(&s.owner, &0, &filter, &None),
+        Err(Ok(ContractError::InvalidAmount))
+    );
+    // Only the Brazilian tonne matches, although the owner holds six
+    assert_eq!(
+        s.tracker.try_retire_amount(&s.owner, &2000, &filter, &None),
+        Err(Ok(ContractError::InsufficientCredits))
+    );
+
+    s.assets.pause(&s.admin, &PauseScope::Retire);
+    assert_eq!(
+        s.tracker.try_retire_amount(&s.owner, &500, &filter, &None),
+        Err(Ok(ContractError::AssetPaused))
+    );
+
+    assert!(!s.assets.is_burned(&kenya));
+    assert_eq!(s.tracker.last_sequence(), 0);
+    assert_eq!(s.tracker.get_grouped_retirement(&1), None);
+}
+
+#[test]
+fn test_retire_amount_finds_credits_past_the_first_page() {
+    let s = setup();
+    for _ in 0..100 {
+        mint_in(&s, &s.owner, 1, "KE");
+    }
+    let brazil = mint(&s, &s.owner, 2);
+
+    // The only matching credit sits beyond CarbonAsset's first page of the wallet
+    let group = s
+        .tracker
+        .retire_amount(&s.owner, &2000, &filter_country(&s.env, "BR"), &None);
+    assert_eq!(group.token_ids, Vec::from_array(&s.env, [brazil]));
+    assert!(s.tracker.is_retired(&brazil));
+}
+
+#[test]
+fn test_retire_from_records_operator_and_spends_allowance() {
+    let s = setup();
+    let broker = Address::generate(&s.env);
+    let beneficiary = String::from_str(&s.env, "Acme Corp");
+    let first = mint(&s, &s.owner, 2);
+    let second = mint(&s, &s.owner, 2);
+    let approved = mint(&s, &s.owner, 2);
+    s.assets
+        .approve(&s.owner, &broker, &(3 * UNITS_PER_TONNE), &1000);
+
+    let record = s
+        .tracker
+        .retire_from(&broker, &s.owner, &first, &beneficiary);
+    assert_eq!(s.env.auths()[0].0, broker);
+
+    // The credit is retired as the owner's, with the broker kept alongside
+    assert_eq!(record.retiring_entity, s.owner);
+    assert_eq!(s.tracker.get_retirement_record(&first), Some(record));
+    assert_eq!(
+        s.tracker.get_delegated_retirement(&first),
+        Some(DelegatedRetirement {
+            token_id: first,
+            operator: broker.clone(),
+            beneficial_owner: s.owner.clone(),
+            beneficiary: beneficiary.clone(),
+        })
+    );
+    assert!(s.assets.is_burned(&first));
+    assert_eq!(s.assets.allowance(&s.owner, &broker), UNITS_PER_TONNE);
+
+    // What is left of the allowance does not cover another two tonnes
+    assert_eq!(
+        s.tracker
+            .try_retire_from(&broker, &s.owner, &second, &beneficiary),
+        Err(Ok(ContractError::NotApproved))
+    );
+    assert!(!s.tracker.is_retired(&second));
+    assert_eq!(s.tracker.get_delegated_retirement(&second), None);
+
+    // A token approval is used instead of the allowance
+    s.assets.approve_token(&approved, &broker);
+    s.tracker
+        .retire_from(&broker, &s.owner, &approved, &beneficiary);
+    assert_eq!(
+        s.tracker
+            .get_delegated_retirement(&approved)
+            .unwrap()
+            .operator,
+        broker
+    );
+    assert_eq!(s.assets.allowance(&s.owner, &broker), UNITS_PER_TONNE);
+    assert_eq!(s.tracker.get_retirements_by_entity(&s.owner).len(), 2);
+
+    s.tracker.retire(&second, &s.owner, &None);
+    assert_eq!(s.tracker.get_delegated_retirement(&second), None);
+}
+
+// Walks the log from sequence 1, recomputing every record_hash from the record's own
+// fields, and returns the records in order
+fn verify_chain(s: &Setup) -> std::vec::Vec<RetirementRecord> {
+    let mut records = std::vec::Vec::new();
+    let mut prev_hash = BytesN::from_array(&s.env, &[0u8; 32]);
+    for sequence in 1..=s.tracker.last_sequence() {
+        let record = s.tracker.get_record_by_sequence(&sequence).unwrap();
+        let preimage = (
+            prev_hash.clone(),
+            sequence,
+            record.token_id,
+            record.retiring_entity.clone(),
+            record.timestamp,
+            record.quantity,
+            record.operator.clone(),
+            record.claim.clone(),
+            record.group_id,
+            record.reason.clone(),
+        )
+            .to_xdr(&s.env);
+        let record_hash: BytesN<32> = s.env.crypto().sha256(&preimage).into();
+
+        assert_eq!(record.sequence, sequence);
+        assert_eq!(record.prev_hash, prev_hash);
+        assert_eq!(record.record_hash, record_hash);
+        prev_hash = record_hash;
+        records.push(record);
+    }
+    assert_eq!(s.tracker.get_chain_head(), prev_hash);
+    assert_eq!(
+        s.tracker
+            .get_record_by_sequence(&(records.len() as u64 + 1)),
+        None
+    );
+    records
+}
+
+#[test]
+fn test_record_hashes_chain_every_retirement() {
+    let s = setup();
+    let broker = Address::generate(&s.env);
+    let plain = mint(&s, &s.owner, 1);
+    let delegated = mint(&s, &s.owner, 2);
+    let claimed = mint(&s, &s.owner, 3);
+    let batched = mint(&s, &s.owner, 4);
+    let grouped = mint_in(&s, &s.owner, 5, "KE");
+    s.assets.approve_token(&delegated, &broker);
+    assert_eq!(verify_chain(&s).len(), 0);
+
+    s.env.ledger().set_timestamp(1_000);
+    s.tracker.retire(&plain, &s.owner, &None);
+    s.tracker.retire_from(
+        &broker,
+        &s.owner,
+        &delegated,
+        &String::from_str(&s.env, "Acme Corp"),
+    );
+    s.env.ledger().set_timestamp(2_000);
+    s.tracker
+        .retire_with_claim(&claimed, &s.owner, &claim(&s.env));
+    s.tracker.batch_retire(
+        &Vec::from_array(&s.env, [batched]),
+        &s.owner,
+        &Some(String::from_str(&s.env, "Q4")),
+        &BatchMode::Strict,
+    );
+    s.tracker.retire_amount(
+        &s.owner,
+        &(5 * UNITS_PER_TONNE),
+        &filter_country(&s.env, "KE"),
+        &None,
+    );
+
+    let records = verify_chain(&s);
+    assert_eq!(records.len(), 5);
+    let tokens: std::vec::Vec<u32> = records.iter().map(|record| record.token_id).collect();
+    assert_eq!(tokens, [plain, delegated, claimed, batched, grouped]);
+
+    // Quantity, operator, claim, beneficiary and group are part of what each hash
+    // commits to
+    assert_eq!(records[0].quantity, UNITS_PER_TONNE);
+    assert_eq!(records[0].operator, None);
+    assert_eq!(records[0].group_id, None);
+    assert_eq!(records[1].quantity, 2 * UNITS_PER_TONNE);
+    assert_eq!(records[1].operator, Some(broker));
+    assert_eq!(
+        records[1].claim,
+        RecordedClaim::Beneficiary(String::from_str(&s.env, "Acme Corp"))
+    );
+    assert_eq!(records[2].claim, RecordedClaim::Claimed(claim(&s.env)));
+    assert_eq!(records[2].timestamp, 2_000);
+    assert_eq!(records[3].quantity, 4 * UNITS_PER_TONNE);
+    assert_eq!(records[3].claim, RecordedClaim::Unclaimed);
+    assert_eq!(records[3].reason, Some(String::from_str(&s.env, "Q4")));
+    assert_eq!(records[4].group_id, Some(1));
+    assert_eq!(
+        s.tracker
+            .get_certificate_for_token(&claimed)
+            .unwrap()
+            .record_hash,
+        records[2].record_hash
+    );
+}
+
+#[test]
+fn test_migrate_links_legacy_records_into_the_chain() {
+    let s = setup();
+    let broker = Address::generate(&s.env);
+    let delegated = mint(&s, &s.owner, 1);
+    let claimed = mint(&s, &s.owner, 2);
+    let later = mint(&s, &s.owner, 3);
+    let grouped = mint(&s, &s.owner, 4);
+    s.assets.burn_token(&delegated, &s.owner);
+    s.assets.burn_token(&claimed, &s.owner);
+    s.assets.burn_token(&grouped, &s.owner);
+
+    // What a data version 1 build left behind for a retire_from and a retire_with_claim
+    let tracker_id = s.tracker.address.clone();
+    s.env.as_contract(&tracker_id, || {
+        let storage = s.env.storage();
+        storage.instance().set(&DataKey::DataVersion, &1u32);
+        storage.persistent().set(
+            &DataKey::RetirementLedger(delegated),
+            &RetirementRecordV1 {
+                token_id: delegated,
+                retiring_entity: s.owner.clone(),
+                timestamp: 1_000,
+                tx_hash: BytesN::from_array(&s.env, &[1u8; 32]),
+                reason: None,
+            },
+        );
+        storage.persistent().set(
+            &DataKey::Delegation(delegated),
+            &DelegatedRetirement {
+                token_id: delegated,
+                operator: broker.clone(),
+                beneficial_owner: s.owner.clone(),
+                beneficiary: String::from_str(&s.env, "Acme Corp"),
+            },
+        );
+        storage.persistent().set(
+            &DataKey::RetirementLedger(claimed),
+            &RetirementRecordV1 {
+                token_id: claimed,
+                retiring_entity: s.owner.clone(),
+                timestamp: 2_000,
+                tx_hash: BytesN::from_array(&s.env, &[2u8; 32]),
+                reason: Some(claim(&s.env).purpose),
+            },
+        );
+        storage.persistent().set(
+            &DataKey::Certificate(1),
+            &RetirementCertificateV1 {
+                certificate_id: 1,
+                token_id: claimed,
+                retiring_entity: s.owner.clone(),
+                claim: claim(&s.env),
+                timestamp: 2_000,
+                tx_hash: BytesN::from_array(&s.env, &[2u8; 32]),
+            },
+        );
+        storage
+            .persistent()
+            .set(&DataKey::TokenCertificate(claimed), &1u64);
+        storage.instance().set(&DataKey::NextCertificateId, &2u64);
+        storage.persistent().set(
+            &DataKey::RetirementLedger(grouped),
+            &RetirementRecordV1 {
+                token_id: grouped,
+                retiring_entity: s.owner.clone(),
+                timestamp: 2_500,
+                tx_hash: BytesN::from_array(&s.env, &[3u8; 32]),
+                reason: None,
+            },
+        );
+        storage
+            .persistent()
+            .set(&DataKey::TokenGroup(grouped), &1u64);
+        storage.persistent().set(
+            &DataKey::EntityIndex(s.owner.clone()),
+            &Vec::from_array(&s.env, [delegated, claimed, grouped]),
+        );
+    });
+
+    // Nothing may be appended until the legacy records are linked
+    assert_eq!(
+        s.tracker.try_retire(&later, &s.owner, &None),
+        Err(Ok(ContractError::MigrationPending))
+    );
+    // Linking reads quantities CarbonAsset only holds once it is migrated itself. Its
+    // DataVersion key encodes the same as this contract's.
+    let assets_id = s.assets.address.clone();
+    s.env.as_contract(&assets_id, || {
+        s.env.storage().instance().set(&DataKey::DataVersion, &2u32);
+    });
+    let both = Vec::from_array(&s.env, [delegated, claimed]);
+    assert_eq!(
+        s.tracker.try_migrate(&s.admin, &3, &both),
+        Err(Ok(ContractError::AssetMigrationPending))
+    );
+    s.assets.migrate(&s.admin);
+
+    assert_eq!(
+        s.tracker
+            .try_migrate(&s.admin, &3, &Vec::from_array(&s.env, [claimed, delegated])),
+        Err(Ok(ContractError::MigrationOutOfOrder))
+    );
+    assert_eq!(
+        s.tracker
+            .try_migrate(&s.admin, &3, &Vec::from_array(&s.env, [later])),
+        Err(Ok(ContractError::InvalidTokenId))
+    );
+    // Finishing before every legacy record is linked would leave some undecodable
+    assert_eq!(
+        s.tracker.try_migrate(&s.admin, &3, &Vec::new(&s.env)),
+        Err(Ok(ContractError::MigrationPending))
+    );
+    assert_eq!(s.tracker.last_sequence(), 0);
+
+    // Batches may overlap; tokens already linked are skipped
+    assert_eq!(
+        s.tracker
+            .migrate(&s.admin, &3, &Vec::from_array(&s.env, [delegated])),
+        1
+    );
+    // The count is fixed by the first call
+    assert_eq!(
+        s.tracker.try_migrate(&s.admin, &2, &both),
+        Err(Ok(ContractError::LegacyCountMismatch))
+    );
+    assert_eq!(s.tracker.migrate(&s.admin, &3, &both), 1);
+    assert_eq!(
+        s.tracker.try_migrate(&s.admin, &3, &Vec::new(&s.env)),
+        Err(Ok(ContractError::MigrationPending))
+    );
+    assert_eq!(
+        s.tracker
+            .migrate(&s.admin, &3, &Vec::from_array(&s.env, [grouped])),
+        1
+    );
+    assert_eq!(s.tracker.get_data_version(), 1);
+    assert_eq!(s.tracker.migrate(&s.admin, &3, &Vec::new(&s.env)), 2);
+    assert_eq!(s.tracker.get_data_version(), 2);
+
+    let records = verify_chain(&s);
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].token_id, delegated);
+    assert_eq!(records[0].timestamp, 1_000);
+    assert_eq!(records[0].quantity, UNITS_PER_TONNE);
+    assert_eq!(records[0].operator, Some(broker));
+    assert_eq!(
+        records[0].claim,
+        RecordedClaim::Beneficiary(String::from_str(&s.env, "Acme Corp"))
+    );
+    assert_eq!(records[1].token_id, claimed);
+    assert_eq!(records[1].quantity, 2 * UNITS_PER_TONNE);
+    assert_eq!(records[1].claim, RecordedClaim::Claimed(claim(&s.env)));
+    assert_eq!(records[1].reason, Some(claim(&s.env).purpose));
+    assert_eq!(records[2].token_id, grouped);
+    assert_eq!(records[2].group_id, Some(1));
+
+    let certificate = s.tracker.get_certificate(&1).unwrap();
+    assert_eq!(certificate.record_hash, records[1].record_hash);
+    assert_eq!(certificate.timestamp, 2_000);
+    assert_eq!(
+        s.tracker.get_certificate_for_token(&claimed),
+        Some(certificate)
+    );
+
+    // New retirements carry on from the linked records
+    s.env.ledger().set_timestamp(3_000);
+    let record = s.tracker.retire(&later, &s.owner, &None);
+    assert_eq!(record.sequence, 4);
+    assert_eq!(record.prev_hash, records[2].record_hash);
+    assert_eq!(verify_chain(&s).len(), 4);
+    assert_eq!(
+        s.tracker.get_retirements_by_entity(&s.owner),
+        Vec::from_array(&s.env, [delegated, claimed, grouped, later])
+    );
+}
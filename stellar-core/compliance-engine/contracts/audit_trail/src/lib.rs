@@ -1,7 +1,13 @@
 #![no_std]
 mod test;
 
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Bytes, BytesN, Env, Map, String, Vec};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env, Map, String,
+    Vec,
+};
+
+/// Storage layout version written by this build. Bump together with a step in `migrate`.
+const DATA_VERSION: u32 = 1;
 
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
@@ -16,6 +22,14 @@ pub struct AuditEvent {
     pub tx_hash: BytesN<32>,
 }
 
+/// Admin handover waiting for the proposed address to accept it.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingAdmin {
+    pub new_admin: Address,
+    pub expiration_ledger: u32,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
@@ -25,6 +39,8 @@ pub enum DataKey {
     EntityIndex(String), // primary_entity_id -> Vec<BytesN<32>>
     TypeTimeIndex((String, u64)), // (event_type, day_timestamp) -> Vec<BytesN<32>>
     ContractIndex(Address), // emitting_contract -> Vec<BytesN<32>>
+    DataVersion, // u32
+    PendingAdmin, // PendingAdmin
 }
 
 #[contract]
@@ -40,6 +56,96 @@ impl AuditTrailContract {
         
         let empty_emitters: Map<Address, bool> = Map::new(&env);
         env.storage().instance().set(&DataKey::AuthorizedEmitters, &empty_emitters);
+        env.storage().instance().set(&DataKey::DataVersion, &DATA_VERSION);
+    }
+
+    /// First step of an admin handover. `new_admin` must call `accept_admin`
+    /// on or before `expiration_ledger`.
+    pub fn propose_admin(env: Env, new_admin: Address, expiration_ledger: u32) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if expiration_ledger < env.ledger().sequence() {
+            panic!("Admin proposal expired");
+        }
+
+        let pending = PendingAdmin {
+            new_admin: new_admin.clone(),
+            expiration_ledger,
+        };
+        env.storage().instance().set(&DataKey::PendingAdmin, &pending);
+
+        env.events().publish(
+            (symbol_short!("adm_prop"),),
+            (admin, new_admin, expiration_ledger),
+        );
+    }
+
+    pub fn accept_admin(env: Env) {
+        let pending: PendingAdmin = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .expect("No pending admin");
+        pending.new_admin.require_auth();
+
+        if env.ledger().sequence() > pending.expiration_ledger {
+            panic!("Admin proposal expired");
+        }
+
+        let previous_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        env.storage()
+            .instance()
+            .set(&DataKey::Admin, &pending.new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        env.events().publish(
+            (symbol_short!("adm_xfer"),),
+            (previous_admin, pending.new_admin),
+        );
+    }
+
+    pub fn get_pending_admin(env: Env) -> Option<PendingAdmin> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events().publish(
+            (symbol_short!("upgraded"),),
+            (new_wasm_hash, admin),
+        );
+    }
+
+    /// Brings stored data up to `DATA_VERSION` after an upgrade and returns the
+    /// resulting version. A no-op when already current.
+    pub fn migrate(env: Env) -> u32 {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let from_version = Self::get_data_version(env.clone());
+        if from_version < DATA_VERSION {
+            env.storage()
+                .instance()
+                .set(&DataKey::DataVersion, &DATA_VERSION);
+
+            env.events().publish(
+                (symbol_short!("migrated"),),
+                (from_version, DATA_VERSION),
+            );
+        }
+        Self::get_data_version(env)
+    }
+
+    pub fn get_data_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::DataVersion)
+            .unwrap_or(0)
     }
 
     pub fn authorize_emitter(env: Env, emitter: Address) {
@@ -1,7 +1,10 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, String};
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    vec, Address, Bytes, BytesN, Env, IntoVal, String,
+};
 
 #[test]
 fn test_initialize_and_auth() {
@@ -104,3 +107,112 @@ fn test_unauthorized_emitter() {
         &tx_hash,
     );
 }
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, AuditTrailContract);
+    let client = AuditTrailContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    client.initialize(&admin);
+    env.mock_all_auths();
+
+    client.propose_admin(&new_admin, &(env.ledger().sequence() + 10));
+    assert_eq!(client.get_pending_admin().unwrap().new_admin, new_admin);
+
+    client.accept_admin();
+    assert!(client.get_pending_admin().is_none());
+
+    // Only the new admin can manage emitters from now on.
+    let emitter = Address::generate(&env);
+    client.authorize_emitter(&emitter);
+    assert_eq!(env.auths()[0].0, new_admin);
+}
+
+#[test]
+#[should_panic(expected = "Admin proposal expired")]
+fn test_expired_admin_proposal() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, AuditTrailContract);
+    let client = AuditTrailContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    env.mock_all_auths();
+
+    let expiration = env.ledger().sequence() + 10;
+    client.propose_admin(&Address::generate(&env), &expiration);
+    env.ledger().with_mut(|li| li.sequence_number = expiration + 1);
+    client.accept_admin();
+}
+
+#[test]
+fn test_migrate_publishes_event() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, AuditTrailContract);
+    let client = AuditTrailContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    env.mock_all_auths();
+
+    // Simulate a deployment from before data versioning.
+    env.as_contract(&contract_id, || {
+        env.storage().instance().remove(&DataKey::DataVersion);
+    });
+    assert_eq!(client.migrate(), 1);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("migrated"),).into_val(&env),
+                (0u32, 1u32).into_val(&env),
+            ),
+        ]
+    );
+
+    // Already current: nothing more to report.
+    assert_eq!(client.migrate(), 1);
+    assert_eq!(env.events().all().len(), 1);
+}
+
+#[test]
+fn test_upgrade_publishes_event() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, AuditTrailContract);
+    let client = AuditTrailContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    env.mock_all_auths();
+
+    // Smallest module the host accepts as contract code: the wasm header and a
+    // `contractenvmetav0` section declaring protocol 21.
+    let mut wasm = [0u8; 40];
+    wasm[..10].copy_from_slice(&[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x00, 0x1e]);
+    wasm[10] = 17;
+    wasm[11..28].copy_from_slice(b"contractenvmetav0");
+    wasm[35] = 21;
+    let wasm_hash = env
+        .deployer()
+        .upload_contract_wasm(Bytes::from_slice(&env, &wasm));
+
+    client.upgrade(&wasm_hash);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("upgraded"),).into_val(&env),
+                (wasm_hash, admin).into_val(&env),
+            ),
+        ]
+    );
+}
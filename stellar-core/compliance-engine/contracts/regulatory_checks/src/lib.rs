@@ -1,8 +1,14 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, Address, BytesN, Env, String, Vec,
+    contract, contracterror, contractevent, contractimpl, contracttype, Address, BytesN, Env, Map,
+    String, Symbol, Val, Vec,
 };
 
+mod test;
+
+/// Storage layout version written by this build. Bump together with a step in `migrate`.
+const DATA_VERSION: u32 = 1;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub enum OperationType {
@@ -10,6 +16,39 @@ pub enum OperationType {
     RETIREMENT,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum CorrespondingAdjustment {
+    NotApplicable,
+    Pending,
+    Applied,
+}
+
+/// Credit-level attributes forwarded by the CarbonAsset contract with each
+/// validation request.
+#[derive(Clone)]
+#[contracttype]
+pub struct CreditContext {
+    pub token_id: u32,
+    pub project_id: String,
+    pub vintage_year: u64,
+    pub methodology_id: u32,
+    pub country: String,
+    pub corresponding_adjustment: CorrespondingAdjustment,
+}
+
+/// Predicates a rule places on the credit being moved. Empty lists and
+/// `None` bounds match anything.
+#[derive(Clone)]
+#[contracttype]
+pub struct CreditCriteria {
+    pub min_vintage: Option<u64>,
+    pub max_vintage: Option<u64>,
+    pub methodology_ids: Vec<u32>,
+    pub countries: Vec<String>,
+    pub corresponding_adjustments: Vec<CorrespondingAdjustment>,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub struct JurisdictionRule {
@@ -21,6 +60,21 @@ pub struct JurisdictionRule {
     pub operation: OperationType,
     pub is_allowed: bool,
     pub required_authority: Option<Address>,
+    pub credit_criteria: CreditCriteria,
+}
+
+/// Rule layout stored before credit criteria were introduced (data version 0).
+#[derive(Clone)]
+#[contracttype]
+pub struct JurisdictionRuleV0 {
+    pub rule_id: String,
+    pub description: String,
+    pub source_jur: String,
+    pub dest_jur: String,
+    pub host_jur: String,
+    pub operation: OperationType,
+    pub is_allowed: bool,
+    pub required_authority: Option<Address>,
 }
 
 #[derive(Clone)]
@@ -44,6 +98,14 @@ pub struct PendingApproval {
     pub approved: bool,
 }
 
+/// Admin handover waiting for the proposed address to accept it
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PendingAdmin {
+    pub new_admin: Address,
+    pub expiration_ledger: u32,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
@@ -54,9 +116,11 @@ pub enum DataKey {
     ActiveRuleIds,
     AddressJurisdiction(Address),
     PendingApproval(BytesN<32>),
+    DataVersion,
+    PendingAdmin,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[contracterror]
 pub enum ContractError {
     NotAuthorized = 1,
@@ -66,6 +130,33 @@ pub enum ContractError {
     InvalidApprovalKey = 5,
     ApprovalExpired = 6,
     NoMatchingRule = 7,
+    NoPendingAdmin = 8,
+    AdminProposalExpired = 9,
+    ApprovalExists = 10,
+}
+
+#[contractevent]
+pub struct AdminProposedEvent {
+    pub current_admin: Address,
+    pub proposed_admin: Address,
+    pub expiration_ledger: u32,
+}
+
+#[contractevent]
+pub struct AdminTransferredEvent {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent]
+pub struct UpgradeEvent {
+    pub new_wasm_hash: BytesN<32>,
+}
+
+#[contractevent]
+pub struct MigrateEvent {
+    pub from_version: u32,
+    pub to_version: u32,
 }
 
 #[contract]
@@ -95,6 +186,9 @@ impl RegulatoryCheck {
         env.storage()
             .instance()
             .set(&DataKey::ActiveRuleIds, &active_rules);
+        env.storage()
+            .instance()
+            .set(&DataKey::DataVersion, &DATA_VERSION);
     }
 
     // ========================================================================
@@ -248,6 +342,7 @@ impl RegulatoryCheck {
         destination_address: Address,
         operation: OperationType,
         host_jurisdiction: String,
+        credit: CreditContext,
     ) -> ValidationResult {
         let source_jur = Self::get_address_jurisdiction(env.clone(), source_address.clone());
 
@@ -290,6 +385,7 @@ impl RegulatoryCheck {
                     &dest_jur,
                     &host_jurisdiction,
                     &operation,
+                    &credit,
                 ) {
                     // Rule matched
                     if rule.is_allowed {
@@ -372,7 +468,8 @@ impl RegulatoryCheck {
         Ok(())
     }
 
-    /// Create pending approval request
+    /// Create pending approval request; only the registered CarbonAsset contract may
+    /// open one, and an existing request is never overwritten
     pub fn create_pending_approval(
         env: Env,
         approval_key: BytesN<32>,
@@ -380,7 +477,19 @@ impl RegulatoryCheck {
         source: Address,
         destination: Address,
         operation: OperationType,
-    ) {
+    ) -> Result<(), ContractError> {
+        let carbon_asset: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::CarbonAssetContract)
+            .ok_or(ContractError::NotAuthorized)?;
+        carbon_asset.require_auth();
+
+        let key = DataKey::PendingApproval(approval_key);
+        if env.storage().persistent().has(&key) {
+            return Err(ContractError::ApprovalExists);
+        }
+
         let pending = PendingApproval {
             token_id,
             source,
@@ -389,9 +498,8 @@ impl RegulatoryCheck {
             timestamp: env.ledger().timestamp(),
             approved: false,
         };
-
-        let key = DataKey::PendingApproval(approval_key);
         env.storage().persistent().set(&key, &pending);
+        Ok(())
     }
 
     /// Check if approval exists and is valid
@@ -422,6 +530,7 @@ impl RegulatoryCheck {
         dest_jur: &String,
         host_jur: &String,
         operation: &OperationType,
+        credit: &CreditContext,
     ) -> bool {
         let any = String::from_str(env, "ANY");
 
@@ -441,6 +550,40 @@ impl RegulatoryCheck {
             return false;
         }
 
+        Self::credit_matches(&rule.credit_criteria, credit)
+    }
+
+    fn credit_matches(criteria: &CreditCriteria, credit: &CreditContext) -> bool {
+        if let Some(min) = criteria.min_vintage {
+            if credit.vintage_year < min {
+                return false;
+            }
+        }
+
+        if let Some(max) = criteria.max_vintage {
+            if credit.vintage_year > max {
+                return false;
+            }
+        }
+
+        if !criteria.methodology_ids.is_empty()
+            && !criteria.methodology_ids.contains(credit.methodology_id)
+        {
+            return false;
+        }
+
+        if !criteria.countries.is_empty() && !criteria.countries.contains(&credit.country) {
+            return false;
+        }
+
+        if !criteria.corresponding_adjustments.is_empty()
+            && !criteria
+                .corresponding_adjustments
+                .contains(&credit.corresponding_adjustment)
+        {
+            return false;
+        }
+
         true
     }
 
@@ -448,11 +591,12 @@ impl RegulatoryCheck {
     // Admin Functions
     // ========================================================================
 
-    /// Update admin address
-    pub fn update_admin(
+    /// Propose a new admin; takes effect once accepted before `expiration_ledger`
+    pub fn propose_admin(
         env: Env,
         caller: Address,
         new_admin: Address,
+        expiration_ledger: u32,
     ) -> Result<(), ContractError> {
         caller.require_auth();
 
@@ -462,10 +606,62 @@ impl RegulatoryCheck {
             return Err(ContractError::NotAuthorized);
         }
 
+        if expiration_ledger < env.ledger().sequence() {
+            return Err(ContractError::AdminProposalExpired);
+        }
+
+        let pending = PendingAdmin {
+            new_admin: new_admin.clone(),
+            expiration_ledger,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::PendingAdmin, &pending);
+
+        AdminProposedEvent {
+            current_admin: admin,
+            proposed_admin: new_admin,
+            expiration_ledger,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Accept a pending admin proposal; must be called by the proposed admin
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), ContractError> {
+        new_admin.require_auth();
+
+        let pending: PendingAdmin = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .ok_or(ContractError::NoPendingAdmin)?;
+
+        if pending.new_admin != new_admin {
+            return Err(ContractError::NotAuthorized);
+        }
+
+        if env.ledger().sequence() > pending.expiration_ledger {
+            return Err(ContractError::AdminProposalExpired);
+        }
+
+        let previous_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
         env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        AdminTransferredEvent {
+            previous_admin,
+            new_admin,
+        }
+        .publish(&env);
         Ok(())
     }
 
+    /// Get the pending admin proposal, if any
+    pub fn get_pending_admin(env: Env) -> Option<PendingAdmin> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
     /// Update governance address
     pub fn update_governance(
         env: Env,
@@ -486,6 +682,96 @@ impl RegulatoryCheck {
         Ok(())
     }
 
+    /// Replace the contract WASM, keeping its address and storage
+    pub fn upgrade(
+        env: Env,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+
+        if caller != admin {
+            return Err(ContractError::NotAuthorized);
+        }
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        UpgradeEvent { new_wasm_hash }.publish(&env);
+        Ok(())
+    }
+
+    /// Bring stored data up to `DATA_VERSION` and return the resulting version
+    pub fn migrate(env: Env, caller: Address) -> Result<u32, ContractError> {
+        caller.require_auth();
+
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+
+        if caller != admin {
+            return Err(ContractError::NotAuthorized);
+        }
+
+        let from_version = Self::get_data_version(env.clone());
+
+        // v0 -> v1: rules gained `credit_criteria`; existing rules match any credit.
+        if from_version < 1 {
+            let active_rules = Self::get_active_rules(env.clone());
+            for rule_id in active_rules.iter() {
+                let rule_key = DataKey::Rule(rule_id);
+                // Rules written after the upgrade already have the new layout.
+                let stored: Option<Map<Symbol, Val>> = env.storage().persistent().get(&rule_key);
+                let is_legacy = stored
+                    .map(|fields| !fields.contains_key(Symbol::new(&env, "credit_criteria")))
+                    .unwrap_or(false);
+                if is_legacy {
+                    let old: JurisdictionRuleV0 =
+                        env.storage().persistent().get(&rule_key).unwrap();
+                    let rule = JurisdictionRule {
+                        rule_id: old.rule_id,
+                        description: old.description,
+                        source_jur: old.source_jur,
+                        dest_jur: old.dest_jur,
+                        host_jur: old.host_jur,
+                        operation: old.operation,
+                        is_allowed: old.is_allowed,
+                        required_authority: old.required_authority,
+                        credit_criteria: CreditCriteria {
+                            min_vintage: None,
+                            max_vintage: None,
+                            methodology_ids: Vec::new(&env),
+                            countries: Vec::new(&env),
+                            corresponding_adjustments: Vec::new(&env),
+                        },
+                    };
+                    env.storage().persistent().set(&rule_key, &rule);
+                }
+            }
+        }
+
+        if from_version < DATA_VERSION {
+            env.storage()
+                .instance()
+                .set(&DataKey::DataVersion, &DATA_VERSION);
+
+            MigrateEvent {
+                from_version,
+                to_version: DATA_VERSION,
+            }
+            .publish(&env);
+        }
+        Ok(Self::get_data_version(env))
+    }
+
+    /// Get the stored data version (0 for deployments that predate versioning)
+    pub fn get_data_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::DataVersion)
+            .unwrap_or(0)
+    }
+
     /// Get rule by ID
     pub fn get_rule(env: Env, rule_id: String) -> Option<JurisdictionRule> {
         let key = DataKey::Rule(rule_id);
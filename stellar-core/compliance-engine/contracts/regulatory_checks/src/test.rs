@@ -0,0 +1,253 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{vec, Address, Env, String};
+
+fn credit(env: &Env, vintage_year: u64, methodology_id: u32) -> CreditContext {
+    CreditContext {
+        token_id: 1,
+        project_id: String::from_str(env, "PROJ-0"),
+        vintage_year,
+        methodology_id,
+        country: String::from_str(env, "BR"),
+        corresponding_adjustment: CorrespondingAdjustment::Applied,
+    }
+}
+
+fn any_credit(env: &Env) -> CreditCriteria {
+    CreditCriteria {
+        min_vintage: None,
+        max_vintage: None,
+        methodology_ids: Vec::new(env),
+        countries: Vec::new(env),
+        corresponding_adjustments: Vec::new(env),
+    }
+}
+
+fn rule(env: &Env, rule_id: &str, is_allowed: bool, criteria: CreditCriteria) -> JurisdictionRule {
+    let any = String::from_str(env, "ANY");
+    JurisdictionRule {
+        rule_id: String::from_str(env, rule_id),
+        description: String::from_str(env, rule_id),
+        source_jur: any.clone(),
+        dest_jur: any.clone(),
+        host_jur: any,
+        operation: OperationType::TRANSFER,
+        is_allowed,
+        required_authority: None,
+        credit_criteria: criteria,
+    }
+}
+
+#[test]
+fn test_rule_predicates_on_credit_metadata() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(RegulatoryCheck, ());
+    let client = RegulatoryCheckClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let governance = Address::generate(&env);
+    let carbon_asset = Address::generate(&env);
+    client.initialize(&admin, &governance, &carbon_asset);
+
+    let source = Address::generate(&env);
+    let destination = Address::generate(&env);
+    client.set_address_jurisdiction(&admin, &source, &String::from_str(&env, "BR"));
+    client.set_address_jurisdiction(&admin, &destination, &String::from_str(&env, "CH"));
+
+    // CORSIA-style eligibility: 2021+ vintages, approved methodologies, with
+    // a corresponding adjustment applied. Everything else falls through to
+    // the catch-all prohibition.
+    let corsia = CreditCriteria {
+        min_vintage: Some(1609459200),
+        max_vintage: None,
+        methodology_ids: vec![&env, 1u32, 2u32],
+        corresponding_adjustments: vec![&env, CorrespondingAdjustment::Applied],
+        ..any_credit(&env)
+    };
+    client.add_rule(&governance, &rule(&env, "CORSIA", true, corsia));
+    client.add_rule(&governance, &rule(&env, "DEFAULT", false, any_credit(&env)));
+
+    let host = String::from_str(&env, "US");
+
+    let eligible = client.validate_transaction(
+        &source,
+        &destination,
+        &OperationType::TRANSFER,
+        &host,
+        &credit(&env, 1704067200, 1),
+    );
+    assert!(eligible.is_compliant);
+    assert_eq!(eligible.rule_id, Some(String::from_str(&env, "CORSIA")));
+
+    let old_vintage = client.validate_transaction(
+        &source,
+        &destination,
+        &OperationType::TRANSFER,
+        &host,
+        &credit(&env, 1546300800, 1),
+    );
+    assert!(!old_vintage.is_compliant);
+    assert_eq!(old_vintage.rule_id, Some(String::from_str(&env, "DEFAULT")));
+
+    let other_methodology = client.validate_transaction(
+        &source,
+        &destination,
+        &OperationType::TRANSFER,
+        &host,
+        &credit(&env, 1704067200, 9),
+    );
+    assert!(!other_methodology.is_compliant);
+
+    let mut unadjusted = credit(&env, 1704067200, 1);
+    unadjusted.corresponding_adjustment = CorrespondingAdjustment::Pending;
+    let result = client.validate_transaction(
+        &source,
+        &destination,
+        &OperationType::TRANSFER,
+        &host,
+        &unadjusted,
+    );
+    assert!(!result.is_compliant);
+}
+
+#[test]
+fn test_migrate_backfills_credit_criteria() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(RegulatoryCheck, ());
+    let client = RegulatoryCheckClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let governance = Address::generate(&env);
+    client.initialize(&admin, &governance, &Address::generate(&env));
+
+    // Simulate a deployment from before credit criteria and data versioning.
+    let any = String::from_str(&env, "ANY");
+    let legacy_id = String::from_str(&env, "LEGACY");
+    env.as_contract(&contract_id, || {
+        let legacy = JurisdictionRuleV0 {
+            rule_id: legacy_id.clone(),
+            description: legacy_id.clone(),
+            source_jur: any.clone(),
+            dest_jur: any.clone(),
+            host_jur: any.clone(),
+            operation: OperationType::TRANSFER,
+            is_allowed: true,
+            required_authority: None,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Rule(legacy_id.clone()), &legacy);
+        env.storage()
+            .instance()
+            .set(&DataKey::ActiveRuleIds, &vec![&env, legacy_id.clone()]);
+        env.storage().instance().remove(&DataKey::DataVersion);
+    });
+    client.add_rule(&governance, &rule(&env, "NEW", false, any_credit(&env)));
+
+    assert_eq!(client.get_data_version(), 0);
+    assert_eq!(
+        client.try_migrate(&governance),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    assert_eq!(client.migrate(&admin), 1);
+    assert_eq!(client.get_data_version(), 1);
+
+    let migrated = client.get_rule(&legacy_id).unwrap();
+    assert!(migrated.credit_criteria.methodology_ids.is_empty());
+    assert!(client.get_rule(&String::from_str(&env, "NEW")).is_some());
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(RegulatoryCheck, ());
+    let client = RegulatoryCheckClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    client.initialize(&admin, &Address::generate(&env), &Address::generate(&env));
+
+    let expiration = env.ledger().sequence() + 10;
+    client.propose_admin(&admin, &new_admin, &expiration);
+    assert_eq!(
+        client.try_accept_admin(&admin),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+
+    env.ledger()
+        .with_mut(|li| li.sequence_number = expiration + 1);
+    assert_eq!(
+        client.try_accept_admin(&new_admin),
+        Err(Ok(ContractError::AdminProposalExpired))
+    );
+
+    let expiration = env.ledger().sequence() + 10;
+    client.propose_admin(&admin, &new_admin, &expiration);
+    client.accept_admin(&new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+
+    let account = Address::generate(&env);
+    let jurisdiction = String::from_str(&env, "BR");
+    assert_eq!(
+        client.try_set_address_jurisdiction(&admin, &account, &jurisdiction),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+    client.set_address_jurisdiction(&new_admin, &account, &jurisdiction);
+}
+
+#[test]
+fn test_only_carbon_asset_opens_pending_approvals() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(RegulatoryCheck, ());
+    let client = RegulatoryCheckClient::new(&env, &contract_id);
+
+    let carbon_asset = Address::generate(&env);
+    client.initialize(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &carbon_asset,
+    );
+
+    let key = BytesN::from_array(&env, &[7u8; 32]);
+    let source = Address::generate(&env);
+    let destination = Address::generate(&env);
+    client.create_pending_approval(&key, &1, &source, &destination, &OperationType::TRANSFER);
+    assert_eq!(env.auths()[0].0, carbon_asset);
+
+    let authority = Address::generate(&env);
+    client.record_authorization(&authority, &key);
+    assert!(client.check_approval(&key));
+
+    // An approved request cannot be reset by opening it again.
+    assert_eq!(
+        client.try_create_pending_approval(
+            &key,
+            &1,
+            &source,
+            &destination,
+            &OperationType::TRANSFER
+        ),
+        Err(Ok(ContractError::ApprovalExists))
+    );
+    assert!(client.check_approval(&key));
+
+    // Without the CarbonAsset contract's authorization nothing is written.
+    env.set_auths(&[]);
+    let other_key = BytesN::from_array(&env, &[8u8; 32]);
+    assert!(client
+        .try_create_pending_approval(
+            &other_key,
+            &1,
+            &source,
+            &destination,
+            &OperationType::TRANSFER
+        )
+        .is_err());
+    assert!(!client.check_approval(&other_key));
+}
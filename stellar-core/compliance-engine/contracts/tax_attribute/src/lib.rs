@@ -1,5 +1,12 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, String, Vec};
+mod test;
+
+use soroban_sdk::{
+    contract, contractevent, contractimpl, contracttype, Address, BytesN, Env, String, Vec,
+};
+
+/// Storage layout version written by this build. Bump together with a step in `migrate`.
+const DATA_VERSION: u32 = 1;
 
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
@@ -14,6 +21,39 @@ pub struct TaxAttributeTag {
     pub attached_at: u64,
 }
 
+/// Admin handover waiting for the proposed address to accept it.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingAdmin {
+    pub new_admin: Address,
+    pub expiration_ledger: u32,
+}
+
+#[contractevent]
+pub struct AdminProposed {
+    pub current_admin: Address,
+    pub proposed_admin: Address,
+    pub expiration_ledger: u32,
+}
+
+#[contractevent]
+pub struct AdminTransferred {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent]
+pub struct ContractUpgraded {
+    pub new_wasm_hash: BytesN<32>,
+    pub upgraded_by: Address,
+}
+
+#[contractevent]
+pub struct DataMigrated {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
 #[contracttype]
 pub enum DataKey {
     Admin,                // Address
@@ -21,6 +61,8 @@ pub enum DataKey {
     Attribute(String),    // TaxAttributeTag
     TokenAttributes(u32), // Vec<String> (list of tag_ids)
     AllIssuers,           // Vec<Address>
+    DataVersion,          // u32
+    PendingAdmin,         // PendingAdmin
 }
 
 #[contract]
@@ -48,6 +90,104 @@ impl TaxAttributeContract {
         env.storage()
             .instance()
             .set(&DataKey::AllIssuers, &empty_issuers);
+        env.storage()
+            .instance()
+            .set(&DataKey::DataVersion, &DATA_VERSION);
+    }
+
+    /// First step of an admin handover. `new_admin` must call `accept_admin`
+    /// on or before `expiration_ledger`.
+    pub fn propose_admin(env: Env, new_admin: Address, expiration_ledger: u32) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if expiration_ledger < env.ledger().sequence() {
+            panic!("Admin proposal expired");
+        }
+
+        let pending = PendingAdmin {
+            new_admin: new_admin.clone(),
+            expiration_ledger,
+        };
+        env.storage().instance().set(&DataKey::PendingAdmin, &pending);
+
+        AdminProposed {
+            current_admin: admin,
+            proposed_admin: new_admin,
+            expiration_ledger,
+        }
+        .publish(&env);
+    }
+
+    pub fn accept_admin(env: Env) {
+        let pending: PendingAdmin = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .expect("No pending admin");
+        pending.new_admin.require_auth();
+
+        if env.ledger().sequence() > pending.expiration_ledger {
+            panic!("Admin proposal expired");
+        }
+
+        let previous_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        env.storage()
+            .instance()
+            .set(&DataKey::Admin, &pending.new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        AdminTransferred {
+            previous_admin,
+            new_admin: pending.new_admin,
+        }
+        .publish(&env);
+    }
+
+    pub fn get_pending_admin(env: Env) -> Option<PendingAdmin> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        ContractUpgraded {
+            new_wasm_hash,
+            upgraded_by: admin,
+        }
+        .publish(&env);
+    }
+
+    /// Brings stored data up to `DATA_VERSION` after an upgrade and returns the
+    /// resulting version. A no-op when already current.
+    pub fn migrate(env: Env) -> u32 {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let from_version = Self::get_data_version(env.clone());
+        if from_version < DATA_VERSION {
+            env.storage()
+                .instance()
+                .set(&DataKey::DataVersion, &DATA_VERSION);
+
+            DataMigrated {
+                from_version,
+                to_version: DATA_VERSION,
+            }
+            .publish(&env);
+        }
+        Self::get_data_version(env)
+    }
+
+    pub fn get_data_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::DataVersion)
+            .unwrap_or(0)
     }
 
     pub fn add_issuer(env: Env, issuer: Address) {
@@ -0,0 +1,162 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::testutils::{Address as _, Events as _, Ledger, MockAuth, MockAuthInvoke};
+use soroban_sdk::{Bytes, IntoVal, Map, Symbol, TryFromVal, Val};
+
+fn setup() -> (Env, TaxAttributeContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(TaxAttributeContract, ());
+    let client = TaxAttributeContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.init(&admin);
+    (env, client, admin)
+}
+
+// Fields of the `name` event the contract published in the last call, if any.
+fn published(env: &Env, contract: &Address, name: &str) -> Option<Map<Symbol, Val>> {
+    let topic = Symbol::new(env, name);
+    env.events()
+        .all()
+        .iter()
+        .find_map(|(emitter, topics, data)| {
+            let event = Symbol::try_from_val(env, &topics.get(0)?).ok()?;
+            if emitter != *contract || event != topic {
+                return None;
+            }
+            Map::try_from_val(env, &data).ok()
+        })
+}
+
+fn field<T: TryFromVal<Env, Val>>(env: &Env, fields: &Map<Symbol, Val>, name: &str) -> T {
+    T::try_from_val(env, &fields.get(Symbol::new(env, name)).unwrap()).unwrap()
+}
+
+// Smallest module the host accepts as contract code: the wasm header and a
+// `contractenvmetav0` section declaring protocol 23. It exports nothing, so
+// it only stands in as an upgrade target.
+fn upload_empty_wasm(env: &Env) -> BytesN<32> {
+    let mut wasm = [0u8; 40];
+    wasm[..10].copy_from_slice(&[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x00, 0x1e]);
+    wasm[10] = 17;
+    wasm[11..28].copy_from_slice(b"contractenvmetav0");
+    wasm[35] = 23;
+    env.deployer()
+        .upload_contract_wasm(Bytes::from_slice(env, &wasm))
+}
+
+#[test]
+fn test_migrate_brings_unversioned_data_current() {
+    let (env, client, admin) = setup();
+    assert_eq!(client.get_data_version(), 1);
+
+    env.as_contract(&client.address, || {
+        env.storage().instance().remove(&DataKey::DataVersion);
+    });
+    assert_eq!(client.get_data_version(), 0);
+
+    assert_eq!(client.migrate(), 1);
+    assert_eq!(env.auths()[0].0, admin);
+    let migrated = published(&env, &client.address, "data_migrated").unwrap();
+    assert_eq!(field::<u32>(&env, &migrated, "from_version"), 0);
+    assert_eq!(field::<u32>(&env, &migrated, "to_version"), 1);
+    assert_eq!(client.get_data_version(), 1);
+
+    // Already current: nothing to report.
+    assert_eq!(client.migrate(), 1);
+    assert!(published(&env, &client.address, "data_migrated").is_none());
+}
+
+#[test]
+fn test_upgrade_requires_admin() {
+    let (env, client, admin) = setup();
+    let wasm_hash = upload_empty_wasm(&env);
+
+    let outsider = Address::generate(&env);
+    env.mock_auths(&[MockAuth {
+        address: &outsider,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "upgrade",
+            args: (wasm_hash.clone(),).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client.try_upgrade(&wasm_hash).is_err());
+
+    env.mock_all_auths();
+    client.upgrade(&wasm_hash);
+    let upgraded = published(&env, &client.address, "contract_upgraded").unwrap();
+    assert_eq!(
+        field::<BytesN<32>>(&env, &upgraded, "new_wasm_hash"),
+        wasm_hash
+    );
+    assert_eq!(field::<Address>(&env, &upgraded, "upgraded_by"), admin);
+    assert_eq!(env.auths()[0].0, admin);
+
+    // The replacement code exports nothing, so the old entry points are gone.
+    assert!(client.try_get_data_version().is_err());
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let (env, client, admin) = setup();
+    let new_admin = Address::generate(&env);
+
+    let expiration = env.ledger().sequence() + 10;
+    client.propose_admin(&new_admin, &expiration);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(
+        client.get_pending_admin(),
+        Some(PendingAdmin {
+            new_admin: new_admin.clone(),
+            expiration_ledger: expiration,
+        })
+    );
+
+    client.accept_admin();
+    assert_eq!(env.auths()[0].0, new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+
+    // Only the new admin can manage issuers from now on.
+    client.add_issuer(&Address::generate(&env));
+    assert_eq!(env.auths()[0].0, new_admin);
+}
+
+#[test]
+#[should_panic(expected = "Admin proposal expired")]
+fn test_expired_admin_proposal() {
+    let (env, client, _) = setup();
+
+    let expiration = env.ledger().sequence() + 10;
+    client.propose_admin(&Address::generate(&env), &expiration);
+    env.ledger()
+        .with_mut(|li| li.sequence_number = expiration + 1);
+    client.accept_admin();
+}
+
+#[test]
+fn test_accept_admin_requires_proposed_address() {
+    let (env, client, admin) = setup();
+    let new_admin = Address::generate(&env);
+    client.propose_admin(&new_admin, &(env.ledger().sequence() + 10));
+
+    let outsider = Address::generate(&env);
+    env.mock_auths(&[MockAuth {
+        address: &outsider,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "accept_admin",
+            args: ().into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client.try_accept_admin().is_err());
+
+    // The proposal is still open for the proposed address.
+    env.mock_all_auths();
+    assert_eq!(client.get_pending_admin().unwrap().new_admin, new_admin);
+    client.migrate();
+    assert_eq!(env.auths()[0].0, admin);
+}
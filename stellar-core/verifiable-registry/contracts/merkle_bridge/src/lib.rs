@@ -5,6 +5,10 @@ use soroban_sdk::{
     BytesN, Env, String, Vec,
 };
 
+/// Storage layout version written by this build. Bump together with a step in
+/// `migrate` whenever the layout changes.
+const DATA_VERSION: u32 = 1;
+
 /// Storage keys for the contract's persistent data
 #[contracttype]
 #[derive(Clone)]
@@ -25,6 +29,18 @@ pub enum DataKey {
     RetiredCredit(String),
     /// Next token ID for minting
     NextTokenId,
+    /// Storage layout version (0 when absent)
+    DataVersion,
+    /// Admin handover awaiting acceptance
+    PendingAdmin,
+}
+
+/// Admin handover waiting for the proposed address to accept it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingAdmin {
+    pub new_admin: Address,
+    pub expiration_ledger: u32,
 }
 
 /// Credit status enum for leaf node construction
@@ -62,6 +78,39 @@ pub struct CreditRetiredEvent {
     pub retired_by: Address,
 }
 
+/// Event emitted when an admin handover is proposed
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AdminProposedEvent {
+    pub current_admin: Address,
+    pub proposed_admin: Address,
+    pub expiration_ledger: u32,
+}
+
+/// Event emitted when a proposed admin accepts the handover
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AdminTransferredEvent {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+/// Event emitted when the contract WASM is replaced
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ContractUpgradedEvent {
+    pub new_wasm_hash: BytesN<32>,
+    pub upgraded_by: Address,
+}
+
+/// Event emitted when stored data is migrated to a newer layout
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct DataMigratedEvent {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
 /// Contract error codes
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -91,6 +140,10 @@ pub enum MerkleBridgeError {
     CarbonAssetNotSet = 11,
     /// Epoch must be sequential
     NonSequentialEpoch = 12,
+    /// No admin handover has been proposed
+    NoPendingAdmin = 13,
+    /// Admin proposal is past its expiration ledger
+    AdminProposalExpired = 14,
 }
 
 // Note: CarbonAsset contract integration will be added once the CarbonAsset
@@ -136,6 +189,9 @@ impl MerkleBridge {
         env.storage().instance().set(&DataKey::Updater, &updater);
         env.storage().instance().set(&DataKey::CurrentEpoch, &0u64);
         env.storage().instance().set(&DataKey::NextTokenId, &1u32);
+        env.storage()
+            .instance()
+            .set(&DataKey::DataVersion, &DATA_VERSION);
 
         log!(&env, "MerkleBridge initialized with admin: {}", admin);
 
@@ -397,6 +453,157 @@ impl MerkleBridge {
             .ok_or(MerkleBridgeError::RootNotFound)
     }
 
+    /// Propose a new admin (first step of a two-step handover)
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `caller` - The caller address (must be admin)
+    /// * `new_admin` - The address that must call `accept_admin`
+    /// * `expiration_ledger` - Last ledger on which the proposal can be accepted
+    ///
+    /// # Returns
+    /// * `Result<(), MerkleBridgeError>` - Success or error
+    pub fn propose_admin(
+        env: Env,
+        caller: Address,
+        new_admin: Address,
+        expiration_ledger: u32,
+    ) -> Result<(), MerkleBridgeError> {
+        caller.require_auth();
+        Self::require_admin(&env, &caller)?;
+
+        if expiration_ledger < env.ledger().sequence() {
+            return Err(MerkleBridgeError::AdminProposalExpired);
+        }
+
+        env.storage().instance().set(
+            &DataKey::PendingAdmin,
+            &PendingAdmin {
+                new_admin: new_admin.clone(),
+                expiration_ledger,
+            },
+        );
+
+        AdminProposedEvent {
+            current_admin: caller,
+            proposed_admin: new_admin,
+            expiration_ledger,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Accept a pending admin proposal (second step of the handover)
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `new_admin` - The proposed admin address
+    ///
+    /// # Returns
+    /// * `Result<(), MerkleBridgeError>` - Success or error
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), MerkleBridgeError> {
+        new_admin.require_auth();
+
+        let pending: PendingAdmin = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .ok_or(MerkleBridgeError::NoPendingAdmin)?;
+
+        if pending.new_admin != new_admin {
+            return Err(MerkleBridgeError::NotAdmin);
+        }
+        if env.ledger().sequence() > pending.expiration_ledger {
+            return Err(MerkleBridgeError::AdminProposalExpired);
+        }
+
+        let previous_admin = Self::get_admin(env.clone())?;
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        AdminTransferredEvent {
+            previous_admin,
+            new_admin,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Get the pending admin proposal, if any
+    pub fn get_pending_admin(env: Env) -> Option<PendingAdmin> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    /// Replace the contract WASM, keeping its address and storage
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `caller` - The caller address (must be admin)
+    /// * `new_wasm_hash` - Hash of the WASM already uploaded to the network
+    ///
+    /// # Returns
+    /// * `Result<(), MerkleBridgeError>` - Success or error
+    pub fn upgrade(
+        env: Env,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), MerkleBridgeError> {
+        caller.require_auth();
+        Self::require_admin(&env, &caller)?;
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        ContractUpgradedEvent {
+            new_wasm_hash,
+            upgraded_by: caller,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Migrate stored data to the layout expected by the current WASM
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `caller` - The caller address (must be admin)
+    ///
+    /// # Returns
+    /// * `Result<u32, MerkleBridgeError>` - The resulting data version; a no-op
+    ///   when already current
+    pub fn migrate(env: Env, caller: Address) -> Result<u32, MerkleBridgeError> {
+        caller.require_auth();
+        Self::require_admin(&env, &caller)?;
+
+        let from_version = Self::get_data_version(env.clone());
+        if from_version >= DATA_VERSION {
+            return Ok(from_version);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::DataVersion, &DATA_VERSION);
+
+        DataMigratedEvent {
+            from_version,
+            to_version: DATA_VERSION,
+        }
+        .publish(&env);
+
+        Ok(DATA_VERSION)
+    }
+
+    /// Get the stored data version (0 for deployments that predate versioning)
+    pub fn get_data_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::DataVersion)
+            .unwrap_or(0)
+    }
+
     /// Check if a credit has been minted
     pub fn is_minted(env: Env, registry_credit_id: String) -> bool {
         Self::is_credit_minted(&env, &registry_credit_id)
@@ -514,8 +721,8 @@ impl MerkleBridge {
         let id_len = registry_credit_id.len() as usize;
         let mut id_buffer = [0u8; 256]; // Max length buffer
         registry_credit_id.copy_into_slice(&mut id_buffer[..id_len]);
-        for i in 0..id_len {
-            data.push_back(id_buffer[i]);
+        for byte in &id_buffer[..id_len] {
+            data.push_back(*byte);
         }
 
         // Append status bytes
@@ -598,7 +805,10 @@ impl MerkleBridge {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Bytes, Env};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Bytes, Env,
+    };
 
     fn setup_env() -> (Env, Address, Address) {
         let env = Env::default();
@@ -698,6 +908,75 @@ mod tests {
         client.set_updater(&not_admin, &new_updater); // Should panic
     }
 
+    #[test]
+    fn test_admin_handover() {
+        let (env, admin, updater) = setup_env();
+        let contract_id = create_contract(&env);
+        let client = MerkleBridgeClient::new(&env, &contract_id);
+
+        client.initialize(&admin, &updater);
+
+        let new_admin = Address::generate(&env);
+        let expiration = env.ledger().sequence() + 10;
+        client.propose_admin(&admin, &new_admin, &expiration);
+        assert_eq!(
+            client.get_pending_admin(),
+            Some(PendingAdmin {
+                new_admin: new_admin.clone(),
+                expiration_ledger: expiration,
+            })
+        );
+        // Nothing changes until the proposed admin accepts.
+        assert_eq!(client.get_admin(), admin);
+
+        client.accept_admin(&new_admin);
+        assert_eq!(client.get_admin(), new_admin);
+        assert_eq!(client.get_pending_admin(), None);
+
+        // Only the new admin can manage the bridge from now on.
+        let new_updater = Address::generate(&env);
+        assert_eq!(
+            client.try_set_updater(&admin, &new_updater),
+            Err(Ok(MerkleBridgeError::NotAdmin))
+        );
+        client.set_updater(&new_admin, &new_updater);
+        assert_eq!(client.get_updater(), new_updater);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #14)")]
+    fn test_accept_admin_expired_fails() {
+        let (env, admin, updater) = setup_env();
+        let contract_id = create_contract(&env);
+        let client = MerkleBridgeClient::new(&env, &contract_id);
+
+        client.initialize(&admin, &updater);
+
+        let new_admin = Address::generate(&env);
+        let expiration = env.ledger().sequence() + 10;
+        client.propose_admin(&admin, &new_admin, &expiration);
+
+        env.ledger()
+            .with_mut(|li| li.sequence_number = expiration + 1);
+        client.accept_admin(&new_admin); // Should panic
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #3)")]
+    fn test_accept_admin_wrong_address_fails() {
+        let (env, admin, updater) = setup_env();
+        let contract_id = create_contract(&env);
+        let client = MerkleBridgeClient::new(&env, &contract_id);
+
+        client.initialize(&admin, &updater);
+
+        let new_admin = Address::generate(&env);
+        client.propose_admin(&admin, &new_admin, &(env.ledger().sequence() + 10));
+
+        let not_proposed = Address::generate(&env);
+        client.accept_admin(&not_proposed); // Should panic
+    }
+
     #[test]
     fn test_update_root() {
         let (env, admin, updater) = setup_env();
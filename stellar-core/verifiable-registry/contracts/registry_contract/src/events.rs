@@ -1,4 +1,4 @@
-use soroban_sdk::{contractevent, Env, String};
+use soroban_sdk::{contractevent, Address, BytesN, Env, String};
 
 /// Structured event emitted when a document is anchored
 /// This enables off-chain indexing and real-time updates to Layer 3 portals
@@ -28,3 +28,69 @@ pub fn emit_document_anchored_event(
     }
     .publish(env);
 }
+
+/// Event emitted when an admin handover is proposed
+#[contractevent]
+pub struct AdminProposed {
+    pub current_admin: Address,
+    pub proposed_admin: Address,
+    pub expiration_ledger: u32,
+}
+
+/// Event emitted when a proposed admin accepts the handover
+#[contractevent]
+pub struct AdminTransferred {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+/// Event emitted when the contract WASM is replaced
+#[contractevent]
+pub struct ContractUpgraded {
+    pub new_wasm_hash: BytesN<32>,
+}
+
+/// Event emitted when stored data is migrated to a newer layout
+#[contractevent]
+pub struct DataMigrated {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+/// Emit an event when the contract WASM is replaced
+pub fn emit_contract_upgraded_event(env: &Env, new_wasm_hash: BytesN<32>) {
+    ContractUpgraded { new_wasm_hash }.publish(env);
+}
+
+/// Emit an event when stored data is migrated
+pub fn emit_data_migrated_event(env: &Env, from_version: u32, to_version: u32) {
+    DataMigrated {
+        from_version,
+        to_version,
+    }
+    .publish(env);
+}
+
+/// Emit an event when an admin handover is proposed
+pub fn emit_admin_proposed_event(
+    env: &Env,
+    current_admin: Address,
+    proposed_admin: Address,
+    expiration_ledger: u32,
+) {
+    AdminProposed {
+        current_admin,
+        proposed_admin,
+        expiration_ledger,
+    }
+    .publish(env);
+}
+
+/// Emit an event when an admin handover completes
+pub fn emit_admin_transferred_event(env: &Env, previous_admin: Address, new_admin: Address) {
+    AdminTransferred {
+        previous_admin,
+        new_admin,
+    }
+    .publish(env);
+}
@@ -5,10 +5,13 @@ mod storage;
 mod types;
 mod validation;
 
-use events::emit_document_anchored_event;
-use soroban_sdk::{contract, contractimpl, Address, Env, String, Vec};
+use events::{
+    emit_admin_proposed_event, emit_admin_transferred_event, emit_contract_upgraded_event,
+    emit_data_migrated_event, emit_document_anchored_event,
+};
+use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, String, Vec};
 use storage::extend_instance_ttl;
-use types::{DocumentRecord, Error};
+use types::{DocumentRecord, Error, PendingAdmin};
 use validation::validate_ipfs_cid;
 
 #[contract]
@@ -24,11 +27,96 @@ impl ProjectRegistry {
 
         admin.require_auth();
         storage::set_admin(&env, &admin);
+        storage::set_data_version(&env, storage::DATA_VERSION);
         extend_instance_ttl(&env);
 
         Ok(())
     }
 
+    /// Propose a new admin; takes effect once accepted on or before `expiration_ledger`
+    pub fn propose_admin(env: Env, new_admin: Address, expiration_ledger: u32) -> Result<(), Error> {
+        let admin = storage::get_admin(&env)?;
+        admin.require_auth();
+
+        if expiration_ledger < env.ledger().sequence() {
+            return Err(Error::AdminProposalExpired);
+        }
+
+        storage::set_pending_admin(
+            &env,
+            &PendingAdmin {
+                new_admin: new_admin.clone(),
+                expiration_ledger,
+            },
+        );
+        emit_admin_proposed_event(&env, admin, new_admin, expiration_ledger);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Accept a pending admin proposal (must be called by the proposed admin)
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        let pending = storage::get_pending_admin(&env).ok_or(Error::NoPendingAdmin)?;
+        if pending.new_admin != new_admin {
+            return Err(Error::NotProposedAdmin);
+        }
+        new_admin.require_auth();
+
+        if env.ledger().sequence() > pending.expiration_ledger {
+            return Err(Error::AdminProposalExpired);
+        }
+
+        let previous_admin = storage::get_admin(&env)?;
+        storage::set_admin(&env, &new_admin);
+        storage::remove_pending_admin(&env);
+        emit_admin_transferred_event(&env, previous_admin, new_admin);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Get the pending admin proposal, if any
+    pub fn get_pending_admin(env: Env) -> Option<PendingAdmin> {
+        storage::get_pending_admin(&env)
+    }
+
+    /// Replace the contract WASM in place, keeping address and storage (admin only)
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        let admin = storage::get_admin(&env)?;
+        admin.require_auth();
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        emit_contract_upgraded_event(&env, new_wasm_hash);
+
+        Ok(())
+    }
+
+    /// Migrate stored data to the current layout version (admin only)
+    ///
+    /// Returns the resulting version; a no-op when already current.
+    pub fn migrate(env: Env) -> Result<u32, Error> {
+        let admin = storage::get_admin(&env)?;
+        admin.require_auth();
+
+        let from_version = storage::get_data_version(&env);
+        if from_version >= storage::DATA_VERSION {
+            return Ok(from_version);
+        }
+
+        storage::set_data_version(&env, storage::DATA_VERSION);
+        emit_data_migrated_event(&env, from_version, storage::DATA_VERSION);
+        extend_instance_ttl(&env);
+
+        Ok(storage::DATA_VERSION)
+    }
+
+    /// Get the stored data version (0 for deployments that predate versioning)
+    pub fn get_data_version(env: Env) -> u32 {
+        storage::get_data_version(&env)
+    }
+
     /// Register a new project and assign initial owner (admin only)
     pub fn register_project(env: Env, project_id: String, owner: Address) -> Result<(), Error> {
         let admin = storage::get_admin(&env)?;
@@ -1,10 +1,13 @@
-use crate::types::{DocumentRecord, Error};
+use crate::types::{DocumentRecord, Error, PendingAdmin};
 use soroban_sdk::{contracttype, Address, Env, String, Vec};
 
 const DAY_IN_LEDGERS: u32 = 17280; // Approximately 1 day worth of ledgers (5s per ledger)
 const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS; // 30 days
 const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
 
+/// Storage layout version written by this build. Bump together with a step in `migrate`.
+pub const DATA_VERSION: u32 = 1;
+
 #[contracttype]
 #[derive(Clone)]
 pub enum StorageKey {
@@ -12,6 +15,8 @@ pub enum StorageKey {
     ProjectOwner(String),
     DocumentHistory(String),
     AncorerProjects(Address),
+    DataVersion,
+    PendingAdmin,
 }
 
 /// Extend the TTL of instance storage
@@ -37,6 +42,35 @@ pub fn set_admin(env: &Env, admin: &Address) {
     env.storage().instance().set(&StorageKey::Admin, admin);
 }
 
+// Pending admin storage functions
+pub fn get_pending_admin(env: &Env) -> Option<PendingAdmin> {
+    env.storage().instance().get(&StorageKey::PendingAdmin)
+}
+
+pub fn set_pending_admin(env: &Env, pending: &PendingAdmin) {
+    env.storage()
+        .instance()
+        .set(&StorageKey::PendingAdmin, pending);
+}
+
+pub fn remove_pending_admin(env: &Env) {
+    env.storage().instance().remove(&StorageKey::PendingAdmin);
+}
+
+// Data version storage functions
+pub fn get_data_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&StorageKey::DataVersion)
+        .unwrap_or(0)
+}
+
+pub fn set_data_version(env: &Env, version: u32) {
+    env.storage()
+        .instance()
+        .set(&StorageKey::DataVersion, &version);
+}
+
 // Project owner storage functions
 pub fn has_project_owner(env: &Env, project_id: &String) -> bool {
     let key = StorageKey::ProjectOwner(project_id.clone());
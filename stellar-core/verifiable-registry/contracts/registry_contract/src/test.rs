@@ -1,6 +1,9 @@
 #![cfg(test)]
 
-use soroban_sdk::{testutils::Address as _, Address, Env, String as SorobanString, Vec};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, BytesN, Env, String as SorobanString, Vec,
+};
 
 use crate::types::Error;
 use crate::validation::validate_ipfs_cid;
@@ -246,6 +249,71 @@ fn test_empty_batch() {
     client.anchor_document_batch(&project_id, &empty_documents); // Should panic
 }
 
+#[test]
+fn test_data_version_and_migrate() {
+    let (env, _, client) = create_contract();
+    let admin = Address::generate(&env);
+
+    client.initialize(&admin);
+    assert_eq!(client.get_data_version(), 1);
+
+    // Already current, so migrating again is a no-op.
+    assert_eq!(client.migrate(), 1);
+    assert_eq!(client.get_data_version(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_upgrade_requires_admin_auth() {
+    let env = Env::default();
+    let contract_id = env.register(ProjectRegistry, ());
+    let client = ProjectRegistryClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    env.set_auths(&[]);
+    client.upgrade(&BytesN::from_array(&env, &[0u8; 32]));
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let (env, _contract_id, client) = create_contract();
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let expiration = env.ledger().sequence() + 10;
+    client.propose_admin(&new_admin, &expiration);
+    assert_eq!(
+        client.get_pending_admin().map(|p| p.new_admin),
+        Some(new_admin.clone())
+    );
+
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_accept_admin(&outsider),
+        Err(Ok(Error::NotProposedAdmin))
+    );
+
+    env.ledger().with_mut(|li| li.sequence_number = expiration + 1);
+    assert_eq!(
+        client.try_accept_admin(&new_admin),
+        Err(Ok(Error::AdminProposalExpired))
+    );
+
+    let expiration = env.ledger().sequence() + 10;
+    client.propose_admin(&new_admin, &expiration);
+    client.accept_admin(&new_admin);
+    assert_eq!(client.get_admin(), new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+    assert_eq!(
+        client.try_accept_admin(&new_admin),
+        Err(Ok(Error::NoPendingAdmin))
+    );
+}
+
 // ========== Validation Tests ==========
 
 #[test]
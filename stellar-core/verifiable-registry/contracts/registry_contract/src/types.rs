@@ -14,6 +14,16 @@ pub struct DocumentRecord {
     pub anchorer: Address,
 }
 
+/// Admin handover waiting for the proposed address to accept it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingAdmin {
+    /// Address that must call `accept_admin`
+    pub new_admin: Address,
+    /// Last ledger on which the proposal can be accepted
+    pub expiration_ledger: u32,
+}
+
 /// Contract error types
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -35,4 +45,10 @@ pub enum Error {
     EmptyBatch = 7,
     /// No projects found for anchorer
     NoProjectsFound = 8,
+    /// No admin handover has been proposed
+    NoPendingAdmin = 9,
+    /// Admin proposal is past its expiration ledger
+    AdminProposalExpired = 10,
+    /// Caller is not the proposed admin
+    NotProposedAdmin = 11,
 }
@@ -1,10 +1,14 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractevent, contractimpl, contracttype, symbol_short,
-    token::Client as TokenClient, vec, Address, Env, Map, Vec,
+    contract, contracterror, contractevent, contractimpl, contracttype, symbol_short, vec, Address,
+    BytesN, Env, IntoVal, Map, Symbol, Vec,
 };
 
+/// Storage layout version written by this build. Bump together with a step in
+/// `migrate` whenever the layout changes.
+const DATA_VERSION: u32 = 1;
+
 // ---------------------------------------------------------------------------
 // Errors
 // ---------------------------------------------------------------------------
@@ -21,6 +25,8 @@ pub enum TimeLockError {
     InvalidUnlockTime = 6,
     VintageValidationFailed = 7,
     EmptyBatch = 8,
+    NoPendingAdmin = 9,
+    AdminProposalExpired = 10,
 }
 
 // ---------------------------------------------------------------------------
@@ -36,6 +42,14 @@ pub struct LockRecord {
     pub deposited_at: u64,
 }
 
+/// Admin handover waiting for the proposed address to accept it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingAdmin {
+    pub new_admin: Address,
+    pub expiration_ledger: u32,
+}
+
 // ---------------------------------------------------------------------------
 // Storage keys
 // ---------------------------------------------------------------------------
@@ -47,6 +61,8 @@ enum DataKey {
     ValidateVintage,
     VintageCheckContract,
     LockRecords,
+    DataVersion,
+    PendingAdmin,
 }
 
 // ---------------------------------------------------------------------------
@@ -78,6 +94,31 @@ pub struct ForceRelease {
     pub released_at: u64,
 }
 
+#[contractevent]
+pub struct AdminProposed {
+    pub current_admin: Address,
+    pub proposed_admin: Address,
+    pub expiration_ledger: u32,
+}
+
+#[contractevent]
+pub struct AdminTransferred {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent]
+pub struct ContractUpgraded {
+    pub new_wasm_hash: BytesN<32>,
+    pub upgraded_by: Address,
+}
+
+#[contractevent]
+pub struct DataMigrated {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
 // ---------------------------------------------------------------------------
 // Event emitter helpers
 // ---------------------------------------------------------------------------
@@ -169,16 +210,24 @@ impl TimeLock {
         env.storage()
             .persistent()
             .set(&DataKey::LockRecords, &records);
+        env.storage()
+            .instance()
+            .set(&DataKey::DataVersion, &DATA_VERSION);
     }
 
-    pub fn version(_env: Env) -> u32 {
-        1
+    /// Stored data layout version; 0 for deployments that predate versioning.
+    pub fn get_data_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::DataVersion)
+            .unwrap_or(0)
     }
 
     /// Lock a CarbonAsset token in this contract's custody.
     ///
-    /// The caller must have previously approved this contract as an operator on
-    /// the CarbonAsset contract (or must be the owner calling `transfer_from`).
+    /// The caller must first approve this contract for `token_id` on the
+    /// CarbonAsset contract (`approve_token`), or as an operator for all of
+    /// their credits (`set_approval_for_all`). Exactly that token is moved.
     ///
     /// # Arguments
     /// * `caller`            – Address locking the credit (pays fees, must auth).
@@ -221,13 +270,12 @@ impl TimeLock {
             .get(&DataKey::CarbonAssetContract)
             .ok_or(TimeLockError::NotInitialized)?;
 
-        let token_client = TokenClient::new(&env, &ca_contract);
-
-        token_client.transfer_from(
-            &env.current_contract_address(),
+        Self::move_credit(
+            &env,
+            &ca_contract,
             &caller,
             &env.current_contract_address(),
-            &(token_id as i128),
+            token_id,
         );
 
         let record = LockRecord {
@@ -266,11 +314,12 @@ impl TimeLock {
             .get(&DataKey::CarbonAssetContract)
             .ok_or(TimeLockError::NotInitialized)?;
 
-        let token_client = TokenClient::new(&env, &ca_contract);
-        token_client.transfer(
+        Self::move_credit(
+            &env,
+            &ca_contract,
             &env.current_contract_address(),
             &record.owner,
-            &(token_id as i128),
+            token_id,
         );
 
         records.remove(token_id);
@@ -300,17 +349,18 @@ impl TimeLock {
             .instance()
             .get(&DataKey::CarbonAssetContract)
             .ok_or(TimeLockError::NotInitialized)?;
-        let token_client = TokenClient::new(&env, &ca_contract);
 
         let mut released: Vec<u32> = vec![&env];
 
         for token_id in token_ids.iter() {
             if let Some(record) = records.get(token_id) {
                 if now >= record.unlock_timestamp {
-                    token_client.transfer(
+                    Self::move_credit(
+                        &env,
+                        &ca_contract,
                         &env.current_contract_address(),
                         &record.owner,
-                        &(token_id as i128),
+                        token_id,
                     );
 
                     emit_credit_released(
@@ -330,7 +380,6 @@ impl TimeLock {
     }
 
     /// Admin-only override to release a token before its unlock time.
-
     pub fn force_release(env: Env, token_id: u32) -> Result<(), TimeLockError> {
         Self::require_initialized(&env)?;
 
@@ -350,11 +399,12 @@ impl TimeLock {
             .get(&DataKey::CarbonAssetContract)
             .ok_or(TimeLockError::NotInitialized)?;
 
-        let token_client = TokenClient::new(&env, &ca_contract);
-        token_client.transfer(
+        Self::move_credit(
+            &env,
+            &ca_contract,
             &env.current_contract_address(),
             &record.owner,
-            &(token_id as i128),
+            token_id,
         );
 
         records.remove(token_id);
@@ -400,7 +450,13 @@ impl TimeLock {
     // Admin helpers
     // -----------------------------------------------------------------------
 
-    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), TimeLockError> {
+    /// Propose a new admin. The handover only takes effect once `new_admin`
+    /// calls `accept_admin` on or before `expiration_ledger`.
+    pub fn propose_admin(
+        env: Env,
+        new_admin: Address,
+        expiration_ledger: u32,
+    ) -> Result<(), TimeLockError> {
         Self::require_initialized(&env)?;
         let current_admin: Address = env
             .storage()
@@ -408,10 +464,112 @@ impl TimeLock {
             .get(&DataKey::Admin)
             .ok_or(TimeLockError::NotInitialized)?;
         current_admin.require_auth();
+
+        if expiration_ledger < env.ledger().sequence() {
+            return Err(TimeLockError::AdminProposalExpired);
+        }
+
+        env.storage().instance().set(
+            &DataKey::PendingAdmin,
+            &PendingAdmin {
+                new_admin: new_admin.clone(),
+                expiration_ledger,
+            },
+        );
+
+        AdminProposed {
+            current_admin,
+            proposed_admin: new_admin,
+            expiration_ledger,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Complete a pending admin handover. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), TimeLockError> {
+        Self::require_initialized(&env)?;
+        new_admin.require_auth();
+
+        let pending: PendingAdmin = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .ok_or(TimeLockError::NoPendingAdmin)?;
+        if pending.new_admin != new_admin {
+            return Err(TimeLockError::Unauthorized);
+        }
+        if env.ledger().sequence() > pending.expiration_ledger {
+            return Err(TimeLockError::AdminProposalExpired);
+        }
+
+        let previous_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(TimeLockError::NotInitialized)?;
         env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        AdminTransferred {
+            previous_admin,
+            new_admin,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn get_pending_admin(env: Env) -> Option<PendingAdmin> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    /// Replace the contract WASM in place. Locked credits, their records and
+    /// the contract address all survive the upgrade.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), TimeLockError> {
+        Self::require_initialized(&env)?;
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(TimeLockError::NotInitialized)?;
+        admin.require_auth();
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        ContractUpgraded {
+            new_wasm_hash,
+            upgraded_by: admin,
+        }
+        .publish(&env);
         Ok(())
     }
 
+    /// Bring stored data up to `DATA_VERSION` after an upgrade. Returns the
+    /// resulting version; a no-op when already current.
+    pub fn migrate(env: Env) -> Result<u32, TimeLockError> {
+        Self::require_initialized(&env)?;
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(TimeLockError::NotInitialized)?;
+        admin.require_auth();
+
+        let from_version = Self::get_data_version(env.clone());
+        if from_version < DATA_VERSION {
+            env.storage()
+                .instance()
+                .set(&DataKey::DataVersion, &DATA_VERSION);
+
+            DataMigrated {
+                from_version,
+                to_version: DATA_VERSION,
+            }
+            .publish(&env);
+        }
+        Ok(Self::get_data_version(env))
+    }
+
     pub fn set_validate_vintage(
         env: Env,
         validate: bool,
@@ -458,6 +616,24 @@ impl TimeLock {
             .set(&DataKey::LockRecords, records);
     }
 
+    /// Move one specific CarbonAsset token with `transfer_token_from`, acting
+    /// as spender. Credits are non-fungible, so the amount-based SEP-41
+    /// transfer would pick whichever of the holder's tokens comes first rather
+    /// than `token_id`.
+    fn move_credit(env: &Env, ca_contract: &Address, from: &Address, to: &Address, token_id: u32) {
+        env.invoke_contract::<()>(
+            ca_contract,
+            &Symbol::new(env, "transfer_token_from"),
+            soroban_sdk::vec![
+                env,
+                env.current_contract_address().into_val(env),
+                from.into_val(env),
+                to.into_val(env),
+                token_id.into_val(env),
+            ],
+        );
+    }
+
     /// Validate that `unlock_timestamp` is consistent with the credit's vintage
     /// year stored in the CarbonAsset metadata contract.
     ///
@@ -502,3 +678,6 @@ impl TimeLock {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,304 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::testutils::{Address as _, Events as _, Ledger, MockAuth, MockAuthInvoke};
+use soroban_sdk::{contract, contractimpl, Bytes, TryFromVal, Val};
+
+#[contracttype]
+enum MockKey {
+    NextTokenId,
+    Owner(u32),
+    Approved(u32),
+    Operator(Address, Address),
+}
+
+// CarbonAsset's per-token transfer surface: a spender moves a token when it
+// is the owner, the token's approved spender or one of the owner's operators.
+#[contract]
+struct MockCarbonAsset;
+
+#[contractimpl]
+impl MockCarbonAsset {
+    pub fn mint(env: Env, owner: Address) -> u32 {
+        let token_id: u32 = env
+            .storage()
+            .instance()
+            .get(&MockKey::NextTokenId)
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&MockKey::NextTokenId, &(token_id + 1));
+        env.storage()
+            .persistent()
+            .set(&MockKey::Owner(token_id), &owner);
+        token_id
+    }
+
+    pub fn owner_of(env: Env, token_id: u32) -> Address {
+        env.storage()
+            .persistent()
+            .get(&MockKey::Owner(token_id))
+            .unwrap()
+    }
+
+    pub fn approve_token(env: Env, token_id: u32, spender: Address) {
+        Self::owner_of(env.clone(), token_id).require_auth();
+        env.storage()
+            .persistent()
+            .set(&MockKey::Approved(token_id), &spender);
+    }
+
+    pub fn set_approval_for_all(env: Env, owner: Address, operator: Address, approved: bool) {
+        owner.require_auth();
+        env.storage()
+            .persistent()
+            .set(&MockKey::Operator(owner, operator), &approved);
+    }
+
+    pub fn transfer_token_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        token_id: u32,
+    ) {
+        spender.require_auth();
+        let owner = Self::owner_of(env.clone(), token_id);
+        assert_eq!(owner, from, "not owner");
+
+        let storage = env.storage().persistent();
+        let approved: Option<Address> = storage.get(&MockKey::Approved(token_id));
+        let operator: bool = storage
+            .get(&MockKey::Operator(owner.clone(), spender.clone()))
+            .unwrap_or(false);
+        assert!(
+            spender == owner || approved == Some(spender) || operator,
+            "not approved"
+        );
+
+        storage.remove(&MockKey::Approved(token_id));
+        storage.set(&MockKey::Owner(token_id), &to);
+    }
+}
+
+fn setup() -> (Env, TimeLockClient<'static>, Address, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let carbon_asset = env.register(MockCarbonAsset, ());
+    let contract_id = env.register(TimeLock, ());
+    let client = TimeLockClient::new(&env, &contract_id);
+    client.initialize(&admin, &carbon_asset, &false, &None);
+    (env, client, admin, carbon_asset)
+}
+
+// Smallest module the host accepts as contract code: the wasm header and a
+// `contractenvmetav0` section declaring protocol 23. It exports nothing, so
+// it only stands in as an upgrade target.
+fn upload_empty_wasm(env: &Env) -> BytesN<32> {
+    let mut wasm = [0u8; 40];
+    wasm[..10].copy_from_slice(&[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x00, 0x1e]);
+    wasm[10] = 17;
+    wasm[11..28].copy_from_slice(b"contractenvmetav0");
+    wasm[35] = 23;
+    env.deployer()
+        .upload_contract_wasm(Bytes::from_slice(env, &wasm))
+}
+
+// Fields of the `name` event the contract published in the last call, if any.
+fn published(env: &Env, contract: &Address, name: &str) -> Option<Map<Symbol, Val>> {
+    let topic = Symbol::new(env, name);
+    env.events()
+        .all()
+        .iter()
+        .find_map(|(emitter, topics, data)| {
+            let event = Symbol::try_from_val(env, &topics.get(0)?).ok()?;
+            if emitter != *contract || event != topic {
+                return None;
+            }
+            Map::try_from_val(env, &data).ok()
+        })
+}
+
+fn field<T: TryFromVal<Env, Val>>(env: &Env, fields: &Map<Symbol, Val>, name: &str) -> T {
+    T::try_from_val(env, &fields.get(Symbol::new(env, name)).unwrap()).unwrap()
+}
+
+#[test]
+fn test_admin_handover_is_two_step() {
+    let (env, client, admin, _) = setup();
+    let new_admin = Address::generate(&env);
+    let typo = Address::generate(&env);
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+
+    assert_eq!(
+        client.try_accept_admin(&new_admin),
+        Err(Ok(TimeLockError::NoPendingAdmin))
+    );
+    assert_eq!(
+        client.try_propose_admin(&new_admin, &(env.ledger().sequence() - 1)),
+        Err(Ok(TimeLockError::AdminProposalExpired))
+    );
+
+    let expiration = env.ledger().sequence() + 100;
+    client.propose_admin(&typo, &expiration);
+    assert_eq!(env.auths()[0].0, admin);
+
+    // A wrong proposal can be replaced before anyone accepts it.
+    client.propose_admin(&new_admin, &expiration);
+    assert_eq!(
+        client.try_accept_admin(&typo),
+        Err(Ok(TimeLockError::Unauthorized))
+    );
+
+    env.ledger()
+        .with_mut(|li| li.sequence_number = expiration + 1);
+    assert_eq!(
+        client.try_accept_admin(&new_admin),
+        Err(Ok(TimeLockError::AdminProposalExpired))
+    );
+
+    let expiration = env.ledger().sequence() + 100;
+    client.propose_admin(&new_admin, &expiration);
+    assert_eq!(
+        client.get_pending_admin(),
+        Some(PendingAdmin {
+            new_admin: new_admin.clone(),
+            expiration_ledger: expiration,
+        })
+    );
+    client.accept_admin(&new_admin);
+    assert_eq!(env.auths()[0].0, new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+
+    // Admin-only calls now need the new admin's signature.
+    client.migrate();
+    assert_eq!(env.auths()[0].0, new_admin);
+}
+
+#[test]
+fn test_migrate_brings_unversioned_data_current() {
+    let (env, client, admin, _) = setup();
+    assert_eq!(client.get_data_version(), 1);
+
+    env.as_contract(&client.address, || {
+        env.storage().instance().remove(&DataKey::DataVersion);
+    });
+    assert_eq!(client.get_data_version(), 0);
+
+    assert_eq!(client.migrate(), 1);
+    assert_eq!(env.auths()[0].0, admin);
+    let migrated = published(&env, &client.address, "data_migrated").unwrap();
+    assert_eq!(field::<u32>(&env, &migrated, "from_version"), 0);
+    assert_eq!(field::<u32>(&env, &migrated, "to_version"), 1);
+    assert_eq!(client.get_data_version(), 1);
+
+    // Already current: nothing to report.
+    assert_eq!(client.migrate(), 1);
+    assert!(published(&env, &client.address, "data_migrated").is_none());
+}
+
+#[test]
+fn test_upgrade_requires_admin() {
+    let (env, client, admin, _) = setup();
+    let wasm_hash = upload_empty_wasm(&env);
+
+    let outsider = Address::generate(&env);
+    env.mock_auths(&[MockAuth {
+        address: &outsider,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "upgrade",
+            args: (wasm_hash.clone(),).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client.try_upgrade(&wasm_hash).is_err());
+
+    env.mock_all_auths();
+    client.upgrade(&wasm_hash);
+    let upgraded = published(&env, &client.address, "contract_upgraded").unwrap();
+    assert_eq!(
+        field::<BytesN<32>>(&env, &upgraded, "new_wasm_hash"),
+        wasm_hash
+    );
+    assert_eq!(field::<Address>(&env, &upgraded, "upgraded_by"), admin);
+    assert_eq!(env.auths()[0].0, admin);
+
+    // The replacement code exports nothing, so the old entry points are gone.
+    assert!(client.try_get_data_version().is_err());
+}
+
+#[test]
+fn test_lock_and_release_with_token_approval() {
+    let (env, client, _, carbon_asset) = setup();
+    let assets = MockCarbonAssetClient::new(&env, &carbon_asset);
+    let owner = Address::generate(&env);
+    let kept = assets.mint(&owner);
+    let locked = assets.mint(&owner);
+    let unlock_at = env.ledger().timestamp() + 1_000;
+
+    // Without an approval the CarbonAsset contract refuses the move.
+    assert!(client.try_lock_credit(&owner, &locked, &unlock_at).is_err());
+    assert_eq!(client.get_lock_status(&locked), None);
+
+    assets.approve_token(&locked, &client.address);
+    client.lock_credit(&owner, &locked, &unlock_at);
+    assert_eq!(assets.owner_of(&locked), client.address);
+    assert_eq!(assets.owner_of(&kept), owner);
+    assert_eq!(
+        client.get_lock_status(&locked).unwrap().unlock_timestamp,
+        unlock_at
+    );
+    assert_eq!(
+        client.try_lock_credit(&owner, &locked, &unlock_at),
+        Err(Ok(TimeLockError::AlreadyLocked))
+    );
+
+    assert_eq!(
+        client.try_release_if_eligible(&locked),
+        Err(Ok(TimeLockError::LockNotExpired))
+    );
+    env.ledger().with_mut(|li| li.timestamp = unlock_at);
+    client.release_if_eligible(&locked);
+    assert_eq!(assets.owner_of(&locked), owner);
+    assert_eq!(client.get_lock_status(&locked), None);
+    assert_eq!(
+        client.try_release_if_eligible(&locked),
+        Err(Ok(TimeLockError::NotLocked))
+    );
+
+    // The per-token approval was used up by the lock.
+    let later = unlock_at + 1_000;
+    assert!(client.try_lock_credit(&owner, &locked, &later).is_err());
+}
+
+#[test]
+fn test_operator_approval_covers_every_credit() {
+    let (env, client, _, carbon_asset) = setup();
+    let assets = MockCarbonAssetClient::new(&env, &carbon_asset);
+    let owner = Address::generate(&env);
+    let first = assets.mint(&owner);
+    let second = assets.mint(&owner);
+    let third = assets.mint(&owner);
+    let unlock_at = env.ledger().timestamp() + 1_000;
+
+    assets.set_approval_for_all(&owner, &client.address, &true);
+    client.lock_credit(&owner, &first, &unlock_at);
+    client.lock_credit(&owner, &second, &(unlock_at * 2));
+    client.lock_credit(&owner, &third, &unlock_at);
+    assert_eq!(client.get_all_locked().len(), 3);
+
+    // Only expired locks are released; the rest are skipped.
+    env.ledger().with_mut(|li| li.timestamp = unlock_at);
+    let released = client.batch_release(&Vec::from_array(&env, [first, second, third]));
+    assert_eq!(released, Vec::from_array(&env, [first, third]));
+    assert_eq!(assets.owner_of(&first), owner);
+    assert_eq!(assets.owner_of(&third), owner);
+    assert_eq!(assets.owner_of(&second), client.address);
+
+    // The admin can hand a credit back early.
+    client.force_release(&second);
+    assert_eq!(assets.owner_of(&second), owner);
+    assert!(client.get_all_locked().is_empty());
+}